@@ -0,0 +1,125 @@
+use std::{fmt, io, path::PathBuf};
+
+use color_eyre::{Report, Section};
+use itertools::Itertools;
+use semver::{Version, VersionReq};
+
+use crate::models::package::{PACKAGE_FILE_NAME, SHARED_PACKAGE_FILE_NAME};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Failures of the [`crate::api`] functions callers may want to handle
+#[derive(Debug)]
+pub enum Error {
+    /// The workspace has no `qpm.json`
+    MissingPackage(PathBuf),
+
+    /// The workspace has no `qpm.shared.json`
+    MissingLock(PathBuf),
+
+    /// A frozen restore would have to write the lock file, the reason is attached
+    LockOutOfDate(String),
+
+    /// A frozen restore would have to download these dependencies
+    DownloadRequired(Vec<(String, Version)>),
+
+    /// The package does not set `workspace.ndk`
+    NoNdkRequirement,
+
+    /// No installed NDK satisfies the requirement and downloading was not allowed
+    NdkNotInstalled {
+        requirement: VersionReq,
+        /// Newest version available for download that would satisfy it
+        suggested_version: Option<String>,
+    },
+
+    /// The NDK in use does not satisfy the requirement of the package
+    NdkMismatch {
+        version: Version,
+        requirement: VersionReq,
+    },
+
+    /// A workspace script exited with a non-zero code
+    ScriptFailed { script: String, code: i32 },
+
+    /// Anything else, e.g. network or filesystem errors
+    Other(Report),
+}
+
+impl Error {
+    /// Converts into a report with the suggestions `qpm` prints for it
+    pub fn into_report(self) -> Report {
+        match self {
+            Error::Other(report) => report,
+            Error::MissingLock(_) => Report::new(self).suggestion("qpm restore"),
+            Error::NdkMismatch { .. } => Report::new(self).suggestion("qpm ndk resolve"),
+            Error::NdkNotInstalled {
+                suggested_version: Some(ref version),
+                ..
+            } => {
+                let suggestion = format!("qpm ndk download {version}");
+                Report::new(self).suggestion(suggestion)
+            }
+            _ => Report::new(self),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingPackage(workspace) => {
+                write!(f, "No {PACKAGE_FILE_NAME} found in {}", workspace.display())
+            }
+            Error::MissingLock(workspace) => {
+                write!(
+                    f,
+                    "No {SHARED_PACKAGE_FILE_NAME} found in {}",
+                    workspace.display()
+                )
+            }
+            Error::LockOutOfDate(reason) => write!(f, "{reason}"),
+            Error::DownloadRequired(missing) => write!(
+                f,
+                "Dependencies are missing from the cache: {}",
+                missing
+                    .iter()
+                    .map(|(id, version)| format!("{id}:{version}"))
+                    .join(", ")
+            ),
+            Error::NoNdkRequirement => write!(f, "No NDK requirement set in project"),
+            Error::NdkNotInstalled { requirement, .. } => {
+                write!(f, "No NDK version installed that satisfies {requirement}")
+            }
+            Error::NdkMismatch {
+                version,
+                requirement,
+            } => write!(f, "NDK Version {version} does not satisfy {requirement}"),
+            Error::ScriptFailed { script, code } => {
+                write!(f, "Script {script} failed with exit code {code}")
+            }
+            Error::Other(report) => write!(f, "{report}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Other(report) => report.chain().nth(1),
+            _ => None,
+        }
+    }
+}
+
+impl From<Report> for Error {
+    fn from(report: Report) -> Self {
+        Error::Other(report)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Other(error.into())
+    }
+}
@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::Context;
+use itertools::Itertools;
+use qpm_package::{
+    extensions::package_metadata::PackageMetadataExtensions,
+    models::{dependency::SharedPackageConfig, package::PackageConfig},
+};
+use semver::Version;
+
+use crate::{
+    models::package::{
+        PackageConfigExtensions, SharedPackageConfigExtensions, StaticLibraryExtensions,
+    },
+    repository::{self, local::FileRepository},
+    resolver::dependency,
+};
+
+use super::{Error, Result};
+
+/// Options of [`install`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstallOptions {
+    /// Release binary to install, relative to the workspace.
+    /// Defaults to `build/<so name>` for CMake builds, `build/<archive name>` for static libraries
+    pub binary_path: Option<PathBuf>,
+
+    /// Debug binary to install, relative to the workspace.
+    /// Defaults to `build/debug/<so name>` for CMake builds of shared libraries
+    pub debug_binary_path: Option<PathBuf>,
+
+    /// Whether the binaries are built by CMake into `build/`, `None` means they are
+    pub cmake_build: Option<bool>,
+
+    /// Only use the local cache when resolving
+    pub offline: bool,
+
+    /// Resolve anew and write the lock file instead of installing from it
+    pub update: bool,
+}
+
+/// What [`install`] put into the local file repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallResult {
+    pub id: String,
+    pub version: Version,
+    pub binary_path: Option<PathBuf>,
+    pub debug_binary_path: Option<PathBuf>,
+}
+
+/// Installs the package in `workspace` and its binaries into the local file repository,
+/// making it available to other packages on this machine
+pub fn install(workspace: &Path, options: &InstallOptions) -> Result<InstallResult> {
+    log::info!("Publishing package to local file repository");
+
+    if !PackageConfig::exists(workspace) {
+        return Err(Error::MissingPackage(workspace.to_path_buf()));
+    }
+    if !options.update && !SharedPackageConfig::exists(workspace) {
+        return Err(Error::MissingLock(workspace.to_path_buf()));
+    }
+
+    let package = PackageConfig::read(workspace)?;
+    let repo = repository::useful_default_new(options.offline)?;
+    let shared_package = match !options.update {
+        true => SharedPackageConfig::read(workspace)?,
+        false => SharedPackageConfig::resolve_from_package(package, &repo)?.0,
+    };
+
+    if options.update {
+        log::info!("Not using lock file, updating dependencies and writing!");
+        shared_package.write(workspace)?;
+    } else {
+        log::info!("Using lock file");
+    }
+
+    let mut binary_path = options.binary_path.as_ref().map(|p| workspace.join(p));
+    let mut debug_binary_path = options
+        .debug_binary_path
+        .as_ref()
+        .map(|p| workspace.join(p));
+
+    let header_only = shared_package
+        .config
+        .info
+        .additional_data
+        .headers_only
+        .unwrap_or(false);
+    log::debug!("Header only: {header_only}");
+
+    let static_linking = shared_package.config.info.is_static_linking();
+    log::debug!("Static linking: {static_linking}");
+
+    if !header_only && options.cmake_build.unwrap_or(true) {
+        // static libraries are built as an archive and have no stripped debug variant
+        let so_name = match static_linking {
+            true => shared_package.config.info.get_static_name(),
+            false => shared_package.config.info.get_so_name(),
+        };
+        let so_file_name = so_name.file_name().unwrap_or_default();
+
+        if binary_path.is_none() {
+            binary_path = Some(
+                workspace
+                    .join("build")
+                    .join(so_file_name)
+                    .canonicalize()
+                    .context("Failed to retrieve release binary for publishing since it is not header only")?,
+            );
+        }
+
+        if debug_binary_path.is_none() && !static_linking {
+            debug_binary_path = Some(
+                workspace
+                    .join("build/debug")
+                    .join(so_file_name)
+                    .canonicalize()
+                    .context("Failed to retrieve debug binary for publishing since it is not header only")?,
+            );
+        }
+    }
+
+    if let Some(p) = &debug_binary_path
+        && !p.exists()
+    {
+        log::warn!("Could not find debug binary {p:?}, skipping")
+    }
+
+    if let Some(p) = &binary_path
+        && !p.exists()
+    {
+        log::warn!("Could not find binary {p:?}, skipping")
+    }
+
+    let id = shared_package.config.info.id.clone();
+    let version = shared_package.config.info.version.clone();
+
+    // the libraries the binaries need must come from the locked dependencies
+    let resolved_deps = dependency::locked_resolve(&shared_package, &repo)?.collect_vec();
+
+    let mut file_repo = FileRepository::read()?;
+    file_repo.add_artifact_and_cache(
+        shared_package,
+        workspace.canonicalize()?,
+        binary_path.clone(),
+        debug_binary_path.clone(),
+        &resolved_deps,
+        true,
+        true,
+    )?;
+    file_repo.write()?;
+
+    Ok(InstallResult {
+        id,
+        version,
+        binary_path,
+        debug_binary_path,
+    })
+}
@@ -0,0 +1,31 @@
+//! Embeddable entry points for the flows behind `qpm restore`, `qpm install`, `qpm qmod zip`
+//! and `qpm ndk resolve`, available without the `cli` feature.
+//!
+//! Every function works on the workspace it is given, the directory holding `qpm.json`,
+//! instead of the current directory. Nothing is printed to stdout, progress is reported
+//! through the `log` crate and failures are returned as [`Error`].
+//!
+//! ```no_run
+//! use std::path::Path;
+//!
+//! use qpm_cli::api::{self, RestoreOptions};
+//!
+//! let restored = api::restore(Path::new("my-mod"), &RestoreOptions::default())?;
+//! println!("Restored {} dependencies", restored.resolved.len());
+//! # Ok::<(), api::Error>(())
+//! ```
+
+mod error;
+mod install;
+mod ndk;
+mod qmod;
+mod restore;
+
+pub use error::{Error, Result};
+pub use install::{InstallOptions, InstallResult, install};
+pub use ndk::{NdkResolveOptions, resolve_ndk, validate_ndk, write_ndk_path};
+pub use qmod::{
+    QmodManifestOptions, QmodZipOptions, QmodZipResult, generate_qmod_manifest, qmod_manifest,
+    qmod_zip,
+};
+pub use restore::{RestoreOptions, RestoreResult, restore, restore_with_repository};
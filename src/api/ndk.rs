@@ -0,0 +1,128 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{Context, eyre};
+use qpm_package::models::package::PackageConfig;
+use semver::Version;
+
+use crate::{
+    models::package::PackageConfigExtensions,
+    utils::{
+        android::{download_ndk_version, get_android_manifest, range_match_ndk},
+        ndk,
+    },
+};
+
+use super::{Error, Result};
+
+/// File in the workspace the NDK path is written to for CMake
+const NDK_PATH_FILE: &str = "ndkpath.txt";
+
+/// Options of [`resolve_ndk`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NdkResolveOptions {
+    /// Download the newest matching NDK if none is installed
+    pub download: bool,
+
+    /// Show a progress bar while downloading
+    pub show_progress: bool,
+}
+
+/// Finds the newest installed NDK satisfying the requirement of the package in `workspace`,
+/// downloading one if allowed, and writes its path to `ndkpath.txt`
+pub fn resolve_ndk(workspace: &Path, options: &NdkResolveOptions) -> Result<PathBuf> {
+    if !PackageConfig::exists(workspace) {
+        return Err(Error::MissingPackage(workspace.to_path_buf()));
+    }
+    let package = PackageConfig::read(workspace)?;
+
+    let Some(ndk_requirement) = package.workspace.ndk.clone() else {
+        return Err(Error::NoNdkRequirement);
+    };
+
+    let ndk_installed_path = match ndk::resolve_ndk_version(&package) {
+        Some(ndk_installed_path) => ndk_installed_path,
+        None if options.download => {
+            let manifest = get_android_manifest()?;
+            let (_version, ndk) = range_match_ndk(&manifest, &ndk_requirement)?;
+
+            download_ndk_version(ndk, options.show_progress)?
+        }
+        None => {
+            // look up a version suitable to work with
+            // allow this to work offline by handling safely
+            let suggested_version = get_android_manifest().ok().and_then(|manifest| {
+                range_match_ndk(&manifest, &ndk_requirement)
+                    .ok()
+                    .map(|(version, _)| version)
+            });
+
+            return Err(Error::NdkNotInstalled {
+                requirement: ndk_requirement,
+                suggested_version,
+            });
+        }
+    };
+
+    write_ndk_path(workspace, &ndk_installed_path)?;
+    Ok(ndk_installed_path)
+}
+
+/// Points the workspace at the NDK installed in `ndk_path`
+pub fn write_ndk_path(workspace: &Path, ndk_path: &Path) -> Result<()> {
+    fs::write(
+        workspace.join(NDK_PATH_FILE),
+        ndk_path.to_string_lossy().as_bytes(),
+    )
+    .with_context(|| format!("Unable to write {NDK_PATH_FILE}"))?;
+    Ok(())
+}
+
+/// Checks that the NDK in `ndkpath.txt`, or the environment, satisfies the requirement of `package`
+pub fn validate_ndk(workspace: &Path, package: &PackageConfig) -> Result<()> {
+    let Some(ndk_req) = package.workspace.ndk.as_ref() else {
+        return Ok(());
+    };
+
+    let ndk_path_file = workspace.join(NDK_PATH_FILE);
+    let ndk_path_str = if ndk_path_file.exists() {
+        fs::read_to_string(&ndk_path_file)?
+    } else if let Some(ndk_path_env) =
+        env::var_os("ANDROID_NDK_HOME").or_else(|| env::var_os("ANDROID_NDK_LATEST_HOME"))
+    {
+        // validate environment variable if possible
+        ndk_path_env.to_string_lossy().to_string()
+    } else {
+        String::new()
+    };
+
+    let ndk_path = Path::new(ndk_path_str.trim());
+    if ndk_path.is_empty() {
+        log::warn!("NDK Path is empty, skipping validate NDK version!");
+        return Ok(());
+    }
+
+    if !ndk_path.exists() {
+        return Err(eyre!("NDK Path {} does not exist!", ndk_path.display()).into());
+    }
+
+    let ndk_version_str = ndk_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let Ok(ndk_version) = Version::parse(&ndk_version_str) else {
+        log::info!("Unable to validate {ndk_version_str} is a valid NDK version, skipping");
+        return Ok(());
+    };
+
+    if !ndk_req.matches(&ndk_version) {
+        return Err(Error::NdkMismatch {
+            version: ndk_version,
+            requirement: ndk_req.clone(),
+        });
+    }
+
+    Ok(())
+}
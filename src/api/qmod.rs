@@ -0,0 +1,366 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{Context, eyre};
+use itertools::Itertools;
+use owo_colors::OwoColorize;
+use qpm_package::{
+    extensions::{
+        package_metadata::PackageMetadataExtensions, workspace::WorkspaceConfigExtensions,
+    },
+    models::{dependency::SharedPackageConfig, package::PackageConfig},
+};
+use qpm_qmod::models::mod_json::ModJson;
+use semver::VersionReq;
+
+use crate::{
+    models::{
+        mod_json::{ModJsonExtensions, PreProcessingData},
+        package::{PackageConfigExtensions, SharedPackageConfigExtensions},
+        schemas::{SchemaLinks, WithSchema},
+    },
+    terminal::colors::QPMColor,
+    utils::{
+        license::{self, PackageLicense, THIRD_PARTY_NOTICES_FILE},
+        scripts,
+    },
+};
+
+use super::{Error, Result};
+
+/// Options of [`qmod_manifest`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QmodManifestOptions {
+    /// Libraries to leave out of the mod and library files
+    pub exclude_libs: Option<Vec<String>>,
+
+    /// Only list these libraries as mod and library files, ignored if `exclude_libs` is set
+    pub include_libs: Option<Vec<String>>,
+}
+
+/// Options of [`qmod_zip`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QmodZipOptions {
+    pub manifest: QmodManifestOptions,
+
+    /// Directories to look for the files of the manifest in, relative to the workspace.
+    /// Defaults to `workspace.qmodIncludeDirs`
+    pub include_dirs: Option<Vec<PathBuf>>,
+
+    /// Files to add to the zip regardless of the manifest.
+    /// Defaults to `workspace.qmodIncludeFiles`
+    pub include_files: Option<Vec<PathBuf>>,
+
+    /// Use this manifest instead of generating one from the package
+    pub import: Option<PathBuf>,
+
+    /// Run the clean script before building
+    pub clean: bool,
+
+    /// Don't run the build script
+    pub skip_build: bool,
+
+    /// Bundle a `THIRD_PARTY_NOTICES.txt` with the licenses of every library in `libraryFiles`
+    pub notices: bool,
+
+    /// Where to write the qmod, the extension is replaced with `.qmod`.
+    /// Defaults to `workspace.qmodOutput` or the package id
+    pub out_target: Option<PathBuf>,
+}
+
+/// What [`qmod_zip`] wrote
+#[derive(Debug, Clone)]
+pub struct QmodZipResult {
+    /// Path of the written qmod
+    pub path: PathBuf,
+
+    /// Files added to the qmod besides the manifest and notices
+    pub files: Vec<PathBuf>,
+
+    pub manifest: ModJson,
+}
+
+/// Generates the `mod.json` of the package in `workspace` from its `mod.template.json` and lock file
+pub fn qmod_manifest(workspace: &Path, options: &QmodManifestOptions) -> Result<ModJson> {
+    let (package, shared_package) = read_workspace(workspace)?;
+    generate_qmod_manifest(workspace, &package, shared_package, options)
+}
+
+/// Generates a `mod.json` from the `mod.template.json` in `workspace`
+pub fn generate_qmod_manifest(
+    workspace: &Path,
+    package: &PackageConfig,
+    shared_package: SharedPackageConfig,
+    options: &QmodManifestOptions,
+) -> Result<ModJson> {
+    let template_path = workspace.join(ModJson::get_template_name());
+    if !template_path.exists() {
+        return Err(eyre!(
+            "No {} found in {}, set it up please :) Hint: use \"qmod create\"",
+            ModJson::get_template_name(),
+            workspace.display()
+        )
+        .into());
+    }
+    log::info!("Generating mod.json file from template using qpm.shared.json...");
+
+    let binary = shared_package
+        .config
+        .info
+        .get_so_name()
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string());
+
+    let preprocess_data = PreProcessingData {
+        version: shared_package.config.info.version.to_string(),
+        mod_id: shared_package.config.info.id.clone(),
+        mod_name: shared_package.config.info.name.clone(),
+        binary,
+    };
+    let mut existing_json = ModJson::read_and_preprocess(&template_path, preprocess_data)?;
+    let template_mod_json: ModJson = shared_package.to_mod_json();
+    let legacy_0_1_0 = package.matches_version(
+        &VersionReq::parse("^0.1.0").context("Unable to parse legacy version range")?,
+    );
+    existing_json = ModJson::merge_modjson(existing_json, template_mod_json, legacy_0_1_0);
+    if let Some(excluded) = &options.exclude_libs {
+        let exclude_filter = |lib_name: &String| -> bool {
+            // returning false means don't include
+            // don't include anything that is excluded
+            !excluded.iter().any(|s| lib_name == s)
+        };
+
+        existing_json.mod_files.retain(exclude_filter);
+        existing_json.library_files.retain(exclude_filter);
+        // whitelist libraries
+    } else if let Some(included) = &options.include_libs {
+        let include_filter = |lib_name: &String| -> bool {
+            // returning false means don't include
+            // only include anything that is specified included
+            included.iter().any(|s| lib_name == s)
+        };
+
+        existing_json.mod_files.retain(include_filter);
+        existing_json.library_files.retain(include_filter);
+    }
+    Ok(existing_json)
+}
+
+/// Builds the package in `workspace` and zips its binaries and manifest into a qmod
+pub fn qmod_zip(workspace: &Path, options: &QmodZipOptions) -> Result<QmodZipResult> {
+    let (package, shared_package) = read_workspace(workspace)?;
+
+    let manifest = match &options.import {
+        Some(import_path) => {
+            let import_path = workspace.join(import_path);
+            ModJson::read(&import_path)?
+        }
+        None => generate_qmod_manifest(
+            workspace,
+            &package,
+            shared_package.clone(),
+            &options.manifest,
+        )?,
+    };
+
+    if options.clean
+        && let Some(clean_script) = &package.workspace.get_clean()
+    {
+        log::info!("Running clean script");
+        run_script("clean", clean_script, &package, workspace)?;
+    }
+
+    if !options.skip_build
+        && let Some(build_script) = &package.workspace.get_build()
+    {
+        log::info!("Running build script");
+        run_script("build", build_script, &package, workspace)?;
+    }
+
+    let include_dirs = options
+        .include_dirs
+        .clone()
+        .unwrap_or_else(|| package.workspace.qmod_include_dirs.clone())
+        .into_iter()
+        .map(|dir| workspace.join(dir))
+        .collect_vec();
+
+    let include_files = options
+        .include_files
+        .clone()
+        .unwrap_or_else(|| package.workspace.qmod_include_files.clone());
+
+    let qmod_out = workspace.join(
+        options
+            .out_target
+            .clone()
+            .or_else(|| package.workspace.qmod_output.clone())
+            .unwrap_or_else(|| PathBuf::from(&package.info.id)),
+    );
+
+    let look_for_files = |s: &str| -> Result<PathBuf> {
+        include_dirs
+            .iter()
+            .map(|path| path.join(s))
+            .find(|path| path.exists())
+            .ok_or_else(|| {
+                eyre!(
+                    "No file found for {s} in directories {}",
+                    include_dirs.iter().map(|s| s.display()).join(";")
+                )
+                .into()
+            })
+    };
+
+    let manifest_files = manifest
+        .file_copies
+        .iter()
+        .map(|c| c.name.as_str())
+        .chain(manifest.late_mod_files.iter().map(String::as_str))
+        .chain(manifest.mod_files.iter().map(String::as_str))
+        .chain(manifest.library_files.iter().map(String::as_str))
+        .map(look_for_files)
+        .collect::<Result<Vec<_>>>()?;
+
+    let extra_files = include_files.iter().map(|p| workspace.join(p));
+    let cover_image = manifest.cover_image.as_ref().map(|p| workspace.join(p));
+
+    let files = manifest_files
+        .into_iter()
+        .chain(extra_files)
+        .chain(cover_image)
+        .map(|p| {
+            p.canonicalize()
+                .with_context(|| format!("Unable to find {}", p.display()))
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()?
+        .into_iter()
+        .unique()
+        .collect_vec();
+
+    let out_target_qmod = qmod_out.with_extension("qmod");
+
+    log::info!(
+        "Writing qmod zip {}",
+        out_target_qmod.to_string_lossy().file_path_color()
+    );
+    log::info!(
+        "Using files: \n{}",
+        files
+            .iter()
+            .map(|s| format!("\t{}", s.to_string_lossy().file_path_color()))
+            .join("\n")
+    );
+
+    write_zip(
+        &out_target_qmod,
+        &files,
+        &manifest,
+        &shared_package,
+        options.notices,
+    )
+    .with_context(|| format!("Unable to write {}", out_target_qmod.display()))?;
+
+    log::info!("Wrote zip file to {}", out_target_qmod.display().blue());
+
+    Ok(QmodZipResult {
+        path: out_target_qmod,
+        files,
+        manifest,
+    })
+}
+
+fn write_zip(
+    out_target_qmod: &Path,
+    files: &[PathBuf],
+    manifest: &ModJson,
+    shared_package: &SharedPackageConfig,
+    notices: bool,
+) -> color_eyre::Result<()> {
+    let mut zip_file = File::create(out_target_qmod)?;
+
+    let mut zip = zip::ZipWriter::new(&mut zip_file);
+
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .compression_level(Some(9));
+    for file in files {
+        log::info!("Adding file {}", file.to_string_lossy().green());
+
+        let contents = fs::read(file)?;
+
+        zip.start_file(file.file_name().unwrap().to_string_lossy(), options)?;
+        zip.write_all(contents.as_slice())?;
+    }
+
+    if notices {
+        let licenses = bundled_licenses(shared_package, &manifest.library_files)?;
+        license::warn_unknown_licenses(&licenses);
+
+        log::info!("Adding file {}", THIRD_PARTY_NOTICES_FILE.green());
+        zip.start_file(THIRD_PARTY_NOTICES_FILE, options)?;
+        zip.write_all(license::third_party_notices(&licenses)?.as_bytes())?;
+    }
+
+    zip.start_file(ModJson::get_result_name(), options)?;
+    serde_json::to_writer_pretty(
+        &mut zip,
+        &WithSchema {
+            schema: SchemaLinks::MOD_CONFIG,
+            value: manifest,
+        },
+    )?;
+    // Apply the changes you've made.
+    // Dropping the `ZipWriter` will have the same effect, but may silently fail
+    zip.finish()?;
+
+    Ok(())
+}
+
+fn read_workspace(workspace: &Path) -> Result<(PackageConfig, SharedPackageConfig)> {
+    if !PackageConfig::exists(workspace) {
+        return Err(Error::MissingPackage(workspace.to_path_buf()));
+    }
+    if !SharedPackageConfig::exists(workspace) {
+        return Err(Error::MissingLock(workspace.to_path_buf()));
+    }
+    Ok((
+        PackageConfig::read(workspace)?,
+        SharedPackageConfig::read(workspace)?,
+    ))
+}
+
+fn run_script(
+    name: &str,
+    script: &[String],
+    package: &PackageConfig,
+    workspace: &Path,
+) -> Result<()> {
+    match scripts::run_script(script, &[], package, workspace)? {
+        0 => Ok(()),
+        code => Err(Error::ScriptFailed {
+            script: name.to_string(),
+            code,
+        }),
+    }
+}
+
+/// Licenses of the restored dependencies whose binaries are listed in `library_files`
+fn bundled_licenses(
+    shared_package: &SharedPackageConfig,
+    library_files: &[String],
+) -> color_eyre::Result<Vec<PackageLicense>> {
+    shared_package
+        .restored_dependencies
+        .iter()
+        .filter(|dep| {
+            dep.get_so_name()
+                .to_str()
+                .is_some_and(|so_name| library_files.iter().any(|lib| lib == so_name))
+        })
+        .map(license::dependency_license)
+        .try_collect()
+}
@@ -0,0 +1,299 @@
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::ContextCompat;
+use itertools::Itertools;
+use qpm_package::models::{dependency::SharedPackageConfig, package::PackageConfig};
+
+use crate::{
+    models::package::{
+        PACKAGE_FILE_NAME, PackageConfigExtensions, SHARED_PACKAGE_FILE_NAME,
+        SharedPackageConfigExtensions,
+    },
+    repository::{
+        self, Repository,
+        local::{ExternChange, FileRepository},
+    },
+    resolver::dependency,
+    terminal::colors::QPMColor,
+};
+
+use super::{Error, Result, ndk};
+
+/// Options of [`restore`], the defaults restore from the lock file like `qpm restore`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestoreOptions {
+    /// Ignore the lock file and resolve the newest matching versions
+    pub update: bool,
+
+    /// Only use the local cache
+    pub offline: bool,
+
+    /// Fail with [`Error::LockOutOfDate`] or [`Error::DownloadRequired`]
+    /// instead of writing the lock file or downloading
+    pub frozen: bool,
+
+    /// Resolve and plan without downloading or writing anything
+    pub dry_run: bool,
+}
+
+/// What [`restore`] did, or would do in a dry run
+#[derive(Debug, Clone)]
+pub struct RestoreResult {
+    /// The lock file before restoring, `None` if there was none
+    pub original_lock: Option<SharedPackageConfig>,
+
+    /// The lock file after restoring
+    pub shared_package: SharedPackageConfig,
+
+    /// Every resolved dependency
+    pub resolved: Vec<SharedPackageConfig>,
+
+    /// Dependencies that were not in the cache and had to be downloaded
+    pub downloads: Vec<SharedPackageConfig>,
+
+    /// Whether the lock file changed
+    pub lock_changed: bool,
+
+    /// Changes a dry run would make to the dependencies directory.
+    /// `None` if this was not a dry run or downloads are needed to know them
+    pub extern_changes: Option<Vec<ExternChange>>,
+
+    /// Generated files a dry run would rewrite with other contents,
+    /// e.g. extern.cmake, qpm_defines.cmake and the toolchain file. Empty if this was not a dry run
+    pub generated_changes: Vec<PathBuf>,
+}
+
+impl RestoreResult {
+    /// Whether restoring changed, or would change, anything in the workspace
+    pub fn has_changes(&self) -> bool {
+        self.lock_changed
+            || !self.downloads.is_empty()
+            || !self.generated_changes.is_empty()
+            || self
+                .extern_changes
+                .as_ref()
+                .is_some_and(|changes| !changes.is_empty())
+    }
+}
+
+#[cfg(feature = "gitoxide")]
+fn is_ignored(workspace: &Path) -> bool {
+    gix::open(workspace).is_ok_and(|r| {
+        let Ok(index) = r.index() else { return false };
+
+        let excludes = r.excludes(&index, None, Default::default());
+
+        excludes.is_ok_and(|mut attribute| {
+            attribute
+                .at_path(
+                    SHARED_PACKAGE_FILE_NAME,
+                    Some(gix::index::entry::Mode::FILE),
+                )
+                .is_ok_and(|e| e.is_excluded())
+        })
+    })
+}
+
+#[cfg(not(feature = "gitoxide"))]
+fn is_ignored(_workspace: &Path) -> bool {
+    false
+}
+
+/// Whether `new` locks other dependencies than `old`.
+/// Only the dependency ranges and restored versions count, not the copy of qpm.json
+fn lock_differs(old: &SharedPackageConfig, new: &SharedPackageConfig) -> bool {
+    let locked = |lock: &SharedPackageConfig| {
+        let ranges = lock
+            .config
+            .dependencies
+            .iter()
+            .map(|d| (d.id.clone(), d.version_range.to_string()))
+            .collect::<BTreeSet<_>>();
+        let restored = lock
+            .restored_dependencies
+            .iter()
+            .map(|d| {
+                (
+                    d.dependency.id.clone(),
+                    d.dependency.version_range.to_string(),
+                    d.version.clone(),
+                )
+            })
+            .collect::<BTreeSet<_>>();
+        (ranges, restored)
+    };
+
+    locked(old) != locked(new)
+}
+
+/// Resolves the dependencies of the package in `workspace`, downloads them into the cache,
+/// copies them into its dependencies directory and writes the lock file
+pub fn restore(workspace: &Path, options: &RestoreOptions) -> Result<RestoreResult> {
+    let mut repo = repository::useful_default_new(options.offline)?;
+    restore_with_repository(workspace, options, &mut repo)
+}
+
+/// [`restore`] using `repo` instead of the default repositories, `options.offline` is ignored.
+/// Lets long running callers keep the metadata cached between restores
+pub fn restore_with_repository(
+    workspace: &Path,
+    options: &RestoreOptions,
+    repo: &mut impl Repository,
+) -> Result<RestoreResult> {
+    if !PackageConfig::exists(workspace) {
+        return Err(Error::MissingPackage(workspace.to_path_buf()));
+    }
+    let package = PackageConfig::read(workspace)?;
+    // optionally does not exist
+    let mut shared_package_opt = SharedPackageConfig::exists(workspace)
+        .then(|| SharedPackageConfig::read(workspace))
+        .transpose()?;
+
+    // only update if:
+    // manually
+    // no shared.qpm.json
+    // dependencies have been updated
+    let lock_out_of_sync = shared_package_opt
+        .as_ref()
+        .is_some_and(|shared_package| shared_package.config.dependencies != package.dependencies);
+    let unlocked = options.update || shared_package_opt.is_none() || lock_out_of_sync;
+
+    if options.frozen && shared_package_opt.is_none() {
+        return Err(Error::LockOutOfDate(format!(
+            "{SHARED_PACKAGE_FILE_NAME} does not exist"
+        )));
+    }
+    if options.frozen && lock_out_of_sync {
+        return Err(Error::LockOutOfDate(format!(
+            "{SHARED_PACKAGE_FILE_NAME} is out of sync with {PACKAGE_FILE_NAME}"
+        )));
+    }
+
+    if !unlocked && is_ignored(workspace) {
+        log::warn!("It seems that the current repository has {SHARED_PACKAGE_FILE_NAME} ignored. ");
+        log::warn!(
+            "Please commit it to avoid inconsistent dependency resolving. git add {SHARED_PACKAGE_FILE_NAME} --force"
+        );
+    }
+
+    if unlocked && env::var("CI") == Ok("true".to_string()) {
+        log::warn!("Running in CI and using unlocked resolve, this seems like a bug!");
+        log::warn!(
+            "Make sure {SHARED_PACKAGE_FILE_NAME} is not gitignore'd and is comitted in the repository"
+        );
+    }
+
+    let original_lock = shared_package_opt.clone();
+
+    let resolved = match &mut shared_package_opt {
+        // locked resolve
+        // only if shared_package is Some() and locked
+        Some(shared_package) if !unlocked => {
+            // if the same, restore as usual
+            log::info!("Using lock file for restoring");
+
+            // update config
+            shared_package.config = package;
+            // make additional data use cached data
+            shared_package
+                .restored_dependencies
+                .iter_mut()
+                .try_for_each(|d| -> color_eyre::Result<()> {
+                    let package = repo
+                        .get_package(&d.dependency.id, &d.version)
+                        .ok()
+                        .flatten()
+                        .with_context(|| {
+                            format!(
+                                "Unable to fetch {}:{}",
+                                d.dependency.id.dependency_id_color(),
+                                d.version.version_id_color()
+                            )
+                        })?;
+                    d.dependency.additional_data = package.config.info.additional_data;
+                    Ok(())
+                })?;
+            dependency::locked_resolve(shared_package, repo)?.collect_vec()
+        }
+        // Unlocked resolve
+        _ => {
+            log::info!("Resolving packages");
+
+            let (spc_result, restored_deps) =
+                SharedPackageConfig::resolve_from_package(package, repo)?;
+            // update shared_package
+            shared_package_opt = Some(spc_result);
+
+            restored_deps
+        }
+    };
+
+    let shared_package = shared_package_opt.expect("SharedPackage is None somehow!");
+
+    let lock_changed = original_lock
+        .as_ref()
+        .is_none_or(|original| lock_differs(original, &shared_package));
+    let downloads = dependency::missing_from_cache(&resolved)
+        .into_iter()
+        .cloned()
+        .collect_vec();
+
+    if options.frozen && lock_changed {
+        return Err(Error::LockOutOfDate(format!(
+            "{SHARED_PACKAGE_FILE_NAME} would change"
+        )));
+    }
+    if options.frozen && !downloads.is_empty() {
+        return Err(Error::DownloadRequired(
+            downloads
+                .iter()
+                .map(|d| (d.config.info.id.clone(), d.config.info.version.clone()))
+                .collect(),
+        ));
+    }
+
+    if options.dry_run {
+        // the cache must be complete to know what ends up in extern
+        let extern_changes = downloads
+            .is_empty()
+            .then(|| {
+                FileRepository::plan_copy_from_cache(&shared_package.config, &resolved, workspace)
+            })
+            .transpose()?;
+        let generated_changes = dependency::generated_files(workspace, &shared_package, &*repo)?
+            .into_iter()
+            .filter(|(path, content)| fs::read_to_string(path).ok().as_ref() != Some(content))
+            .map(|(path, _)| path)
+            .collect();
+
+        return Ok(RestoreResult {
+            original_lock,
+            shared_package,
+            resolved,
+            downloads,
+            lock_changed,
+            extern_changes,
+            generated_changes,
+        });
+    }
+
+    // always write to reflect config changes
+    dependency::restore(workspace, &shared_package, &resolved, repo)?;
+    shared_package.write(workspace)?;
+
+    ndk::validate_ndk(workspace, &shared_package.config)?;
+
+    Ok(RestoreResult {
+        original_lock,
+        shared_package,
+        resolved,
+        downloads,
+        lock_changed,
+        extern_changes: None,
+        generated_changes: vec![],
+    })
+}
@@ -0,0 +1,189 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    hint::black_box,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use color_eyre::Result;
+use qpm_package::models::{
+    backend::PackageVersion, dependency::SharedPackageConfig, package::PackageConfig,
+};
+use semver::Version;
+
+use qpm_cli::repository::Repository;
+
+/// Timings of repeated runs of one benchmark
+pub struct Measurement {
+    pub name: String,
+    /// Sorted ascending
+    pub samples: Vec<Duration>,
+}
+
+impl Measurement {
+    pub fn min(&self) -> Duration {
+        self.samples[0]
+    }
+
+    pub fn median(&self) -> Duration {
+        self.samples[self.samples.len() / 2]
+    }
+
+    pub fn mean(&self) -> Duration {
+        self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<40} min {:>10.3?}  median {:>10.3?}  mean {:>10.3?}  ({} runs)",
+            self.name,
+            self.min(),
+            self.median(),
+            self.mean(),
+            self.samples.len()
+        )
+    }
+}
+
+/// Runs `f` once to warm up, then `iterations` times, and prints the timings
+pub fn measure<T>(name: &str, iterations: usize, mut f: impl FnMut() -> T) -> Measurement {
+    black_box(f());
+
+    let mut samples = (0..iterations.max(1))
+        .map(|_| {
+            let time = Instant::now();
+            black_box(f());
+            time.elapsed()
+        })
+        .collect::<Vec<_>>();
+    samples.sort();
+
+    let measurement = Measurement {
+        name: name.to_string(),
+        samples,
+    };
+    println!("{measurement}");
+    measurement
+}
+
+/// Counts the requests reaching a repository, to check what a cache in front of it saves
+pub struct CountingRepository<R: Repository> {
+    inner: R,
+    pub package_calls: AtomicUsize,
+    pub version_calls: AtomicUsize,
+    pub requested_packages: Mutex<HashSet<(String, Version)>>,
+    pub requested_versions: Mutex<HashSet<String>>,
+}
+
+impl<R: Repository> CountingRepository<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            package_calls: Default::default(),
+            version_calls: Default::default(),
+            requested_packages: Default::default(),
+            requested_versions: Default::default(),
+        }
+    }
+}
+
+impl<R: Repository> Repository for CountingRepository<R> {
+    fn get_package_names(&self) -> Result<Vec<String>> {
+        self.inner.get_package_names()
+    }
+
+    fn get_package_versions(&self, id: &str) -> Result<Option<Vec<PackageVersion>>> {
+        self.version_calls.fetch_add(1, Ordering::Relaxed);
+        self.requested_versions
+            .lock()
+            .unwrap()
+            .insert(id.to_string());
+        self.inner.get_package_versions(id)
+    }
+
+    fn get_package(&self, id: &str, version: &Version) -> Result<Option<SharedPackageConfig>> {
+        self.package_calls.fetch_add(1, Ordering::Relaxed);
+        self.requested_packages
+            .lock()
+            .unwrap()
+            .insert((id.to_string(), version.clone()));
+        self.inner.get_package(id, version)
+    }
+
+    fn add_to_db_cache(&mut self, config: SharedPackageConfig, permanent: bool) -> Result<()> {
+        self.inner.add_to_db_cache(config, permanent)
+    }
+
+    fn is_online(&self) -> bool {
+        self.inner.is_online()
+    }
+
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn download_to_cache(&mut self, config: &PackageConfig) -> Result<bool> {
+        self.inner.download_to_cache(config)
+    }
+
+    fn write_repo(&self) -> Result<()> {
+        self.inner.write_repo()
+    }
+}
+
+/// Delays every metadata request, like a repository behind a high latency link
+pub struct LatencyRepository<R: Repository> {
+    inner: R,
+    latency: Duration,
+}
+
+impl<R: Repository> LatencyRepository<R> {
+    pub fn new(inner: R, latency: Duration) -> Self {
+        Self { inner, latency }
+    }
+}
+
+impl<R: Repository> Repository for LatencyRepository<R> {
+    fn get_package_names(&self) -> Result<Vec<String>> {
+        thread::sleep(self.latency);
+        self.inner.get_package_names()
+    }
+
+    fn get_package_versions(&self, id: &str) -> Result<Option<Vec<PackageVersion>>> {
+        thread::sleep(self.latency);
+        self.inner.get_package_versions(id)
+    }
+
+    fn get_package(&self, id: &str, version: &Version) -> Result<Option<SharedPackageConfig>> {
+        thread::sleep(self.latency);
+        self.inner.get_package(id, version)
+    }
+
+    fn add_to_db_cache(&mut self, config: SharedPackageConfig, permanent: bool) -> Result<()> {
+        self.inner.add_to_db_cache(config, permanent)
+    }
+
+    fn is_online(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn download_to_cache(&mut self, config: &PackageConfig) -> Result<bool> {
+        self.inner.download_to_cache(config)
+    }
+
+    fn write_repo(&self) -> Result<()> {
+        self.inner.write_repo()
+    }
+}
@@ -1 +1,9 @@
+//! Resolver benchmarks over synthetic package universes.
+//!
+//! The timings are only meaningful in release mode:
+//! `cargo test --release benchmark -- --ignored --nocapture`
 
+pub mod harness;
+pub mod universe;
+
+mod resolve;
@@ -0,0 +1,144 @@
+use std::{sync::atomic::Ordering, thread, time::Duration};
+
+use color_eyre::Result;
+use itertools::Itertools;
+
+use qpm_cli::{
+    repository::{Repository, memcached::MemcachedRepository},
+    resolver::dependency,
+};
+
+use super::{
+    harness::{CountingRepository, LatencyRepository, measure},
+    universe::{Universe, conflict_heavy, deep_chain, wide_fan_out},
+};
+
+const ITERATIONS: usize = 10;
+
+fn check_resolves(universe: &Universe, repo: &impl Repository) -> Result<()> {
+    let resolved = dependency::resolve(&universe.root.config, repo)?.collect_vec();
+    assert_eq!(resolved.len(), universe.expected_packages);
+
+    let lock = universe.lock(&resolved);
+    let locked = dependency::locked_resolve(&lock, repo)?.collect_vec();
+    assert_eq!(locked.len(), universe.expected_packages);
+    Ok(())
+}
+
+/// The cache must forward every distinct request to the inner repository exactly once
+fn check_cache(universe: &Universe) -> Result<()> {
+    let cached = MemcachedRepository::new(CountingRepository::new(universe.repo.clone()));
+    check_resolves(universe, &cached)?;
+    let inner = cached.inner();
+    assert_eq!(
+        inner.package_calls.load(Ordering::Relaxed),
+        inner.requested_packages.lock().unwrap().len()
+    );
+    assert_eq!(
+        inner.version_calls.load(Ordering::Relaxed),
+        inner.requested_versions.lock().unwrap().len()
+    );
+    Ok(())
+}
+
+fn bench_universe(name: &str, universe: &Universe) -> Result<()> {
+    check_resolves(universe, &universe.repo)?;
+
+    measure(&format!("{name}: resolve"), ITERATIONS, || {
+        dependency::resolve(&universe.root.config, &universe.repo)
+            .unwrap()
+            .count()
+    });
+    // a new cache per run, like a single qpm invocation
+    measure(&format!("{name}: resolve memcached"), ITERATIONS, || {
+        let repo = MemcachedRepository::new(universe.repo.clone());
+        dependency::resolve(&universe.root.config, &repo)
+            .unwrap()
+            .count()
+    });
+
+    // cold resolves over a slow link, where prefetching pays off
+    measure(
+        &format!("{name}: resolve memcached 1ms latency"),
+        ITERATIONS,
+        || {
+            let repo = MemcachedRepository::new(LatencyRepository::new(
+                universe.repo.clone(),
+                Duration::from_millis(1),
+            ));
+            dependency::resolve(&universe.root.config, &repo)
+                .unwrap()
+                .count()
+        },
+    );
+
+    let resolved = dependency::resolve(&universe.root.config, &universe.repo)?.collect_vec();
+    let lock = universe.lock(&resolved);
+    measure(&format!("{name}: locked_resolve"), ITERATIONS, || {
+        dependency::locked_resolve(&lock, &universe.repo)
+            .unwrap()
+            .count()
+    });
+    Ok(())
+}
+
+#[test]
+fn synthetic_universes_resolve() -> Result<()> {
+    for universe in [deep_chain(20, 5), wide_fan_out(20, 5), conflict_heavy(4, 4)] {
+        check_resolves(&universe, &universe.repo)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn memcached_forwards_each_request_once() -> Result<()> {
+    check_cache(&deep_chain(10, 3))?;
+    check_cache(&wide_fan_out(10, 3))?;
+    check_cache(&conflict_heavy(4, 4))?;
+    Ok(())
+}
+
+#[test]
+fn memcached_shared_between_threads() -> Result<()> {
+    let universe = wide_fan_out(10, 3);
+    let cached = MemcachedRepository::new(CountingRepository::new(universe.repo.clone()));
+
+    thread::scope(|scope| {
+        let handles = (0..4)
+            .map(|_| scope.spawn(|| check_resolves(&universe, &cached)))
+            .collect_vec();
+        handles
+            .into_iter()
+            .try_for_each(|handle| handle.join().expect("Resolve panicked"))
+    })?;
+
+    // racing threads wait for each other instead of repeating requests
+    let inner = cached.inner();
+    assert_eq!(
+        inner.package_calls.load(Ordering::Relaxed),
+        inner.requested_packages.lock().unwrap().len()
+    );
+    assert_eq!(
+        inner.version_calls.load(Ordering::Relaxed),
+        inner.requested_versions.lock().unwrap().len()
+    );
+    Ok(())
+}
+
+#[test]
+#[ignore = "benchmark, run in release mode"]
+fn benchmark_deep_chain() -> Result<()> {
+    bench_universe("deep chain 200x20", &deep_chain(200, 20))
+}
+
+#[test]
+#[ignore = "benchmark, run in release mode"]
+fn benchmark_wide_fan_out() -> Result<()> {
+    bench_universe("wide fan out 500x20", &wide_fan_out(500, 20))
+}
+
+#[test]
+#[ignore = "benchmark, run in release mode"]
+fn benchmark_conflict_heavy() -> Result<()> {
+    bench_universe("conflict heavy 8x8", &conflict_heavy(8, 8))
+}
@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+
+use qpm_package::models::dependency::{Dependency, SharedDependency, SharedPackageConfig};
+use semver::{Version, VersionReq};
+
+use qpm_cli::repository::local::FileRepository;
+
+use crate::tests::mocks::repo::{
+    build_artifact_and_depends, build_artifact_nodeps, build_repository,
+};
+
+/// A synthetic registry and the package to resolve in it
+pub struct Universe {
+    pub root: SharedPackageConfig,
+    pub repo: FileRepository,
+    /// Number of packages a successful resolve returns
+    pub expected_packages: usize,
+}
+
+impl Universe {
+    /// Lock file of the root package with `resolved` as its restored dependencies
+    pub fn lock(&self, resolved: &[SharedPackageConfig]) -> SharedPackageConfig {
+        SharedPackageConfig {
+            config: self.root.config.clone(),
+            restored_dependencies: resolved
+                .iter()
+                .map(|p| SharedDependency {
+                    dependency: Dependency {
+                        id: p.config.info.id.clone(),
+                        version_range: VersionReq::parse(&format!("={}", p.config.info.version))
+                            .unwrap(),
+                        additional_data: p.config.info.additional_data.clone(),
+                    },
+                    version: p.config.info.version.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn caret(major: u64) -> VersionReq {
+    VersionReq::parse(&format!("^{major}.0.0")).unwrap()
+}
+
+/// `versions` releases of a package, each depending on `deps` with the range given for its minor
+fn releases(
+    id: &str,
+    versions: u64,
+    deps: impl Fn(u64) -> Vec<(SharedPackageConfig, VersionReq)>,
+) -> Vec<SharedPackageConfig> {
+    (0..versions)
+        .map(|minor| {
+            let deps = deps(minor);
+            let deps = deps.iter().map(|(p, r)| (p, r.clone())).collect::<Vec<_>>();
+            build_artifact_and_depends(id, Version::new(1, minor, 0), &deps)
+        })
+        .collect()
+}
+
+fn finish(
+    root_deps: &[(&SharedPackageConfig, VersionReq)],
+    artifacts: Vec<SharedPackageConfig>,
+) -> Universe {
+    let root = build_artifact_and_depends("root", Version::new(1, 0, 0), root_deps);
+    let expected_packages = artifacts
+        .iter()
+        .map(|a| &a.config.info.id)
+        .collect::<HashSet<_>>()
+        .len();
+
+    Universe {
+        repo: build_repository(artifacts.into_iter().chain([root.clone()])),
+        root,
+        expected_packages,
+    }
+}
+
+/// `chain-0` depends on `chain-1`, which depends on `chain-2`, down to `depth` packages
+pub fn deep_chain(depth: usize, versions: u64) -> Universe {
+    let mut artifacts: Vec<SharedPackageConfig> = vec![];
+    let mut next: Option<SharedPackageConfig> = None;
+
+    for i in (0..depth).rev() {
+        let id = format!("chain-{i}");
+        let package = releases(&id, versions, |_| {
+            next.iter().map(|n| (n.clone(), caret(1))).collect()
+        });
+        next = package.last().cloned();
+        artifacts.extend(package);
+    }
+
+    let first = next.expect("chain must not be empty");
+    finish(&[(&first, caret(1))], artifacts)
+}
+
+/// The root depends on `width` packages which all depend on the same leaf
+pub fn wide_fan_out(width: usize, versions: u64) -> Universe {
+    let leaf = (0..versions)
+        .map(|minor| build_artifact_nodeps("leaf", Version::new(1, minor, 0)))
+        .collect::<Vec<_>>();
+    let latest_leaf = leaf.last().unwrap().clone();
+
+    let fans = (0..width)
+        .map(|i| {
+            releases(&format!("fan-{i}"), versions, |_| {
+                vec![(latest_leaf.clone(), caret(1))]
+            })
+        })
+        .collect::<Vec<_>>();
+    let root_deps = fans
+        .iter()
+        .map(|f| (f.last().unwrap(), caret(1)))
+        .collect::<Vec<_>>();
+
+    let artifacts = leaf
+        .iter()
+        .cloned()
+        .chain(fans.iter().flatten().cloned())
+        .collect();
+    finish(&root_deps, artifacts)
+}
+
+/// `packages` packages whose releases each need a different major of `base`,
+/// rotated per package so the newest releases never agree and the resolver has to backtrack.
+/// Picking minor `(t - i) % versions` of `conflict-i` for any `t` is a solution.
+pub fn conflict_heavy(packages: usize, versions: u64) -> Universe {
+    let base = (1..=versions)
+        .map(|major| build_artifact_nodeps("base", Version::new(major, 0, 0)))
+        .collect::<Vec<_>>();
+
+    let conflicts = (0..packages)
+        .map(|i| {
+            releases(&format!("conflict-{i}"), versions, |minor| {
+                let major = (minor + i as u64) % versions + 1;
+                vec![(base[major as usize - 1].clone(), caret(major))]
+            })
+        })
+        .collect::<Vec<_>>();
+    let root_deps = conflicts
+        .iter()
+        .map(|c| (c.last().unwrap(), caret(1)))
+        .collect::<Vec<_>>();
+
+    let artifacts = base
+        .iter()
+        .cloned()
+        .chain(conflicts.iter().flatten().cloned())
+        .collect();
+    finish(&root_deps, artifacts)
+}
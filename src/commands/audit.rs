@@ -0,0 +1,110 @@
+use clap::Args;
+use color_eyre::{
+    Result, Section,
+    eyre::{OptionExt, bail},
+};
+use owo_colors::OwoColorize;
+use qpm_package::models::dependency::SharedPackageConfig;
+
+use crate::{
+    models::{
+        advisory::{AdvisorySeverity, AdvisorySource},
+        config::get_combine_config,
+        output::{AuditFinding, AuditOutput},
+        package::PackageConfigExtensions,
+        schemas::SchemaLinks,
+    },
+    terminal::{colors::QPMColor, output},
+};
+
+use super::Command;
+
+#[derive(Args)]
+pub struct AuditCommand {
+    /// Path or git url of the advisory database, defaults to the configured one
+    #[clap(long)]
+    pub db: Option<String>,
+
+    /// Fetch the latest advisories before auditing, otherwise the last synced copy is used
+    #[clap(long, default_value = "false")]
+    pub sync: bool,
+
+    /// Fail if an advisory of this severity or higher is found
+    #[clap(long, value_enum, default_value_t = AdvisorySeverity::Low)]
+    pub fail_on: AdvisorySeverity,
+}
+
+impl Command for AuditCommand {
+    fn execute(self) -> Result<()> {
+        let source = self
+            .db
+            .or_else(|| get_combine_config().advisory_db.clone())
+            .ok_or_eyre("No advisory database was given")
+            .with_suggestion(|| "Pass --db or run qpm config advisories <path or git url>")?;
+        let source = AdvisorySource::parse(&source);
+
+        if self.sync {
+            source.sync()?;
+        }
+        let db = source
+            .load()
+            .with_suggestion(|| "Run qpm audit --sync to fetch the advisory database")?;
+
+        let shared_package = SharedPackageConfig::read(".")?;
+        let findings: Vec<AuditFinding> = shared_package
+            .restored_dependencies
+            .iter()
+            .flat_map(|restored| {
+                db.affecting(&restored.dependency.id, &restored.version)
+                    .map(|advisory| AuditFinding {
+                        id: advisory.id.clone(),
+                        package: restored.dependency.id.clone(),
+                        version: restored.version.clone(),
+                        severity: advisory.severity,
+                        description: advisory.description.clone(),
+                        url: advisory.url.clone(),
+                    })
+            })
+            .collect();
+
+        let failing = findings
+            .iter()
+            .filter(|f| f.severity >= self.fail_on)
+            .count();
+
+        if output::is_json() {
+            output::print_json(SchemaLinks::OUTPUT_AUDIT, AuditOutput { findings })?;
+        } else {
+            for f in &findings {
+                let severity = match f.severity {
+                    AdvisorySeverity::Critical | AdvisorySeverity::High => {
+                        f.severity.bright_red().to_string()
+                    }
+                    AdvisorySeverity::Medium => f.severity.bright_yellow().to_string(),
+                    AdvisorySeverity::Low => f.severity.to_string(),
+                };
+                crate::output!(
+                    "{severity}[{}]: {}:{} {}",
+                    f.id,
+                    f.package.dependency_id_color(),
+                    f.version.version_id_color(),
+                    f.description
+                );
+                if let Some(url) = &f.url {
+                    crate::output!("  {}", url.download_file_name_color());
+                }
+            }
+            if findings.is_empty() {
+                log::info!("No advisories affect the restored dependencies");
+            }
+        }
+
+        if failing > 0 {
+            bail!(
+                "{failing} advisories of severity {} or higher found",
+                self.fail_on
+            );
+        }
+        Ok(())
+    }
+}
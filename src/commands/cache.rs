@@ -5,15 +5,21 @@ use std::{
 
 use clap::Subcommand;
 use color_eyre::{Result, eyre::Context};
+use itertools::Itertools;
 use owo_colors::OwoColorize;
 use qpm_package::models::package::PackageConfig;
 use semver::Version;
 use walkdir::WalkDir;
 
 use crate::{
-    models::{config::get_combine_config, package::PackageConfigExtensions},
+    models::{
+        config::get_combine_config,
+        output::{CacheListOutput, CachedPackage},
+        package::PackageConfigExtensions,
+        schemas::SchemaLinks,
+    },
     repository::local::FileRepository,
-    terminal::colors::QPMColor,
+    terminal::{colors::QPMColor, output},
 };
 
 use super::Command;
@@ -49,7 +55,7 @@ impl Command for CacheCommand {
     fn execute(self) -> color_eyre::Result<()> {
         match self.op {
             CacheOperation::Clear(c) => clear(c)?,
-            CacheOperation::List => list(),
+            CacheOperation::List => list()?,
             CacheOperation::Path => path(),
             CacheOperation::LegacyFix => legacy_fix()?,
         };
@@ -62,7 +68,7 @@ fn clear(clear_params: ClearCommand) -> Result<()> {
         (Some(package), None) => {
             let mut file_repo = FileRepository::read()?;
             file_repo.remove_package_versions(&package)?;
-            println!(
+            log::info!(
                 "Sucessfully removed all versions of {}",
                 package.dependency_id_color()
             );
@@ -73,7 +79,7 @@ fn clear(clear_params: ClearCommand) -> Result<()> {
             let mut file_repo = FileRepository::read()?;
             let version = Version::parse(&version_str).context("version parse")?;
             file_repo.remove_package(&package, &version)?;
-            println!(
+            log::info!(
                 "Sucessfully removed {}/{}",
                 package.dependency_id_color(),
                 version.version_id_color()
@@ -94,30 +100,63 @@ fn clear(clear_params: ClearCommand) -> Result<()> {
 
 fn path() {
     let config = get_combine_config();
-    println!(
+    crate::output!(
         "Config path is: {}",
         config.cache.as_ref().unwrap().display().bright_yellow()
     );
 }
 
-fn list() {
+fn list() -> Result<()> {
     let config = get_combine_config();
     let path = config.cache.as_ref().unwrap();
 
+    if output::is_json() {
+        let packages = WalkDir::new(path)
+            .max_depth(1)
+            .min_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .map(|package_dir| -> Result<CachedPackage> {
+                let package_dir = package_dir?;
+                let versions = WalkDir::new(package_dir.path())
+                    .max_depth(1)
+                    .min_depth(1)
+                    .sort_by_file_name()
+                    .into_iter()
+                    .map_ok(|v| v.file_name().to_string_lossy().to_string())
+                    .try_collect()?;
+
+                Ok(CachedPackage {
+                    id: package_dir.file_name().to_string_lossy().to_string(),
+                    versions,
+                })
+            })
+            .try_collect()?;
+
+        return output::print_json(
+            SchemaLinks::OUTPUT_CACHE_LIST,
+            CacheListOutput {
+                path: path.clone(),
+                packages,
+            },
+        );
+    }
+
     for dir in WalkDir::new(path).max_depth(2).min_depth(1) {
         let unwrapped = dir.unwrap();
         if unwrapped.depth() == 1 {
-            println!(
+            crate::output!(
                 "package {}:",
                 unwrapped.file_name().to_string_lossy().bright_red()
             );
         } else {
-            println!(
+            crate::output!(
                 " - {}",
                 unwrapped.file_name().to_string_lossy().bright_green()
             );
         }
     }
+    Ok(())
 }
 
 fn legacy_fix() -> Result<()> {
@@ -126,7 +165,7 @@ fn legacy_fix() -> Result<()> {
         .max_depth(2)
     {
         let path = entry.unwrap().into_path().join("src");
-        println!("{}", path.display());
+        log::info!("{}", path.display());
         let qpm_path = &path;
         if !qpm_path.exists() {
             continue;
@@ -149,8 +188,7 @@ fn legacy_fix() -> Result<()> {
                 match file.read_to_string(&mut buf) {
                     Ok(_) => {}
                     Err(e) => {
-                        #[cfg(debug_assertions)]
-                        println!(
+                        log::debug!(
                             "reading file {} to string failed: {}",
                             entry_path.display().bright_yellow(),
                             e
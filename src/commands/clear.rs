@@ -61,36 +61,32 @@ fn remove_dependencies_dir() -> Result<()> {
 
     for entry in WalkDir::new(extern_path_canonical).min_depth(1) {
         let path = entry?.into_path();
-        #[cfg(debug_assertions)]
-        println!("Path: {}", path.display().bright_yellow());
+        log::debug!("Path: {}", path.display().bright_yellow());
         if path.is_symlink() {
             if path.is_dir() {
-                #[cfg(debug_assertions)]
-                println!("Was symlink dir!");
+                log::debug!("Was symlink dir!");
                 if let Err(e) = symlink::remove_symlink_dir(&path) {
-                    println!(
+                    log::warn!(
                         "Failed to remove symlink for directory {}: {}",
                         path.display().bright_yellow(),
                         e
                     );
                 }
             } else if path.is_file() {
-                #[cfg(debug_assertions)]
-                println!("Was symlink file!");
+                log::debug!("Was symlink file!");
                 if let Err(e) = symlink::remove_symlink_file(&path) {
-                    println!(
+                    log::warn!(
                         "Failed to remove symlink for file {}: {}",
                         path.display().bright_yellow(),
                         e
                     );
                 }
             } else {
-                #[cfg(debug_assertions)]
-                println!("Was broken symlink!");
+                log::debug!("Was broken symlink!");
                 if let Err(ed) = std::fs::remove_dir(&path)
                     && let Err(ef) = std::fs::remove_file(&path)
                 {
-                    println!(
+                    log::warn!(
                         "Failed to remove broken symlink for {}:\nAttempt 1 (dir):{}\nAttempt 2 (file):{}",
                         path.display().bright_yellow(),
                         ed,
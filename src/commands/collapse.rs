@@ -3,9 +3,14 @@ use owo_colors::OwoColorize;
 use qpm_package::models::package::PackageConfig;
 
 use crate::{
-    models::package::PackageConfigExtensions,
+    models::{
+        output::{CollapseOutput, CollapsedDependency, CollapsedPackage},
+        package::PackageConfigExtensions,
+        schemas::SchemaLinks,
+    },
     repository::{self},
     resolver::dependency::resolve,
+    terminal::output,
 };
 
 use super::Command;
@@ -21,8 +26,28 @@ impl Command for CollapseCommand {
         let package = PackageConfig::read(".")?;
         let binding = repository::useful_default_new(self.offline)?;
         let resolved = resolve(&package, &binding)?;
+
+        if output::is_json() {
+            let packages = resolved
+                .map(|shared_package| CollapsedPackage {
+                    id: shared_package.config.info.id,
+                    version: shared_package.config.info.version,
+                    restored_dependencies: shared_package
+                        .restored_dependencies
+                        .into_iter()
+                        .map(|shared_dep| CollapsedDependency {
+                            id: shared_dep.dependency.id,
+                            version_range: shared_dep.dependency.version_range.to_string(),
+                            version: shared_dep.version,
+                        })
+                        .collect(),
+                })
+                .collect();
+
+            return output::print_json(SchemaLinks::OUTPUT_COLLAPSE, CollapseOutput { packages });
+        }
         for shared_package in resolved {
-            println!(
+            crate::output!(
                 "{} --> {} ({} restored dependencies)",
                 &shared_package.config.info.id.bright_red(),
                 &shared_package.config.info.version.bright_green(),
@@ -34,7 +59,7 @@ impl Command for CollapseCommand {
             );
 
             for shared_dep in shared_package.restored_dependencies.iter() {
-                println!(
+                crate::output!(
                     " - {}: ({}) --> {}",
                     &shared_dep.dependency.id,
                     &shared_dep.dependency.version_range,
@@ -0,0 +1,47 @@
+use clap::Args;
+use color_eyre::Result;
+use itertools::Itertools;
+use owo_colors::OwoColorize;
+
+use crate::models::{abi::Abi, config::UserConfig};
+
+#[derive(Args, Debug, Clone)]
+pub struct AbisCommand {
+    /// ABIs to restore binaries for, the first one is built by default
+    pub abis: Vec<Abi>,
+
+    /// Go back to only arm64-v8a
+    #[clap(long, conflicts_with = "abis")]
+    pub reset: bool,
+}
+
+impl AbisCommand {
+    pub fn execute(self, config: &mut UserConfig) -> Result<()> {
+        if self.reset {
+            log::info!("Reset ABIs to {}!", Abi::default().bright_yellow());
+            config.abis = None;
+            return Ok(());
+        }
+
+        match self.abis.is_empty() {
+            false => {
+                log::info!(
+                    "Set ABIs to {}!",
+                    self.abis.iter().join(", ").bright_yellow()
+                );
+                config.abis = Some(self.abis);
+            }
+            true => match &config.abis {
+                Some(abis) => crate::output!(
+                    "Current configured ABIs are: {}",
+                    abis.iter().join(", ").bright_yellow()
+                ),
+                None => crate::output!(
+                    "No ABIs were configured, using {}",
+                    Abi::default().bright_yellow()
+                ),
+            },
+        }
+        Ok(())
+    }
+}
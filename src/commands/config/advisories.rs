@@ -0,0 +1,30 @@
+use clap::Args;
+use color_eyre::Result;
+use owo_colors::OwoColorize;
+
+use crate::models::config::UserConfig;
+
+#[derive(Args, Debug, Clone)]
+pub struct AdvisoriesCommand {
+    /// Path or git url of the advisory database
+    pub source: Option<String>,
+}
+
+impl AdvisoriesCommand {
+    pub fn execute(self, config: &mut UserConfig) -> Result<()> {
+        match self.source {
+            Some(source) => {
+                log::info!("Set advisory database to {}!", source.bright_yellow());
+                config.advisory_db = Some(source);
+            }
+            None => match &config.advisory_db {
+                Some(source) => crate::output!(
+                    "Current configured advisory database is: {}",
+                    source.bright_yellow()
+                ),
+                None => crate::output!("No advisory database was configured!"),
+            },
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,11 @@
 use clap::{Args, Subcommand};
 
+mod abis;
+mod advisories;
 mod cache;
 mod ndkpath;
 mod publish;
+mod registry;
 mod symlink;
 mod timeout;
 mod token;
@@ -41,6 +44,12 @@ pub enum ConfigOperation {
     NDKPath(ndkpath::NDKPath),
     /// Get or set the publish key used for publish
     Publish(publish::KeyCommand),
+    /// List, add or remove registries to publish to
+    Registry(registry::RegistryCommand),
+    /// Get or set the advisory database used by audit
+    Advisories(advisories::AdvisoriesCommand),
+    /// Get or set the ABIs dependency binaries are restored for
+    Abis(abis::AbisCommand),
 }
 
 impl Command for ConfigCommand {
@@ -56,12 +65,15 @@ impl Command for ConfigCommand {
             ConfigOperation::Symlink(s) => s.execute(&mut config)?,
             ConfigOperation::Timeout(t) => t.execute(&mut config)?,
             ConfigOperation::Token(t) => t.execute()?,
-            ConfigOperation::Location => println!(
+            ConfigOperation::Location => crate::output!(
                 "Global Config is located at {}",
                 UserConfig::global_config_path().display().bright_yellow()
             ),
             ConfigOperation::NDKPath(p) => p.execute(&mut config)?,
             ConfigOperation::Publish(k) => k.execute()?,
+            ConfigOperation::Registry(r) => r.execute(&mut config)?,
+            ConfigOperation::Advisories(a) => a.execute(&mut config)?,
+            ConfigOperation::Abis(a) => a.execute(&mut config)?,
         };
 
         config.write(self.local)?;
@@ -14,15 +14,15 @@ impl NDKPath {
     pub fn execute(self, config: &mut UserConfig) -> Result<()> {
         match self.ndk_path {
             Some(path) => {
-                println!("Set ndk path to {}!", path.bright_yellow());
+                log::info!("Set ndk path to {}!", path.bright_yellow());
                 config.ndk_download_path = Some(path.into());
             }
             None => match &config.ndk_download_path {
-                Some(path) => println!(
+                Some(path) => crate::output!(
                     "Current configured ndk path is: {}",
                     path.to_str().unwrap().bright_yellow()
                 ),
-                None => println!("No ndk path was configured!"),
+                None => crate::output!("No ndk path was configured!"),
             },
         }
         Ok(())
@@ -0,0 +1,88 @@
+use clap::{Args, ValueEnum};
+use color_eyre::{Result, eyre::bail};
+use owo_colors::OwoColorize;
+
+use crate::models::config::{RegistryConfig, UserConfig};
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryKind {
+    /// A qpackages compatible HTTP API
+    Http,
+    /// A git repository used as package index
+    Git,
+    /// The local file repository
+    Local,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct RegistryCommand {
+    /// Name of the registry, lists all registries if unset
+    pub name: Option<String>,
+
+    /// Kind of registry to add
+    #[clap(long, value_enum, requires = "name")]
+    pub kind: Option<RegistryKind>,
+
+    /// Url of the API or git repository
+    #[clap(long)]
+    pub url: Option<String>,
+
+    /// Branch of the git index to push to
+    #[clap(long)]
+    pub branch: Option<String>,
+
+    /// Remove the registry
+    #[clap(long, conflicts_with = "kind")]
+    pub delete: bool,
+}
+
+impl RegistryCommand {
+    pub fn execute(&self, config: &mut UserConfig) -> Result<()> {
+        let Some(name) = &self.name else {
+            match &config.registries {
+                Some(registries) if !registries.is_empty() => {
+                    for (name, registry) in registries {
+                        crate::output!("{} -> {registry}", name.bright_yellow());
+                    }
+                }
+                _ => crate::output!("No registries are configured!"),
+            }
+            return Ok(());
+        };
+
+        if self.delete {
+            match config.registries.as_mut().and_then(|r| r.remove(name)) {
+                Some(_) => log::info!("Removed registry {}", name.bright_yellow()),
+                None => log::warn!("There was no registry {} configured", name.bright_yellow()),
+            }
+            return Ok(());
+        }
+
+        let Some(kind) = self.kind else {
+            match config.registries.as_ref().and_then(|r| r.get(name)) {
+                Some(registry) => crate::output!("{} -> {registry}", name.bright_yellow()),
+                None => crate::output!("Registry {} is not configured!", name.bright_yellow()),
+            }
+            return Ok(());
+        };
+
+        let registry = match (kind, &self.url) {
+            (RegistryKind::Http, Some(url)) => RegistryConfig::Http {
+                url: url.trim_end_matches('/').to_string(),
+            },
+            (RegistryKind::Git, Some(url)) => RegistryConfig::Git {
+                url: url.clone(),
+                branch: self.branch.clone(),
+            },
+            (RegistryKind::Local, _) => RegistryConfig::Local,
+            (_, None) => bail!("--url is required for {kind:?} registries"),
+        };
+
+        log::info!("Set registry {} to {registry}", name.bright_yellow());
+        config
+            .registries
+            .get_or_insert_default()
+            .insert(name.clone(), registry);
+        Ok(())
+    }
+}
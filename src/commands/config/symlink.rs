@@ -33,12 +33,12 @@ impl Symlink {
             },
             None => match config.symlink {
                 Some(symlink) => {
-                    println!(
+                    crate::output!(
                         "Current configured symlink usage is set to: {}",
                         symlink.bright_yellow()
                     );
                 }
-                None => println!("Symlink usage is not configured!"),
+                None => crate::output!("Symlink usage is not configured!"),
             },
         };
 
@@ -47,6 +47,6 @@ impl Symlink {
 }
 
 fn set_symlink_usage(config: &mut UserConfig, value: bool) {
-    println!("Set symlink usage to {}", value.bright_yellow());
+    log::info!("Set symlink usage to {}", value.bright_yellow());
     config.symlink = Some(value);
 }
@@ -13,15 +13,15 @@ impl Timeout {
     pub fn execute(&self, config: &mut UserConfig) -> Result<()> {
         match self.timeout {
             Some(timeout) => {
-                println!("Set timeout to {}!", timeout.bright_yellow());
+                log::info!("Set timeout to {}!", timeout.bright_yellow());
                 config.timeout = Some(timeout);
             }
             None => match config.timeout {
-                Some(timeout) => println!(
+                Some(timeout) => crate::output!(
                     "Current configured timeout is set to: {}",
                     timeout.bright_yellow()
                 ),
-                None => println!("Timeout is not configured!"),
+                None => crate::output!("Timeout is not configured!"),
             },
         }
         Ok(())
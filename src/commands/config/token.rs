@@ -19,10 +19,10 @@ impl Command for TokenCommand {
                     .delete_credential()
                     .context("Removing password failed")?;
             }
-            println!("Deleted github token from config, it will no longer be used");
+            log::info!("Deleted github token from config, it will no longer be used");
             return Ok(());
         } else if self.delete {
-            println!("There was no github token configured, did not delete it");
+            log::info!("There was no github token configured, did not delete it");
             return Ok(());
         }
 
@@ -36,20 +36,23 @@ impl Command for TokenCommand {
                 } else {
                     return Err(color_eyre::eyre::eyre!("Keyring unavailable"));
                 }
-                println!("Configured a github token! This will now be used in qpm restore");
+                log::info!("Configured a github token! This will now be used in qpm restore");
             }
             None => {
                 // read token, possibly unused so prepend with _ to prevent warnings
                 if let Some(_token) = get_keyring().and_then(|e| e.get_password().ok()) {
                     #[cfg(debug_assertions)]
-                    println!("Configured github token: {}", _token.bright_yellow());
+                    crate::terminal::logging::write_secret(format_args!(
+                        "Configured github token: {}",
+                        _token.bright_yellow()
+                    ));
                     #[cfg(not(debug_assertions))]
-                    println!(
+                    crate::output!(
                         "In release builds you {} view the configured github token, a token was configured though!",
                         "cannot".bright_red()
                     );
                 } else {
-                    println!("No token was configured, or getting the token failed!");
+                    crate::output!("No token was configured, or getting the token failed!");
                 }
             }
         }
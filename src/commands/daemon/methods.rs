@@ -0,0 +1,296 @@
+//! Requests the daemon answers, see the module docs of [`super`]
+
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+use qpm_package::models::{dependency::SharedPackageConfig, package::PackageConfig};
+use semver::Version;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use crate::{
+    api::{self, QmodManifestOptions, RestoreOptions},
+    commands::{ndk::installed_ndks, search::search_packages},
+    models::{
+        output::{NdkListOutput, SearchOutput},
+        package::{PackageConfigExtensions, SharedPackageConfigExtensions},
+    },
+    repository::{
+        self, Repository, memcached::MemcachedRepository, multi::MultiDependencyRepository,
+    },
+    resolver::semver::{VersionWrapper, req_to_range},
+};
+
+use super::rpc::{INVALID_PARAMS, METHOD_NOT_FOUND, RpcError};
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct WorkspaceParams {
+    /// Directory of the qpm.json, the working directory of the daemon if unset
+    workspace: Option<PathBuf>,
+}
+
+impl WorkspaceParams {
+    fn path(&self) -> &Path {
+        self.workspace.as_deref().unwrap_or(Path::new("."))
+    }
+}
+
+#[derive(Deserialize)]
+struct VersionsParams {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchParams {
+    query: String,
+    #[serde(default = "default_search_limit")]
+    limit: usize,
+}
+
+fn default_search_limit() -> usize {
+    20
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct RestoreParams {
+    #[serde(flatten)]
+    workspace: WorkspaceParams,
+    update: bool,
+    frozen: bool,
+    dry_run: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct QmodManifestParams {
+    #[serde(flatten)]
+    workspace: WorkspaceParams,
+    exclude_libs: Option<Vec<String>>,
+    include_libs: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DependencyVersion {
+    id: String,
+    version: Version,
+}
+
+impl From<&SharedPackageConfig> for DependencyVersion {
+    fn from(package: &SharedPackageConfig) -> Self {
+        DependencyVersion {
+            id: package.config.info.id.clone(),
+            version: package.config.info.version.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RestoreOutput {
+    lock_changed: bool,
+    dependencies: Vec<DependencyVersion>,
+    downloads: Vec<DependencyVersion>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutdatedDependency {
+    id: String,
+    version_range: String,
+    /// Version in the lock file
+    locked: Version,
+    /// Newest version the range allows
+    latest_matching: Option<Version>,
+    latest: Option<Version>,
+}
+
+/// State kept warm between requests
+pub struct Daemon {
+    offline: bool,
+    repo: Option<MemcachedRepository<MultiDependencyRepository>>,
+}
+
+impl Daemon {
+    pub fn new(offline: bool) -> Self {
+        Self {
+            offline,
+            repo: None,
+        }
+    }
+
+    /// Forgets the cached metadata, e.g. after a package was published
+    pub fn refresh(&mut self) {
+        self.repo = None;
+    }
+
+    fn repo(&mut self) -> Result<&mut MemcachedRepository<MultiDependencyRepository>, RpcError> {
+        if self.repo.is_none() {
+            self.repo = Some(repository::useful_default_new(self.offline)?);
+        }
+        Ok(self.repo.as_mut().expect("Repository was just created"))
+    }
+
+    pub fn handle(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "version" => Ok(json!({ "version": env!("CARGO_PKG_VERSION") })),
+            "packages" => {
+                let ids = self.repo()?.get_package_names()?.into_iter().sorted();
+                to_value(ids.collect_vec())
+            }
+            "versions" => {
+                let params: VersionsParams = parse_params(params)?;
+                let versions = self
+                    .repo()?
+                    .get_package_versions(&params.id)?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|v| v.version)
+                    .sorted()
+                    .rev()
+                    .collect_vec();
+                to_value(versions)
+            }
+            "search" => {
+                let params: SearchParams = parse_params(params)?;
+                let results = search_packages(self.repo()?, &params.query, params.limit)?;
+                to_value(SearchOutput { results })
+            }
+            "resolve" => {
+                let params: WorkspaceParams = parse_params(params)?;
+                let package = read_package(params.path())?;
+                let (_, resolved) =
+                    SharedPackageConfig::resolve_from_package(package, &*self.repo()?)?;
+                to_value(json!({
+                    "dependencies": resolved.iter().map(DependencyVersion::from).collect_vec()
+                }))
+            }
+            "restore" => {
+                let params: RestoreParams = parse_params(params)?;
+                let options = RestoreOptions {
+                    update: params.update,
+                    offline: self.offline,
+                    frozen: params.frozen,
+                    dry_run: params.dry_run,
+                };
+                let restored =
+                    api::restore_with_repository(params.workspace.path(), &options, self.repo()?)
+                        .map_err(api_error)?;
+                to_value(RestoreOutput {
+                    lock_changed: restored.lock_changed,
+                    dependencies: restored.resolved.iter().map(Into::into).collect(),
+                    downloads: restored.downloads.iter().map(Into::into).collect(),
+                })
+            }
+            "outdated" => {
+                let params: WorkspaceParams = parse_params(params)?;
+                let outdated = self.outdated(params.path())?;
+                to_value(json!({ "dependencies": outdated }))
+            }
+            "ndk/list" => to_value(NdkListOutput {
+                ndks: installed_ndks()?,
+            }),
+            "qmod/manifest" => {
+                let params: QmodManifestParams = parse_params(params)?;
+                let options = QmodManifestOptions {
+                    exclude_libs: params.exclude_libs,
+                    include_libs: params.include_libs,
+                };
+                let manifest =
+                    api::qmod_manifest(params.workspace.path(), &options).map_err(api_error)?;
+                to_value(manifest)
+            }
+            "refresh" => {
+                self.refresh();
+                Ok(Value::Null)
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method {method}"),
+            )),
+        }
+    }
+
+    /// Locked dependencies that have a newer version
+    fn outdated(&mut self, workspace: &Path) -> Result<Vec<OutdatedDependency>, RpcError> {
+        if !SharedPackageConfig::exists(workspace) {
+            return Err(api_error(api::Error::MissingLock(workspace.to_path_buf())));
+        }
+        let shared_package = SharedPackageConfig::read(workspace)?;
+        let repo = self.repo()?;
+
+        let mut outdated = vec![];
+        for restored in &shared_package.restored_dependencies {
+            let dependency = &restored.dependency;
+            let range = req_to_range(dependency.version_range.clone());
+            let versions = repo
+                .get_package_versions(&dependency.id)?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|v| v.version)
+                .collect_vec();
+
+            let latest = versions.iter().max().cloned();
+            let latest_matching = versions
+                .iter()
+                .filter(|v| range.contains(&VersionWrapper((*v).clone())))
+                .max()
+                .cloned();
+
+            if latest.as_ref() > Some(&restored.version) {
+                outdated.push(OutdatedDependency {
+                    id: dependency.id.clone(),
+                    version_range: dependency.version_range.to_string(),
+                    locked: restored.version.clone(),
+                    latest_matching,
+                    latest,
+                });
+            }
+        }
+        Ok(outdated)
+    }
+}
+
+fn read_package(workspace: &Path) -> Result<PackageConfig, RpcError> {
+    if !PackageConfig::exists(workspace) {
+        return Err(api_error(api::Error::MissingPackage(
+            workspace.to_path_buf(),
+        )));
+    }
+    Ok(PackageConfig::read(workspace)?)
+}
+
+/// Missing params are treated like empty ones
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = match params {
+        Value::Null => json!({}),
+        params => params,
+    };
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn to_value(value: impl Serialize) -> Result<Value, RpcError> {
+    Ok(serde_json::to_value(value).map_err(color_eyre::Report::from)?)
+}
+
+/// Tags the error with its kind, so clients can e.g. offer to restore when the lock is missing
+fn api_error(error: api::Error) -> RpcError {
+    let kind = match &error {
+        api::Error::MissingPackage(_) => Some("missingPackage"),
+        api::Error::MissingLock(_) => Some("missingLock"),
+        api::Error::LockOutOfDate(_) => Some("lockOutOfDate"),
+        api::Error::DownloadRequired(_) => Some("downloadRequired"),
+        api::Error::NoNdkRequirement => Some("noNdkRequirement"),
+        api::Error::NdkNotInstalled { .. } => Some("ndkNotInstalled"),
+        api::Error::NdkMismatch { .. } => Some("ndkMismatch"),
+        api::Error::ScriptFailed { .. } => Some("scriptFailed"),
+        api::Error::Other(_) => None,
+    };
+
+    let mut rpc_error = RpcError::from(error.into_report());
+    rpc_error.data = kind.map(|kind| json!({ "kind": kind }));
+    rpc_error
+}
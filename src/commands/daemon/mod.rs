@@ -0,0 +1,172 @@
+//! `qpm daemon` answers JSON-RPC 2.0 requests on stdin so editors don't pay
+//! for a fresh process and a cold repository cache on every action.
+//!
+//! Messages are either one JSON object per line or framed with `Content-Length`
+//! headers like the language server protocol, responses use the framing of the last request.
+//!
+//! Methods:
+//! - `version`
+//! - `packages`, `versions { id }`, `search { query, limit? }`
+//! - `resolve { workspace? }` resolves without writing anything
+//! - `restore { workspace?, update?, frozen?, dryRun? }`
+//! - `outdated { workspace? }` lists locked dependencies with newer versions
+//! - `ndk/list`
+//! - `qmod/manifest { workspace?, excludeLibs?, includeLibs? }`
+//! - `refresh` drops the cached repository metadata
+//! - `shutdown`/`exit`
+//!
+//! While a request runs, qpm's log messages are sent as `progress` notifications
+//! with `{ id, level, message }`.
+
+use std::{
+    io::{self, BufRead, Write},
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+};
+
+use clap::Args;
+use color_eyre::{Result, eyre::Context};
+use serde_json::{Value, json};
+
+use crate::terminal::logging;
+
+use super::Command;
+
+mod methods;
+pub mod rpc;
+
+pub use methods::Daemon;
+
+use rpc::{Framing, INVALID_REQUEST, PARSE_ERROR, RpcError, SERVER_ERROR};
+
+#[derive(Args, Debug, Clone)]
+pub struct DaemonCommand {
+    /// Only use the local cache
+    #[clap(long, default_value = "false")]
+    pub offline: bool,
+}
+
+impl Command for DaemonCommand {
+    fn execute(self) -> Result<()> {
+        // stdout belongs to the protocol
+        log::info!("qpm daemon listening on stdin");
+        serve(io::stdin().lock(), io::stdout(), self.offline)
+    }
+}
+
+struct Output {
+    writer: Box<dyn Write + Send>,
+    framing: Framing,
+}
+
+impl Output {
+    fn send(&mut self, message: &Value) -> Result<()> {
+        rpc::write_message(&mut self.writer, self.framing, message)
+            .context("Failed to write to client")
+    }
+}
+
+/// Answers requests read from `reader` on `writer` until the input closes or `shutdown` is called
+pub fn serve(
+    reader: impl BufRead,
+    writer: impl Write + Send + 'static,
+    offline: bool,
+) -> Result<()> {
+    let mut reader = reader;
+    let output = Arc::new(Mutex::new(Output {
+        writer: Box::new(writer),
+        framing: Framing::default(),
+    }));
+    let current_id: Arc<Mutex<Option<Value>>> = Arc::default();
+
+    {
+        let output = output.clone();
+        let current_id = current_id.clone();
+        logging::set_listener(Some(Box::new(move |level, message| {
+            let Some(id) = current_id.lock().ok().and_then(|id| id.clone()) else {
+                return;
+            };
+            let progress = rpc::notification(
+                "progress",
+                json!({ "id": id, "level": level.as_str().to_lowercase(), "message": message }),
+            );
+            if let Ok(mut output) = output.lock() {
+                let _ = output.send(&progress);
+            }
+        })));
+    }
+
+    let result = serve_loop(&mut reader, &output, &current_id, offline);
+    logging::set_listener(None);
+    result
+}
+
+fn serve_loop(
+    reader: &mut impl BufRead,
+    output: &Mutex<Output>,
+    current_id: &Mutex<Option<Value>>,
+    offline: bool,
+) -> Result<()> {
+    let send = |message: Value| -> Result<()> {
+        output
+            .lock()
+            .map_err(|_| color_eyre::eyre::eyre!("Output lock poisoned"))?
+            .send(&message)
+    };
+
+    let mut daemon = Daemon::new(offline);
+
+    while let Some((message, framing)) =
+        rpc::read_message(reader).context("Failed to read from client")?
+    {
+        if let Ok(mut output) = output.lock() {
+            output.framing = framing;
+        }
+
+        let request: Value = match serde_json::from_str(&message) {
+            Ok(request) => request,
+            Err(e) => {
+                let error = RpcError::new(PARSE_ERROR, e.to_string());
+                send(rpc::response(Value::Null, Err(error)))?;
+                continue;
+            }
+        };
+
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            let error = RpcError::new(INVALID_REQUEST, "Missing method");
+            send(rpc::response(id.unwrap_or_default(), Err(error)))?;
+            continue;
+        };
+        let params = request.get("params").cloned().unwrap_or_default();
+
+        if matches!(method, "shutdown" | "exit") {
+            if let Some(id) = id {
+                send(rpc::response(id, Ok(Value::Null)))?;
+            }
+            break;
+        }
+
+        if let Ok(mut current) = current_id.lock() {
+            current.clone_from(&id);
+        }
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| daemon.handle(method, params)))
+            .unwrap_or_else(|_| {
+                // the repository may be half updated
+                daemon.refresh();
+                Err(RpcError::new(SERVER_ERROR, format!("{method} panicked")))
+            });
+
+        if let Ok(mut current) = current_id.lock() {
+            *current = None;
+        }
+
+        // notifications are not answered
+        if let Some(id) = id {
+            send(rpc::response(id, result))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+//! JSON-RPC 2.0 messages and their framing on stdio
+
+use std::io::{self, BufRead, Read, Write};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::terminal::colors::strip_colors;
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// Any failure of qpm itself, `data.kind` tells [`crate::api::Error`]s apart
+pub const SERVER_ERROR: i64 = -32000;
+
+/// How messages are delimited, answered in the same way the client started with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Framing {
+    /// One message per line
+    #[default]
+    Lines,
+    /// `Content-Length` headers like the language server protocol, as used by `vscode-jsonrpc`
+    Headers,
+}
+
+/// Reads the next message, `None` once the input is closed
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<(String, Framing)>> {
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let Some(length) = header_value(line, "content-length") else {
+            return Ok(Some((line.to_string(), Framing::Lines)));
+        };
+        let length: usize = length
+            .parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid Content-Length"))?;
+
+        // skip the remaining headers
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+        }
+
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        let body = String::from_utf8(body)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Message is not UTF-8"))?;
+        return Ok(Some((body, Framing::Headers)));
+    }
+}
+
+fn header_value<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let (key, value) = line.split_once(':')?;
+    key.trim()
+        .eq_ignore_ascii_case(name)
+        .then_some(value.trim())
+}
+
+/// Writes `message` framed as `framing`
+pub fn write_message(writer: &mut impl Write, framing: Framing, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    match framing {
+        Framing::Lines => writeln!(writer, "{body}")?,
+        Framing::Headers => write!(writer, "Content-Length: {}\r\n\r\n{body}", body.len())?,
+    }
+    writer.flush()
+}
+
+/// Error answered instead of a result
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<color_eyre::Report> for RpcError {
+    fn from(report: color_eyre::Report) -> Self {
+        RpcError::new(SERVER_ERROR, strip_colors(&format!("{report:#}")))
+    }
+}
+
+pub fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    }
+}
+
+pub fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
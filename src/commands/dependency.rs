@@ -47,7 +47,7 @@ pub struct DependencyOperationAddArgs {
     pub id: String,
 
     /// optional version of the dependency that you want to add
-    #[clap(short, long)]
+    #[clap(long)]
     pub version: Option<VersionReq>,
 
     /// Additional data for the dependency (as a valid json object)
@@ -65,7 +65,7 @@ pub struct DependencyOperationDownloadArgs {
     pub id: String,
 
     /// version of the dependency that you want to download
-    #[clap(short, long)]
+    #[clap(long)]
     pub version: Option<Version>,
 
     /// Resolve all dependencies of the package
@@ -138,7 +138,7 @@ fn put_dependency(
     additional_data: Option<PackageDependencyModifier>,
     sort: bool,
 ) -> Result<()> {
-    println!(
+    log::info!(
         "Adding dependency with id {} and version {}",
         id.dependency_id_color(),
         version.dependency_version_color()
@@ -161,7 +161,7 @@ fn put_dependency(
     match existing_dep {
         // overwrite existing dep
         Some(existing_dep) => {
-            println!("Dependency already in qpm.json, updating!");
+            log::info!("Dependency already in qpm.json, updating!");
             *existing_dep = dep
         }
         // add dep
@@ -225,7 +225,7 @@ fn download_dependency(dependency_args: DependencyOperationDownloadArgs) -> Resu
         let resolved_deps = resolved_deps.1;
 
         for dep in resolved_deps {
-            println!(
+            log::info!(
                 "Pulling {}:{}",
                 dep.config.info.id.dependency_id_color(),
                 dep.config
@@ -247,7 +247,7 @@ fn download_dependency(dependency_args: DependencyOperationDownloadArgs) -> Resu
         repository.write_repo()?;
     }
 
-    println!(
+    log::info!(
         "Pulling {}:{}",
         dep.config.info.id.dependency_id_color(),
         dep.config
@@ -1,4 +1,4 @@
-use std::{env, fs::File};
+use std::{env, fs::File, path::PathBuf};
 
 use clap::Args;
 use color_eyre::{
@@ -7,6 +7,11 @@ use color_eyre::{
 };
 use owo_colors::OwoColorize;
 
+use crate::{
+    models::{output::DoctorOutput, schemas::SchemaLinks},
+    terminal::output,
+};
+
 use super::Command;
 
 // look_path returns a boolean indicating if the binary can be found in $PATH.
@@ -44,36 +49,68 @@ impl Command for DoctorCommand {
 
         let qpm = look_path("qpm")?;
 
+        if output::is_json() {
+            let ndk = File::open("./qpm.json")
+                .is_ok()
+                .then(|| {
+                    env::var("ANDROID_NDK_HOME").ok().or_else(|| {
+                        std::fs::read_to_string("./ndkpath.txt")
+                            .ok()
+                            .map(|s| s.trim().to_string())
+                    })
+                })
+                .flatten()
+                .map(PathBuf::from);
+            let ok = cmake && adb && qpm && ninja;
+
+            output::print_json(
+                SchemaLinks::OUTPUT_DOCTOR,
+                DoctorOutput {
+                    cmake,
+                    ninja,
+                    adb,
+                    qpm,
+                    ndk,
+                    ok,
+                },
+            )?;
+
+            if !ok {
+                bail!("Some functionality is missing")
+            }
+            return Ok(());
+        }
+
         if !cmake {
-            eprintln!(
+            log::warn!(
                 "CMake is not installed in path! Use winget or your OS package manager to install CMake."
             )
         } else {
-            println!("Cmake found!");
+            log::info!("Cmake found!");
         }
 
         if !ninja {
-            eprintln!(
+            log::warn!(
                 "Ninja is not installed in path! Use {} to download ninja",
                 "qpm download ninja".yellow()
             )
         } else {
-            println!("Ninja found!");
+            log::info!("Ninja found!");
         }
 
         if !qpm {
-            eprintln!("Qpm not found in path!")
+            log::warn!("Qpm not found in path!")
         } else {
-            println!("Qpm found!");
+            log::info!("Qpm found!");
         }
 
         if !adb {
-            eprintln!(
+            log::warn!(
                 "ADB not installed in path. Use {} to download ADB",
                 "qpm download adb".yellow()
             )
         } else {
-            println!("ADB found!");
+            log::info!("ADB found!");
         }
 
         if File::open("./qpm.json").is_ok() {
@@ -81,7 +118,7 @@ impl Command for DoctorCommand {
 
             match ndk_path {
                 Ok(ndk) => {
-                    println!("NDK {} found in path!", ndk);
+                    log::info!("NDK {} found in path!", ndk);
                 }
                 Err(err) => {
                     if File::open("./ndkpath.txt").is_err() {
@@ -95,7 +132,7 @@ impl Command for DoctorCommand {
         };
 
         if cmake && adb && qpm && ninja {
-            println!("{}", "Everything looks good!".green());
+            log::info!("{}", "Everything looks good!".green());
         } else {
             bail!("Some functionality is missing")
         }
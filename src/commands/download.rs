@@ -100,7 +100,7 @@ impl Command for Download {
             }
         }
 
-        println!(
+        log::info!(
             "Sucessfully downloaded and extracted {:?} into {:?}",
             download.cyan(),
             &final_path.file_path_color()
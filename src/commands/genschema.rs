@@ -2,7 +2,17 @@ use clap::Args;
 use color_eyre::eyre::Context;
 
 use crate::{
-    models::{config::UserConfig, schemas::SchemaLinks, toolchain::ToolchainData},
+    models::{
+        abi::PackageFileExtensions,
+        config::UserConfig,
+        output::{
+            AuditOutput, CacheListOutput, CollapseOutput, DoctorOutput, InfoOutput, LicensesOutput,
+            LintOutput, NdkAvailableOutput, NdkListOutput, PackageListOutput,
+            PackageVersionsOutput, SearchOutput, VersionCheckOutput,
+        },
+        schemas::SchemaLinks,
+        toolchain::ToolchainData,
+    },
     repository::local::FileRepository,
 };
 
@@ -27,6 +37,21 @@ impl Command for GenSchemaCommand {
         Self::write_schema::<UserConfig>(SchemaLinks::USER_CONFIG)?;
         Self::write_schema::<FileRepository>(SchemaLinks::FILE_REPOSITORY)?;
         Self::write_schema::<ToolchainData>(SchemaLinks::TOOLCHAIN_DATA)?;
+        Self::write_schema::<PackageFileExtensions>(SchemaLinks::PACKAGE_FILE_EXTENSIONS)?;
+
+        Self::write_schema::<PackageListOutput>(SchemaLinks::OUTPUT_PACKAGE_LIST)?;
+        Self::write_schema::<PackageVersionsOutput>(SchemaLinks::OUTPUT_PACKAGE_VERSIONS)?;
+        Self::write_schema::<CacheListOutput>(SchemaLinks::OUTPUT_CACHE_LIST)?;
+        Self::write_schema::<CollapseOutput>(SchemaLinks::OUTPUT_COLLAPSE)?;
+        Self::write_schema::<NdkListOutput>(SchemaLinks::OUTPUT_NDK_LIST)?;
+        Self::write_schema::<NdkAvailableOutput>(SchemaLinks::OUTPUT_NDK_AVAILABLE)?;
+        Self::write_schema::<DoctorOutput>(SchemaLinks::OUTPUT_DOCTOR)?;
+        Self::write_schema::<VersionCheckOutput>(SchemaLinks::OUTPUT_VERSION_CHECK)?;
+        Self::write_schema::<LintOutput>(SchemaLinks::OUTPUT_LINT)?;
+        Self::write_schema::<LicensesOutput>(SchemaLinks::OUTPUT_LICENSES)?;
+        Self::write_schema::<AuditOutput>(SchemaLinks::OUTPUT_AUDIT)?;
+        Self::write_schema::<SearchOutput>(SchemaLinks::OUTPUT_SEARCH)?;
+        Self::write_schema::<InfoOutput>(SchemaLinks::OUTPUT_INFO)?;
         Ok(())
     }
 }
@@ -0,0 +1,171 @@
+use clap::Args;
+use color_eyre::{
+    Result, Section,
+    eyre::{Context, eyre},
+};
+use itertools::Itertools;
+use owo_colors::OwoColorize;
+use semver::Version;
+
+use crate::{
+    models::{
+        output::{InfoDependency, InfoOutput},
+        schemas::SchemaLinks,
+    },
+    repository::{self, Repository},
+    terminal::{colors::QPMColor, output},
+};
+
+use super::Command;
+
+#[derive(Args, Debug, Clone)]
+pub struct InfoCommand {
+    /// Package to show, as `id` for the latest version or `id@version`
+    pub package: String,
+
+    #[clap(long, default_value = "false")]
+    pub offline: bool,
+}
+
+impl Command for InfoCommand {
+    fn execute(self) -> Result<()> {
+        let (id, version) = match self.package.split_once('@') {
+            Some((id, version)) => (
+                id,
+                Some(
+                    Version::parse(version)
+                        .with_context(|| format!("Invalid version {version}"))?,
+                ),
+            ),
+            None => (self.package.as_str(), None),
+        };
+
+        let repos = repository::default_repositories()?
+            .into_iter()
+            .filter(|r| !self.offline || !r.is_online())
+            .collect_vec();
+
+        let versions = repos
+            .iter()
+            .map(|r| r.get_package_versions(id))
+            .flatten_ok()
+            .flatten_ok()
+            .map_ok(|v| v.version)
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unique()
+            .sorted()
+            .rev()
+            .collect_vec();
+
+        let Some(latest) = versions.first() else {
+            return Err(eyre!("Package {} was not found", id.dependency_id_color()))
+                .with_suggestion(|| format!("Run qpm search {id} to find similar packages"));
+        };
+        let version = version.unwrap_or_else(|| latest.clone());
+
+        // the first repository that has the version serves it, like when restoring
+        let mut served = None;
+        for repo in &repos {
+            if let Some(package) = repo.get_package(id, &version)? {
+                served = Some((package, repo.name()));
+                break;
+            }
+        }
+        let Some((package, repository)) = served else {
+            return Err(eyre!(
+                "Version {} of {} was not found",
+                version.version_id_color(),
+                id.dependency_id_color()
+            ))
+            .with_suggestion(|| {
+                format!("Run qpm list versions {id} to see the available versions")
+            });
+        };
+
+        let config = package.config;
+        let data = config.info.additional_data;
+        let info = InfoOutput {
+            id: config.info.id,
+            name: config.info.name,
+            version,
+            versions,
+            url: config.info.url,
+            branch: data.branch_name,
+            headers_only: data.headers_only.unwrap_or(false),
+            static_linking: data.static_linking.unwrap_or(false),
+            so_link: data.so_link,
+            debug_so_link: data.debug_so_link,
+            static_link: data.static_link,
+            mod_link: data.mod_link,
+            dependencies: config
+                .dependencies
+                .into_iter()
+                .map(|d| InfoDependency {
+                    id: d.id,
+                    version_range: d.version_range.to_string(),
+                })
+                .collect(),
+            repository,
+        };
+
+        if output::is_json() {
+            return output::print_json(SchemaLinks::OUTPUT_INFO, info);
+        }
+
+        print_info(&info);
+        Ok(())
+    }
+}
+
+fn print_info(info: &InfoOutput) {
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+
+    crate::output!(
+        "{}:{} ({})",
+        info.id.dependency_id_color(),
+        info.version.version_id_color(),
+        info.name
+    );
+    crate::output!("  repository: {}", info.repository.bright_yellow());
+    if let Some(url) = &info.url {
+        crate::output!("  url: {}", url.download_file_name_color());
+    }
+    if let Some(branch) = &info.branch {
+        crate::output!("  branch: {branch}");
+    }
+    crate::output!("  headers only: {}", yes_no(info.headers_only));
+    crate::output!("  static linking: {}", yes_no(info.static_linking));
+
+    for (field, link) in [
+        ("soLink", &info.so_link),
+        ("debugSoLink", &info.debug_so_link),
+        ("staticLink", &info.static_link),
+        ("modLink", &info.mod_link),
+    ] {
+        if let Some(link) = link {
+            crate::output!("  {field}: {}", link.download_file_name_color());
+        }
+    }
+
+    if info.dependencies.is_empty() {
+        crate::output!("  dependencies: none");
+    } else {
+        crate::output!("  dependencies:");
+        for dep in &info.dependencies {
+            crate::output!(
+                "   - {} {}",
+                dep.id.dependency_id_color(),
+                dep.version_range.dependency_version_color()
+            );
+        }
+    }
+
+    crate::output!(
+        "  versions: {}",
+        info.versions
+            .iter()
+            .map(|v| v.to_string().bright_green().to_string())
+            .join(", ")
+    );
+}
@@ -0,0 +1,59 @@
+use clap::Args;
+use color_eyre::Result;
+use owo_colors::OwoColorize;
+use qpm_package::models::dependency::SharedPackageConfig;
+
+use crate::{
+    models::{
+        output::{LicensesOutput, PackageLicenseOutput},
+        package::PackageConfigExtensions,
+        schemas::SchemaLinks,
+    },
+    terminal::{colors::QPMColor, output},
+    utils::license::{self, PackageLicense},
+};
+
+use super::Command;
+
+#[derive(Args)]
+pub struct LicensesCommand {}
+
+impl Command for LicensesCommand {
+    fn execute(self) -> Result<()> {
+        let shared_package = SharedPackageConfig::read(".")?;
+        let licenses = license::collect_licenses(&shared_package)?;
+        license::warn_unknown_licenses(&licenses);
+
+        if output::is_json() {
+            let packages = licenses
+                .into_iter()
+                .map(|l| PackageLicenseOutput {
+                    license: l.license().map(str::to_string),
+                    id: l.id,
+                    version: l.version,
+                    files: l.files,
+                })
+                .collect();
+            return output::print_json(SchemaLinks::OUTPUT_LICENSES, LicensesOutput { packages });
+        }
+
+        for l in &licenses {
+            let files = l
+                .files
+                .iter()
+                .filter_map(|f| f.file_name())
+                .map(|f| f.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            crate::output!(
+                "{}:{} {} {}",
+                l.id.dependency_id_color(),
+                l.version.version_id_color(),
+                l.license().unwrap_or("unknown").bright_green(),
+                files.file_path_color()
+            );
+        }
+        Ok(())
+    }
+}
@@ -5,7 +5,9 @@ use owo_colors::OwoColorize;
 
 use crate::{
     commands::Command,
+    models::{output::PackageListOutput, schemas::SchemaLinks},
     repository::{self, Repository},
+    terminal::output,
 };
 
 #[derive(Args, Debug, Clone)]
@@ -21,15 +23,23 @@ impl Command for PackageListCommand {
             .into_iter()
             .sorted()
             .collect_vec();
+
+        if output::is_json() {
+            return output::print_json(
+                SchemaLinks::OUTPUT_PACKAGE_LIST,
+                PackageListOutput { packages: ids },
+            );
+        }
+
         if !ids.is_empty() {
-            println!(
+            crate::output!(
                 "Found {} packages on qpackages.com",
                 ids.len().bright_yellow()
             );
 
-            ids.chunks(5).for_each(|_id| println!("{_id:?}\n"));
+            ids.chunks(5).for_each(|_id| crate::output!("{_id:?}\n"));
         } else {
-            println!("qpackages.com returned 0 packages, is something wrong?");
+            log::warn!("qpackages.com returned 0 packages, is something wrong?");
         }
         Ok(())
     }
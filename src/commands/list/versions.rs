@@ -1,9 +1,12 @@
 use clap::Args;
+use itertools::Itertools;
 use owo_colors::OwoColorize;
 
 use crate::{
     commands::Command,
+    models::{output::PackageVersionsOutput, schemas::SchemaLinks},
     repository::{self, Repository},
+    terminal::output,
 };
 
 #[derive(Args, Debug, Clone)]
@@ -20,8 +23,29 @@ impl Command for PackageCommand {
     fn execute(self) -> color_eyre::Result<()> {
         let versions =
             repository::useful_default_new(self.offline)?.get_package_versions(&self.package)?;
+
+        if output::is_json() {
+            let versions = versions
+                .unwrap_or_default()
+                .into_iter()
+                .map(|v| v.version)
+                .sorted()
+                .rev();
+            let versions = match self.latest {
+                true => versions.take(1).collect(),
+                false => versions.collect(),
+            };
+
+            return output::print_json(
+                SchemaLinks::OUTPUT_PACKAGE_VERSIONS,
+                PackageVersionsOutput {
+                    id: self.package,
+                    versions,
+                },
+            );
+        }
         if self.latest {
-            println!(
+            crate::output!(
                 "The latest version for package {} is {}",
                 self.package.bright_red(),
                 versions
@@ -38,17 +62,17 @@ impl Command for PackageCommand {
 
         match &versions {
             Some(package_versions) => {
-                println!(
+                crate::output!(
                     "Package {} has {} versions on qpackages.com:",
                     self.package.bright_red(),
                     versions.as_ref().unwrap().len().bright_yellow()
                 );
                 for package_version in package_versions.iter().rev() {
-                    println!(" - {}", package_version.version.to_string().bright_green());
+                    crate::output!(" - {}", package_version.version.to_string().bright_green());
                 }
             }
             _ => {
-                println!(
+                crate::output!(
                     "Package {} either did not exist or has no versions on qpackages.com",
                     self.package.bright_red()
                 );
@@ -1,24 +1,35 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{ArgAction, Parser, Subcommand};
 use clap_complete::Shell;
 use color_eyre::Result;
 
+use crate::terminal::{colors::ColorChoice, output::OutputFormat};
+
+pub mod audit;
 pub mod cache;
 pub mod clear;
 pub mod collapse;
 pub mod config;
+pub mod daemon;
 pub mod dependency;
 pub mod doctor;
 pub mod download;
 pub mod genschema;
+pub mod info;
 pub mod install;
+pub mod licenses;
 pub mod list;
 pub mod ndk;
 pub mod package;
 pub mod publish;
 pub mod qmod;
 pub mod restore;
+pub mod sbom;
 pub mod scripts;
+pub mod search;
 pub mod version;
+pub mod yank;
 
 #[cfg(feature = "templatr")]
 pub mod templatr;
@@ -35,6 +46,26 @@ pub struct Opt {
     #[arg(long = "generate", value_enum)]
     pub generator: Option<Shell>,
 
+    /// Output format of query commands such as list, collapse and doctor
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Show debug output, repeat to include traces and output of libraries
+    #[arg(short, long, global = true, action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Only show warnings and errors
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// When to color output, `auto` respects NO_COLOR
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Write a full debug log to this file, useful for bug reports
+    #[arg(long, global = true)]
+    pub log_file: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Option<MainCommand>,
 }
@@ -86,6 +117,24 @@ pub enum MainCommand {
 
     Version(version::VersionCommand),
 
+    /// List the licenses of the restored dependencies
+    Licenses(licenses::LicensesCommand),
+    /// Generate a software bill of materials from qpm.shared.json
+    Sbom(sbom::SbomCommand),
+    /// Check the restored dependencies against an advisory database
+    Audit(audit::AuditCommand),
+    /// Fuzzy search packages by id and name
+    Search(search::SearchCommand),
+    /// Show the metadata of a package version
+    Info(info::InfoCommand),
+    /// Serve JSON-RPC over stdio for editor integrations
+    Daemon(daemon::DaemonCommand),
+
+    /// Mark a published version as broken on its registry
+    Yank(yank::YankCommand),
+    /// Undo a yank
+    Unyank(yank::UnyankCommand),
+
     #[command(hide = true)]
     GenSchema(genschema::GenSchemaCommand),
 }
@@ -110,6 +159,14 @@ impl Command for MainCommand {
             MainCommand::Add(add) => add.execute(),
             MainCommand::Scripts(s) => s.execute(),
             MainCommand::Version(v) => v.execute(),
+            MainCommand::Licenses(l) => l.execute(),
+            MainCommand::Sbom(s) => s.execute(),
+            MainCommand::Audit(a) => a.execute(),
+            MainCommand::Search(s) => s.execute(),
+            MainCommand::Info(i) => i.execute(),
+            MainCommand::Daemon(d) => d.execute(),
+            MainCommand::Yank(y) => y.execute(),
+            MainCommand::Unyank(y) => y.execute(),
             MainCommand::GenSchema(g) => g.execute(),
 
             #[cfg(feature = "templatr")]
@@ -1,31 +1,27 @@
-use std::{
-    fs::File,
-    path::{Path, PathBuf},
-};
+use std::path::Path;
 
 use clap::{Args, Subcommand};
 use color_eyre::{
     Result, Section,
-    eyre::{Context, bail, eyre},
+    eyre::{bail, eyre},
 };
 use itertools::Itertools;
 use owo_colors::OwoColorize;
 use qpm_package::models::package::PackageConfig;
 use semver::{Version, VersionReq};
-use std::io::Write;
 
 use crate::{
+    api::{self, NdkResolveOptions},
     models::{
         android_repo::{AndroidRepositoryManifest, RemotePackage},
         config::get_combine_config,
+        output::{AvailableNdk, InstalledNdk, NdkAvailableOutput, NdkListOutput},
         package::PackageConfigExtensions,
+        schemas::SchemaLinks,
     },
-    utils::{
-        android::{
-            download_ndk_version, get_android_manifest, get_ndk_str_versions,
-            get_ndk_str_versions_str,
-        },
-        ndk,
+    terminal::output,
+    utils::android::{
+        download_ndk_version, get_android_manifest, get_ndk_str_versions_str, range_match_ndk,
     },
 };
 
@@ -109,26 +105,6 @@ fn fuzzy_match_ndk<'a>(
     }
 }
 
-fn range_match_ndk<'a>(
-    manifest: &'a AndroidRepositoryManifest,
-    fuzzy_version_range: &VersionReq,
-) -> Result<(String, &'a RemotePackage)> {
-    // find version closest to specified
-    let ndks = get_ndk_str_versions(manifest);
-    let ndks_versions = ndks.keys().sorted().rev().collect_vec();
-    let matching_version_opt = ndks_versions
-        .iter()
-        .find(|probe| fuzzy_version_range.matches(probe));
-
-    match matching_version_opt {
-        Some(matching_version) => Ok((
-            matching_version.to_string(),
-            ndks.get(matching_version).unwrap(),
-        )),
-        None => bail!("Could not find any ndk version matching requirement {fuzzy_version_range}"),
-    }
-}
-
 impl Command for Ndk {
     fn execute(self) -> Result<()> {
         match self.op {
@@ -137,7 +113,7 @@ impl Command for Ndk {
 
                 let (_version, ndk) = fuzzy_match_ndk(&manifest, &d.version)?;
                 let path = download_ndk_version(ndk, !self.quiet)?;
-                println!("{}", path.display());
+                crate::output!("{}", path.display());
                 return Ok(());
             }
             NdkOperation::Available(a) => {
@@ -147,30 +123,49 @@ impl Command for Ndk {
                 let page_offset = a.page;
                 let skip = page_offset * amount_per_page;
 
-                println!("Page: {page_offset}");
-
-                get_ndk_str_versions_str(&manifest)
-                    .iter()
+                let page = get_ndk_str_versions_str(&manifest)
+                    .into_iter()
                     .sorted_by(|a, b| a.0.cmp(b.0))
                     .rev()
                     .skip(skip)
-                    .take(5)
-                    .for_each(|(v, p)| println!("{} -> {}", v.blue(), p.display_name.purple()))
+                    .take(amount_per_page)
+                    .collect_vec();
+
+                if output::is_json() {
+                    return output::print_json(
+                        SchemaLinks::OUTPUT_NDK_AVAILABLE,
+                        NdkAvailableOutput {
+                            page: page_offset,
+                            ndks: page
+                                .into_iter()
+                                .map(|(v, p)| AvailableNdk {
+                                    version: v.to_string(),
+                                    display_name: p.display_name.clone(),
+                                })
+                                .collect(),
+                        },
+                    );
+                }
+
+                crate::output!("Page: {page_offset}");
+
+                page.into_iter().for_each(|(v, p)| {
+                    crate::output!("{} -> {}", v.blue(), p.display_name.purple())
+                })
             }
             NdkOperation::List => {
-                let dir = get_combine_config().get_ndk_installed();
+                let installed = installed_ndks()?;
 
-                dir.into_iter()
-                    .try_collect::<_, Vec<_>, _>()?
-                    .into_iter()
-                    .filter(|p| p.path().is_dir())
-                    .for_each(|p| {
-                        println!(
-                            "{} -> {}",
-                            p.file_name().to_str().unwrap(),
-                            p.path().to_str().unwrap()
-                        )
-                    })
+                if output::is_json() {
+                    return output::print_json(
+                        SchemaLinks::OUTPUT_NDK_LIST,
+                        NdkListOutput { ndks: installed },
+                    );
+                }
+
+                installed
+                    .iter()
+                    .for_each(|ndk| crate::output!("{} -> {}", ndk.version, ndk.path.display()))
             }
             NdkOperation::Path(p) => {
                 let manifest = get_android_manifest()?;
@@ -188,7 +183,7 @@ impl Command for Ndk {
                     bail!("Path {} not found!", ndk_path.display().red());
                 }
 
-                println!("{}", ndk_path.display());
+                crate::output!("{}", ndk_path.display());
             }
             NdkOperation::Resolve(r) => do_resolve(r, self.quiet)?,
             NdkOperation::Pin(u) => do_pin(u)?,
@@ -198,6 +193,22 @@ impl Command for Ndk {
     }
 }
 
+/// NDKs installed in the configured NDK download path
+pub fn installed_ndks() -> Result<Vec<InstalledNdk>> {
+    let installed = get_combine_config()
+        .get_ndk_installed()
+        .into_iter()
+        .try_collect::<_, Vec<_>, _>()?
+        .into_iter()
+        .filter(|p| p.depth() > 0 && p.path().is_dir())
+        .map(|p| InstalledNdk {
+            version: p.file_name().to_string_lossy().to_string(),
+            path: p.into_path(),
+        })
+        .collect();
+    Ok(installed)
+}
+
 fn do_pin(u: PinArgs) -> Result<(), color_eyre::eyre::Error> {
     let version = match u.online {
         false => {
@@ -242,65 +253,32 @@ fn do_pin(u: PinArgs) -> Result<(), color_eyre::eyre::Error> {
 }
 
 fn do_resolve(r: ResolveArgs, quiet: bool) -> Result<(), color_eyre::eyre::Error> {
-    let package = PackageConfig::exists(".")
-        .then(|| PackageConfig::read("."))
-        .transpose()?;
-
-    let Some(package) = package else {
-        if r.ignore_missing {
-            return Ok(());
-        }
-        bail!("No package found in current directory")
-    };
+    if r.ignore_missing && !PackageConfig::exists(".") {
+        return Ok(());
+    }
 
-    let ndk_requirement = package.workspace.ndk.clone();
-    let Some(ndk_requirement) = ndk_requirement else {
-        bail!("No NDK requirement set in project")
+    let options = NdkResolveOptions {
+        download: r.download,
+        show_progress: !quiet,
     };
-
-    let ndk_installed_path_opt = ndk::resolve_ndk_version(&package);
-
-    let ndk_installed_path: PathBuf = match ndk_installed_path_opt {
-        // NDK Found, unwrap
-        Some(ndk_installed_path) => ndk_installed_path,
-        // download
-        None if r.download => {
-            let manifest = get_android_manifest()?;
-            let (_version, ndk) = range_match_ndk(&manifest, &ndk_requirement)?;
-
-            download_ndk_version(ndk, !quiet)?
-        }
-        // error
-        _ => {
-            let mut report = eyre!("No NDK version installed that satisfies {ndk_requirement}")
-                .note("-d/--download not set, not downloading!");
-
-            // look up a version suitable to work with
-            // allow this to work offline by handling safely
-            let manifest = get_android_manifest().ok();
-            let mut suggested_version = manifest
-                .as_ref()
-                .and_then(|manifest| range_match_ndk(manifest, &ndk_requirement).ok());
-
-            if let Some((suggested_version, _)) = &mut suggested_version {
-                report = report.suggestion(format!("qpm ndk download {suggested_version}"));
-            }
-
-            return Err(report);
+    let ndk_installed_path = match api::resolve_ndk(Path::new("."), &options) {
+        Ok(path) => path,
+        Err(e @ api::Error::NdkNotInstalled { .. }) => {
+            return Err(e
+                .into_report()
+                .note("-d/--download not set, not downloading!"));
         }
+        Err(api::Error::MissingPackage(_)) => bail!("No package found in current directory"),
+        Err(e) => return Err(e.into_report()),
     };
 
-    // apply the NDK to the environment
-    apply_ndk(&ndk_installed_path)?;
-
+    crate::output!("{}", ndk_installed_path.display());
     Ok(())
 }
 
 // apply NDK to project and write
 fn apply_ndk(ndk_installed_path: &Path) -> Result<(), color_eyre::eyre::Error> {
-    let mut ndk_file = File::create("./ndkpath.txt").context("Unable to open ndkpath.txt")?;
-    write!(ndk_file, "{}", ndk_installed_path.to_str().unwrap())?;
-    ndk_file.flush()?;
-    println!("{}", ndk_installed_path.to_str().unwrap());
+    api::write_ndk_path(Path::new("."), ndk_installed_path).map_err(api::Error::into_report)?;
+    crate::output!("{}", ndk_installed_path.to_str().unwrap());
     Ok(())
 }
@@ -0,0 +1,117 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use color_eyre::{
+    Result, Section,
+    eyre::{OptionExt, bail},
+};
+use owo_colors::OwoColorize;
+use qpm_package::{
+    extensions::package_metadata::PackageMetadataExtensions, models::package::PackageConfig,
+};
+use semver::Version;
+
+use crate::{
+    commands::Command,
+    models::{abi::Abi, package::PackageConfigExtensions},
+    repository::local::FileRepository,
+    terminal::colors::QPMColor,
+    utils::elf,
+};
+
+use super::api_diff::{ensure_version_bump, previous_release};
+
+#[derive(Args, Debug, Clone)]
+pub struct AbiCheckArgs {
+    /// Release binary of the current build, looked up in the qmod include dirs and build/ by default
+    pub binary: Option<PathBuf>,
+
+    /// Version to compare against, defaults to the latest published version before the current one
+    #[clap(long)]
+    pub against: Option<Version>,
+
+    #[clap(long, default_value = "false")]
+    pub offline: bool,
+}
+
+impl Command for AbiCheckArgs {
+    fn execute(self) -> Result<()> {
+        let package = PackageConfig::read(".")?;
+        if package.info.additional_data.headers_only.unwrap_or(false) {
+            bail!("Headers only packages have no binary to check");
+        }
+
+        let binary = match self.binary {
+            Some(binary) => binary,
+            None => find_binary(&package)?,
+        };
+
+        let previous = previous_release(&package, self.against.as_ref(), self.offline)?;
+        let previous_version = &previous.config.info.version;
+        let abi = Abi::primary();
+        let previous_binary = FileRepository::collect_abi_files_of_package(&previous.config, abi)?
+            .release_binary
+            .ok_or_eyre(format!(
+                "{}:{} has no release binary to compare against",
+                previous.config.info.id.dependency_id_color(),
+                previous_version.version_id_color()
+            ))?;
+
+        let old = elf::read_shared_object(&previous_binary, abi)?;
+        let new = elf::read_shared_object(&binary, abi)?;
+
+        let soname_changed = old.soname != new.soname;
+        if soname_changed {
+            crate::output!(
+                " {} SONAME {} -> {}",
+                "~".yellow(),
+                old.soname.as_deref().unwrap_or("<none>").bright_red(),
+                new.soname.as_deref().unwrap_or("<none>").bright_green()
+            );
+        }
+
+        let removed = old.exports.difference(&new.exports).collect::<Vec<_>>();
+        let added = new.exports.difference(&old.exports).collect::<Vec<_>>();
+
+        for symbol in &removed {
+            crate::output!(" {} {}", "-".red(), symbol.bright_red());
+        }
+        for symbol in &added {
+            log::debug!("Added symbol {symbol}");
+        }
+        crate::output!(
+            "{} symbols removed, {} added since {}",
+            removed.len(),
+            added.len(),
+            previous_version.version_id_color()
+        );
+
+        ensure_version_bump(
+            "ABI",
+            previous_version,
+            &package.info.version,
+            soname_changed || !removed.is_empty(),
+            !added.is_empty(),
+        )
+    }
+}
+
+/// Looks for the release binary of `package` where the build leaves it
+fn find_binary(package: &PackageConfig) -> Result<PathBuf> {
+    let so_name = package.info.get_so_name2();
+    let file_name = so_name.file_name().unwrap();
+
+    package
+        .workspace
+        .qmod_include_dirs
+        .iter()
+        .map(PathBuf::as_path)
+        .chain([Path::new("build")])
+        .map(|dir| dir.join(file_name))
+        .find(|path| path.exists())
+        .ok_or_eyre(format!(
+            "Unable to find {} in the qmod include dirs or build",
+            file_name.to_string_lossy().file_path_color()
+        ))
+        .with_suggestion(|| "Build the package first or pass the path of the binary")
+}
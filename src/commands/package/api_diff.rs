@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use color_eyre::{
+    Result, Section,
+    eyre::{OptionExt, anyhow, bail},
+};
+use owo_colors::OwoColorize;
+use qpm_package::models::{dependency::SharedPackageConfig, package::PackageConfig};
+use semver::Version;
+
+use crate::{
+    commands::Command,
+    models::package::PackageConfigExtensions,
+    repository::{self, Repository, local::FileRepository},
+    terminal::colors::QPMColor,
+    utils::{
+        cpp::{self, ApiChange},
+        version::VersionBump,
+    },
+};
+
+#[derive(Args, Debug, Clone)]
+pub struct ApiDiffArgs {
+    /// Version to compare against, defaults to the latest published version before the current one
+    #[clap(long)]
+    pub against: Option<Version>,
+
+    #[clap(long, default_value = "false")]
+    pub offline: bool,
+}
+
+impl Command for ApiDiffArgs {
+    fn execute(self) -> Result<()> {
+        let package = PackageConfig::read(".")?;
+        let previous = previous_release(&package, self.against.as_ref(), self.offline)?;
+        let previous_version = &previous.config.info.version;
+
+        let previous_files = FileRepository::collect_files_of_package(&previous.config)?;
+        let old = cpp::scan_headers(&previous_files.headers)?;
+        let new = cpp::scan_headers(&package.shared_dir)?;
+        let changes = cpp::diff_declarations(&old, &new);
+
+        if changes.is_empty() {
+            crate::output!(
+                "No API changes since {}",
+                previous_version.version_id_color()
+            );
+        }
+        for change in &changes {
+            print_change(change);
+        }
+
+        let breaking = changes.iter().any(ApiChange::is_breaking);
+        let additions = changes.iter().any(|c| matches!(c, ApiChange::Added(_)));
+        ensure_version_bump(
+            "API",
+            previous_version,
+            &package.info.version,
+            breaking,
+            additions,
+        )
+    }
+}
+
+/// Fails if going from `previous` to `current` is not a big enough release
+/// for `breaking` changes or `additions` of `what`
+pub(crate) fn ensure_version_bump(
+    what: &str,
+    previous: &Version,
+    current: &Version,
+    breaking: bool,
+    additions: bool,
+) -> Result<()> {
+    let required = VersionBump::required(previous, breaking, additions);
+
+    let Some(actual) = VersionBump::between(previous, current) else {
+        bail!(
+            "Version {} is not newer than {}",
+            current.version_id_color(),
+            previous.version_id_color()
+        );
+    };
+
+    if actual < required {
+        return Err(anyhow!(
+            "{what} changes since {} require a {required} release, but {} is a {actual} release",
+            previous.version_id_color(),
+            current.version_id_color()
+        ))
+        .with_suggestion(|| {
+            format!(
+                "Bump the version to {}",
+                required.apply(previous).version_id_color()
+            )
+        });
+    }
+
+    log::info!(
+        "{} is a {actual} release, compatible with the {what} changes since {}",
+        current.version_id_color(),
+        previous.version_id_color()
+    );
+    Ok(())
+}
+
+fn print_change(change: &ApiChange) {
+    let header = |path: &PathBuf| path.display().file_path_color().to_string();
+    match change {
+        ApiChange::Removed(d) => crate::output!(
+            " {} {} {} ({})\n     {}",
+            "-".red(),
+            d.kind,
+            d.name.bright_red(),
+            header(&d.header),
+            d.signature
+        ),
+        ApiChange::Changed { old, new } => crate::output!(
+            " {} {} {} ({})\n     {}\n  -> {}",
+            "~".yellow(),
+            new.kind,
+            new.name.yellow(),
+            header(&new.header),
+            old.signature,
+            new.signature
+        ),
+        ApiChange::Added(d) => crate::output!(
+            " {} {} {} ({})",
+            "+".green(),
+            d.kind,
+            d.name.bright_green(),
+            header(&d.header)
+        ),
+    }
+}
+
+/// Finds `against` or the latest published version before the current one
+/// and makes sure it is in the cache
+pub(crate) fn previous_release(
+    package: &PackageConfig,
+    against: Option<&Version>,
+    offline: bool,
+) -> Result<SharedPackageConfig> {
+    let mut repo = repository::useful_default_new(offline)?;
+    let id = &package.info.id;
+
+    let version = match against {
+        Some(version) => version.clone(),
+        None => repo
+            .get_package_versions(id)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|v| v.version)
+            .filter(|v| v < &package.info.version)
+            .max()
+            .ok_or_eyre(format!(
+                "No published version of {} before {} to compare against",
+                id.dependency_id_color(),
+                package.info.version.version_id_color()
+            ))
+            .with_suggestion(|| "Pick a version explicitly with --against")?,
+    };
+
+    let previous = repo.get_package(id, &version)?.ok_or_eyre(format!(
+        "Package {}:{} not found",
+        id.dependency_id_color(),
+        version.version_id_color()
+    ))?;
+
+    repo.download_to_cache(&previous.config)?;
+
+    Ok(previous)
+}
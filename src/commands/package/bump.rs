@@ -0,0 +1,170 @@
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::Path,
+    str::FromStr,
+};
+
+use clap::Args;
+use color_eyre::{
+    Result,
+    eyre::{Context, bail},
+};
+use qpm_package::models::package::PackageConfig;
+use qpm_qmod::models::mod_json::ModJson;
+use semver::Version;
+use serde_json::Value;
+
+use crate::{
+    commands::Command,
+    models::{mod_json::ModJsonExtensions, package::PackageConfigExtensions},
+    terminal::colors::QPMColor,
+    utils::{
+        json,
+        version::{VersionBump, next_prerelease},
+    },
+};
+
+use super::format::reserialize_package_with;
+
+/// Which version to release next
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BumpTarget {
+    Major,
+    Minor,
+    Patch,
+    Prerelease,
+    Version(Version),
+}
+
+impl FromStr for BumpTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "major" => Ok(BumpTarget::Major),
+            "minor" => Ok(BumpTarget::Minor),
+            "patch" => Ok(BumpTarget::Patch),
+            "prerelease" => Ok(BumpTarget::Prerelease),
+            _ => Version::parse(s)
+                .map(BumpTarget::Version)
+                .map_err(|e| format!("expected major, minor, patch, prerelease or a version: {e}")),
+        }
+    }
+}
+
+impl BumpTarget {
+    pub fn apply(&self, version: &Version, preid: Option<&str>) -> Result<Version> {
+        let next = match self {
+            BumpTarget::Major => VersionBump::Major.apply(version),
+            BumpTarget::Minor => VersionBump::Minor.apply(version),
+            BumpTarget::Patch => VersionBump::Patch.apply(version),
+            BumpTarget::Prerelease => next_prerelease(version, preid)?,
+            BumpTarget::Version(next) => next.clone(),
+        };
+
+        if next <= *version {
+            bail!(
+                "{} is not newer than the current version {}",
+                next.version_id_color(),
+                version.version_id_color()
+            );
+        }
+        Ok(next)
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct BumpArgs {
+    /// major, minor, patch, prerelease or an explicit version
+    pub target: BumpTarget,
+
+    /// Pre-release identifier for prerelease bumps, e.g. beta for 1.0.0-beta.0
+    #[clap(long)]
+    pub preid: Option<String>,
+
+    /// Create an annotated git tag for the new version on HEAD, the bumped files are not committed
+    #[clap(long, default_value = "false")]
+    pub tag: bool,
+
+    /// Prefix of the tag name
+    #[clap(long, default_value = "v", requires = "tag")]
+    pub tag_prefix: String,
+}
+
+impl Command for BumpArgs {
+    fn execute(self) -> Result<()> {
+        let package = PackageConfig::read(".")?;
+        let previous = package.info.version.clone();
+        let version = self.target.apply(&previous, self.preid.as_deref())?;
+
+        // the lock keeps a copy of the config
+        reserialize_package_with(false, |package| package.info.version = version.clone())?;
+
+        let template_path = ModJson::get_template_path();
+        if template_path.exists() {
+            bump_template(&template_path, &version)?;
+        }
+
+        log::info!(
+            "Bumped {} from {} to {}",
+            package.info.id.dependency_id_color(),
+            previous.version_id_color(),
+            version.version_id_color()
+        );
+
+        if self.tag {
+            let tag = format!("{}{version}", self.tag_prefix);
+            create_tag(&tag, &format!("Release {version}"))?;
+            log::info!("Created tag {} on HEAD", tag.version_id_color());
+            log::warn!("HEAD does not contain the bumped files, they are left for you to commit");
+        }
+
+        Ok(())
+    }
+}
+
+/// Sets the version of the mod template, keeping every other field as it is
+fn bump_template(path: &Path, version: &Version) -> Result<()> {
+    let file = File::open(path).with_context(|| format!("Unable to open {}", path.display()))?;
+    let mut template: Value = json::json_from_reader_fast(BufReader::new(file))
+        .with_context(|| format!("Unable to parse {}", path.display()))?;
+
+    match template.get("version").and_then(Value::as_str) {
+        // ${version} is filled in from qpm.json when building the qmod
+        Some(current) if current.contains("${") => return Ok(()),
+        Some(_) => template["version"] = Value::String(version.to_string()),
+        None => bail!("{} has no version", path.display().file_path_color()),
+    }
+
+    fs::write(path, serde_json::to_string_pretty(&template)?)
+        .with_context(|| format!("Unable to write {}", path.display()))
+}
+
+#[cfg(feature = "gitoxide")]
+fn create_tag(name: &str, message: &str) -> Result<()> {
+    use color_eyre::{Section, eyre::OptionExt};
+
+    let repo = gix::open(".")?;
+    let head = repo.head_id()?.detach();
+    let tagger = repo
+        .committer()
+        .transpose()?
+        .ok_or_eyre("No git committer configured")
+        .with_suggestion(|| "Set user.name and user.email in your git config")?;
+
+    repo.tag(
+        name,
+        head,
+        gix::object::Kind::Commit,
+        Some(tagger),
+        message,
+        gix::refs::transaction::PreviousValue::MustNotExist,
+    )?;
+    Ok(())
+}
+
+#[cfg(not(feature = "gitoxide"))]
+fn create_tag(_name: &str, _message: &str) -> Result<()> {
+    bail!("qpm was built without git support, create the tag with git instead");
+}
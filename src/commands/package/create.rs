@@ -43,11 +43,11 @@ pub struct PackageOperationCreateArgs {
 impl Command for PackageOperationCreateArgs {
     fn execute(self) -> color_eyre::Result<()> {
         if PackageConfig::exists(".") {
-            println!(
+            log::error!(
                 "{}",
                 "Package already existed, not creating a new package!".bright_red()
             );
-            println!(
+            log::error!(
                 "Did you try to make a package in the same directory as another, or did you not use a clean folder?"
             );
             return Ok(());
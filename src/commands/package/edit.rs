@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use clap::Args;
 use qpm_package::models::{dependency::SharedPackageConfig, package::PackageConfig};
 use semver::Version;
@@ -57,8 +59,9 @@ impl Command for EditArgs {
             shared_package.write(".")?;
 
             // HACK: Not sure if this is a proper way of doing this but it seems logical
-            write_define_cmake(&shared_package)?;
+            write_define_cmake(Path::new("."), &shared_package)?;
             write_extern_cmake(
+                Path::new("."),
                 &shared_package,
                 &repository::useful_default_new(self.offline)?,
             )?;
@@ -68,21 +71,21 @@ impl Command for EditArgs {
 }
 
 fn package_set_id(package: &mut PackageConfig, id: String) {
-    println!("Setting package id: {id}");
+    log::info!("Setting package id: {id}");
     package.info.id = id;
 }
 
 fn package_set_name(package: &mut PackageConfig, name: String) {
-    println!("Setting package name: {name}");
+    log::info!("Setting package name: {name}");
     package.info.name = name;
 }
 
 fn package_set_url(package: &mut PackageConfig, url: String) {
-    println!("Setting package url: {url}");
+    log::info!("Setting package url: {url}");
     package.info.url = Option::Some(url);
 }
 
 fn package_set_version(package: &mut PackageConfig, version: Version) {
-    println!("Setting package version: {version}");
+    log::info!("Setting package version: {version}");
     package.info.version = version;
 }
@@ -1,9 +1,14 @@
+use std::path::Path;
+
 use clap::{Args, Subcommand};
 use qpm_package::models::{dependency::SharedPackageConfig, package::PackageConfig};
 
 use crate::{
     commands::Command,
-    models::package::PackageConfigExtensions,
+    models::{
+        abi::{Abi, AbiLinks},
+        package::{PackageConfigExtensions, write_with_abi_links},
+    },
     repository::{self},
     utils::{
         cmake::{write_define_cmake, write_extern_cmake},
@@ -34,6 +39,14 @@ pub struct EditExtraArgs {
     #[clap(long = "debugSoLink")]
     pub debug_so_link: Option<String>,
 
+    /// Provide a static link for downloading the .a file of statically linked packages
+    #[clap(long = "staticLink")]
+    pub static_link: Option<String>,
+
+    /// ABI the so, debug so and static links are for, arm64-v8a sets the regular links
+    #[clap(long)]
+    pub abi: Option<Abi>,
+
     /// Provide an overridden name for the .so file
     #[clap(long = "overrideSoName")]
     pub override_so_name: Option<String>,
@@ -82,9 +95,31 @@ pub struct CompileOptionsEditArgs {
 }
 
 impl Command for EditExtraArgs {
-    fn execute(self) -> color_eyre::Result<()> {
+    fn execute(mut self) -> color_eyre::Result<()> {
         let mut package = PackageConfig::read(".")?;
+        let mut abi_links = AbiLinks::read_map(Path::new("."));
         let mut any_changed = false;
+        if let Some(abi) = self.abi.filter(|abi| *abi != Abi::Arm64V8a) {
+            let mut links = abi_links.get(&abi).cloned().unwrap_or_default();
+            if let Some(so_link) = self.so_link.take() {
+                log::info!("Setting {abi} so_link: {so_link:#?}");
+                links.so_link = Some(so_link);
+                any_changed = true;
+            }
+            if let Some(debug_so_link) = self.debug_so_link.take() {
+                log::info!("Setting {abi} debug_so_link: {debug_so_link:#?}");
+                links.debug_so_link = Some(debug_so_link);
+                any_changed = true;
+            }
+            if let Some(static_link) = self.static_link.take() {
+                log::info!("Setting {abi} static_link: {static_link:#?}");
+                links.static_link = Some(static_link);
+                any_changed = true;
+            }
+            if !links.is_empty() {
+                abi_links.insert(abi, links);
+            }
+        }
         if let Some(branch_name) = self.branch_name {
             package_edit_extra_branch_name(&mut package, branch_name);
             any_changed = true;
@@ -105,6 +140,10 @@ impl Command for EditExtraArgs {
             package_edit_extra_debug_so_link(&mut package, debug_so_link);
             any_changed = true;
         }
+        if let Some(static_link) = self.static_link {
+            package_edit_extra_static_link(&mut package, static_link);
+            any_changed = true;
+        }
         if let Some(mod_link) = self.mod_link {
             package_edit_extra_mod_link(&mut package, mod_link);
             any_changed = true;
@@ -119,14 +158,15 @@ impl Command for EditExtraArgs {
         }
 
         if any_changed {
-            package.write(".")?;
+            write_with_abi_links(&package, ".", &abi_links)?;
             let mut shared_package = SharedPackageConfig::read(".")?;
             shared_package.config = package;
             shared_package.write(".")?;
 
             // HACK: Not sure if this is a proper way of doing this but it seems logical
-            write_define_cmake(&shared_package)?;
+            write_define_cmake(Path::new("."), &shared_package)?;
             write_extern_cmake(
+                Path::new("."),
                 &shared_package,
                 &repository::useful_default_new(self.offline)?,
             )?;
@@ -136,41 +176,46 @@ impl Command for EditExtraArgs {
 }
 
 pub fn package_edit_extra_branch_name(package: &mut PackageConfig, branch_name: String) {
-    println!("Setting branch name: {branch_name:#?}");
+    log::info!("Setting branch name: {branch_name:#?}");
     package.info.additional_data.branch_name = Some(branch_name);
 }
 
 pub fn package_edit_extra_headers_only(package: &mut PackageConfig, headers_only: bool) {
-    println!("Setting headers_only: {headers_only:#?}");
+    log::info!("Setting headers_only: {headers_only:#?}");
     package.info.additional_data.headers_only = Some(headers_only);
 }
 
 pub fn package_edit_extra_static_linking(package: &mut PackageConfig, static_linking: bool) {
-    println!("Setting static_linking: {static_linking:#?}");
+    log::info!("Setting static_linking: {static_linking:#?}");
     package.info.additional_data.static_linking = Some(static_linking);
 }
 
 pub fn package_edit_extra_so_link(package: &mut PackageConfig, so_link: String) {
-    println!("Setting so_link: {so_link:#?}");
+    log::info!("Setting so_link: {so_link:#?}");
     package.info.additional_data.so_link = Some(so_link);
 }
 
+pub fn package_edit_extra_static_link(package: &mut PackageConfig, static_link: String) {
+    log::info!("Setting static_link: {static_link:#?}");
+    package.info.additional_data.static_link = Some(static_link);
+}
+
 pub fn package_edit_extra_mod_link(package: &mut PackageConfig, mod_link: String) {
-    println!("Setting mod_link: {mod_link:#?}");
+    log::info!("Setting mod_link: {mod_link:#?}");
     package.info.additional_data.mod_link = Some(mod_link);
 }
 
 pub fn package_edit_extra_debug_so_link(package: &mut PackageConfig, debug_so_link: String) {
-    println!("Setting debug_so_link: {debug_so_link:#?}");
+    log::info!("Setting debug_so_link: {debug_so_link:#?}");
     package.info.additional_data.debug_so_link = Some(debug_so_link);
 }
 
 pub fn package_edit_extra_override_so_name(package: &mut PackageConfig, override_so_name: String) {
-    println!("Setting override_so_name: {override_so_name:#?}");
+    log::info!("Setting override_so_name: {override_so_name:#?}");
     package.info.additional_data.override_so_name = Some(override_so_name);
 }
 
 pub fn package_edit_extra_sub_folder(package: &mut PackageConfig, sub_folder: String) {
-    println!("Setting sub_folder: {sub_folder:#?}");
+    log::info!("Setting sub_folder: {sub_folder:#?}");
     package.info.additional_data.sub_folder = Some(sub_folder);
 }
@@ -18,7 +18,13 @@ impl Command for FormatArgs {
 }
 
 pub fn reserialize_package(sort: bool) -> Result<()> {
+    reserialize_package_with(sort, |_| {})
+}
+
+/// [`reserialize_package`] that applies `edit` to the package and the copy of it in the lock file
+pub fn reserialize_package_with(sort: bool, edit: impl Fn(&mut PackageConfig)) -> Result<()> {
     let mut package = PackageConfig::read(".")?;
+    edit(&mut package);
 
     if sort {
         // Sort the dependencies by id
@@ -32,6 +38,7 @@ pub fn reserialize_package(sort: bool) -> Result<()> {
     if fs::exists("qpm.shared.json").unwrap_or(false) {
         // Read the shared package
         let mut shared_package = SharedPackageConfig::read(".")?;
+        edit(&mut shared_package.config);
 
         if sort {
             // Sort the dependencies by id
@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use clap::Args;
+use color_eyre::{Result, eyre::bail};
+use owo_colors::OwoColorize;
+use qpm_package::models::{dependency::SharedPackageConfig, package::PackageConfig};
+
+use crate::{
+    commands::Command,
+    models::{
+        output::{LintFinding, LintOutput, LintSeverity},
+        package::PackageConfigExtensions,
+        schemas::SchemaLinks,
+    },
+    terminal::output,
+    utils::lint,
+};
+
+#[derive(Args, Debug, Clone)]
+pub struct LintArgs {
+    /// Fix the findings that can be fixed automatically
+    #[clap(long, default_value = "false")]
+    pub fix: bool,
+}
+
+impl Command for LintArgs {
+    fn execute(self) -> Result<()> {
+        let root = Path::new(".");
+        let mut package = PackageConfig::read(root)?;
+        let shared = SharedPackageConfig::exists(root)
+            .then(|| SharedPackageConfig::read(root))
+            .transpose()?;
+
+        let findings = lint::lint_package(&package, shared.as_ref(), root);
+
+        let mut results = vec![];
+        for finding in findings {
+            let fixed = match &finding.fix {
+                Some(fix) if self.fix => {
+                    fix.apply(&mut package, root)?;
+                    true
+                }
+                _ => false,
+            };
+            results.push(LintFinding {
+                id: finding.id.to_string(),
+                severity: finding.severity,
+                message: finding.message,
+                fixable: finding.fix.is_some(),
+                fixed,
+            });
+        }
+
+        let fixed = results.iter().filter(|f| f.fixed).count();
+        if fixed > 0 {
+            package.write(root)?;
+            log::info!(
+                "Fixed {fixed} findings, run {} to update the restored dependencies",
+                "qpm restore".blue()
+            );
+        }
+
+        let errors = results
+            .iter()
+            .filter(|f| f.severity == LintSeverity::Error && !f.fixed)
+            .count();
+
+        if output::is_json() {
+            output::print_json(SchemaLinks::OUTPUT_LINT, LintOutput { findings: results })?;
+        } else {
+            for finding in &results {
+                print_finding(finding);
+            }
+            if results.is_empty() {
+                log::info!("No problems found");
+            }
+        }
+
+        if errors > 0 {
+            bail!("{errors} errors found");
+        }
+        Ok(())
+    }
+}
+
+fn print_finding(finding: &LintFinding) {
+    let severity = match finding.severity {
+        LintSeverity::Error => "error".bright_red().to_string(),
+        LintSeverity::Warning => "warning".bright_yellow().to_string(),
+    };
+    let status = if finding.fixed {
+        " (fixed)".green().to_string()
+    } else if finding.fixable {
+        " (fixable with --fix)".dimmed().to_string()
+    } else {
+        String::new()
+    };
+
+    crate::output!("{severity}[{}]: {}{status}", finding.id, finding.message);
+}
@@ -0,0 +1,133 @@
+use std::{fs, io::BufReader, path::Path};
+
+use clap::Args;
+use color_eyre::{Result, eyre::Context};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    commands::Command,
+    models::{
+        migration::{self, JsonChange, Migration},
+        package::{PACKAGE_FILE_NAME, PackageConfigExtensions, SHARED_PACKAGE_FILE_NAME},
+        schemas::{SchemaLinks, WithSchema},
+    },
+    terminal::colors::QPMColor,
+    utils::json,
+};
+
+#[derive(Args, Debug, Clone)]
+pub struct MigrateArgs {
+    /// Only show the changes, without writing anything
+    #[clap(long, default_value = "false")]
+    pub dry_run: bool,
+}
+
+impl Command for MigrateArgs {
+    fn execute(self) -> Result<()> {
+        let raw = read_raw(Path::new(PACKAGE_FILE_NAME))?;
+        let migrated = migration::migrate_package_config(raw.clone())?;
+        if self.report(
+            PACKAGE_FILE_NAME,
+            &raw,
+            &migrated,
+            SchemaLinks::PACKAGE_CONFIG,
+        )? {
+            backup(PACKAGE_FILE_NAME)?;
+            migrated.config.write(".")?;
+        }
+
+        if !Path::new(SHARED_PACKAGE_FILE_NAME).exists() {
+            return Ok(());
+        }
+
+        let raw = read_raw(Path::new(SHARED_PACKAGE_FILE_NAME))?;
+        let migrated = migration::migrate_shared_package_config(raw.clone())?;
+        if self.report(
+            SHARED_PACKAGE_FILE_NAME,
+            &raw,
+            &migrated,
+            SchemaLinks::SHARED_PACKAGE_CONFIG,
+        )? {
+            backup(SHARED_PACKAGE_FILE_NAME)?;
+            migrated.config.write(".")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl MigrateArgs {
+    /// Prints what the migration changes in `file`, returns whether it should be written
+    fn report<T: Serialize>(
+        &self,
+        file: &str,
+        raw: &Value,
+        migrated: &Migration<T>,
+        schema: &str,
+    ) -> Result<bool> {
+        let mut new = serde_json::to_value(WithSchema {
+            schema,
+            value: &migrated.config,
+        })?;
+        // writing keeps the abiLinks qpm_package does not know about
+        if let Some(abi_links) = raw.get("abiLinks") {
+            new["abiLinks"] = abi_links.clone();
+        }
+        let changes = migration::json_changes(raw, &new);
+        if changes.is_empty() {
+            log::info!("{} is up to date", file.file_path_color());
+            return Ok(false);
+        }
+
+        log::info!(
+            "Migrating {} from schema {}",
+            file.file_path_color(),
+            migrated
+                .from
+                .as_ref()
+                .map_or("<legacy>".to_string(), |v| v.to_string())
+                .version_id_color()
+        );
+        for note in &migrated.notes {
+            log::info!("  {note}");
+        }
+        for change in &changes {
+            print_change(change);
+        }
+
+        Ok(!self.dry_run)
+    }
+}
+
+fn print_change(change: &JsonChange) {
+    match change {
+        JsonChange::Removed { path, value } => {
+            crate::output!(" {} {path}: {}", "-".red(), value.bright_red())
+        }
+        JsonChange::Changed { path, old, new } => crate::output!(
+            " {} {path}: {} -> {}",
+            "~".yellow(),
+            old.bright_red(),
+            new.bright_green()
+        ),
+        JsonChange::Added { path, value } => {
+            crate::output!(" {} {path}: {}", "+".green(), value.bright_green())
+        }
+    }
+}
+
+fn read_raw(path: &Path) -> Result<Value> {
+    let file = fs::File::open(path).with_context(|| format!("{path:?} does not exist"))?;
+    json::json_from_reader_fast(BufReader::new(file))
+        .with_context(|| format!("Unable to parse {path:?}"))
+}
+
+/// Keeps the unmigrated file next to the original as `<file>.bak`
+fn backup(file: &str) -> Result<()> {
+    let backup = format!("{file}.bak");
+    fs::copy(file, &backup).with_context(|| format!("Unable to back up {file} to {backup}"))?;
+    log::info!("Wrote backup to {}", backup.file_path_color());
+    Ok(())
+}
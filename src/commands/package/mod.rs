@@ -2,10 +2,15 @@ use clap::{Args, Subcommand};
 
 use super::Command;
 
+pub(crate) mod abi_check;
+pub(crate) mod api_diff;
+pub mod bump;
 pub(crate) mod create;
 pub(crate) mod edit;
 pub(crate) mod edit_extra;
 pub(crate) mod format;
+pub(crate) mod lint;
+pub(crate) mod migrate;
 
 #[derive(Args, Debug, Clone)]
 
@@ -25,6 +30,16 @@ pub enum PackageOperation {
     EditExtra(edit_extra::EditExtraArgs),
     /// Re-serialize the qpm.json file
     Format(format::FormatArgs),
+    /// Compare the headers in sharedDir with a previous release and check the version bump
+    ApiDiff(api_diff::ApiDiffArgs),
+    /// Compare the exported symbols of the release binary with a previous release and check the version bump
+    AbiCheck(abi_check::AbiCheckArgs),
+    /// Bump the version in qpm.json, qpm.shared.json and mod.template.json
+    Bump(bump::BumpArgs),
+    /// Upgrade qpm.json and qpm.shared.json from older schema versions or the C# QPM layout
+    Migrate(migrate::MigrateArgs),
+    /// Check the package for common manifest mistakes
+    Lint(lint::LintArgs),
 }
 
 impl Command for PackageCommand {
@@ -34,6 +49,11 @@ impl Command for PackageCommand {
             PackageOperation::Edit(e) => e.execute(),
             PackageOperation::EditExtra(ee) => ee.execute(),
             PackageOperation::Format(f) => f.execute(),
+            PackageOperation::ApiDiff(a) => a.execute(),
+            PackageOperation::AbiCheck(a) => a.execute(),
+            PackageOperation::Bump(b) => b.execute(),
+            PackageOperation::Migrate(m) => m.execute(),
+            PackageOperation::Lint(l) => l.execute(),
         }
     }
 }
@@ -0,0 +1,140 @@
+use std::{env, fs, path::Path, process};
+
+use color_eyre::{
+    Result,
+    eyre::{Context, bail},
+};
+use itertools::Itertools;
+use qpm_package::{
+    extensions::package_metadata::PackageMetadataExtensions,
+    models::{dependency::SharedPackageConfig, package::PackageConfig},
+};
+use walkdir::WalkDir;
+
+use crate::{
+    models::{
+        abi::{Abi, AbiLinks},
+        package::{SHARED_PACKAGE_FILE_NAME, SharedPackageConfigExtensions},
+    },
+    repository::qpackages::QPMRepository,
+    terminal::colors::QPMColor,
+    utils::{cpp::HEADER_EXTENSIONS, elf},
+};
+
+/// Downloads `config` the same way dependents would and verifies the result
+pub fn preflight(config: &PackageConfig) -> Result<()> {
+    let base_path = env::temp_dir().join(format!(
+        "qpm-publish-check-{}-{}-{}",
+        config.info.id,
+        config.info.version,
+        process::id()
+    ));
+    if base_path.exists() {
+        fs::remove_dir_all(&base_path)?;
+    }
+
+    let result = verify_download(config, &base_path);
+
+    fs::remove_dir_all(&base_path)
+        .with_context(|| format!("Failed to remove {}", base_path.display()))?;
+
+    result
+}
+
+fn verify_download(config: &PackageConfig, base_path: &Path) -> Result<()> {
+    // fails if the branch or subfolder does not exist
+    // dependents on the Quest download the arm64-v8a binaries
+    let abi = Abi::default();
+    QPMRepository::download_package_to(config, base_path, &[abi]).with_context(|| {
+        format!(
+            "Unable to download {}:{} from {}",
+            config.info.id.dependency_id_color(),
+            config.info.version.version_id_color(),
+            config.info.url.as_deref().unwrap_or_default()
+        )
+    })?;
+
+    let src_path = base_path.join("src");
+    if !src_path.join(SHARED_PACKAGE_FILE_NAME).exists() {
+        bail!(
+            "Downloaded sources do not contain {}, is it committed on the published branch?",
+            SHARED_PACKAGE_FILE_NAME.file_path_color()
+        );
+    }
+    // dependents resolve with the downloaded lock file, not the one being published
+    let downloaded = SharedPackageConfig::read(&src_path)
+        .context("Downloaded sources contain an invalid lock file")?;
+    let downloaded_info = &downloaded.config.info;
+    if downloaded_info.id != config.info.id || downloaded_info.version != config.info.version {
+        bail!(
+            "Downloaded {} is for {}:{}, expected {}:{}. Was it committed after the version change?",
+            SHARED_PACKAGE_FILE_NAME.file_path_color(),
+            downloaded_info.id.dependency_id_color(),
+            downloaded_info.version.version_id_color(),
+            config.info.id.dependency_id_color(),
+            config.info.version.version_id_color()
+        );
+    }
+
+    let shared_path = src_path.join(&config.shared_dir);
+    let has_headers = shared_path.is_dir()
+        && WalkDir::new(&shared_path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .any(|e| {
+                e.file_type().is_file()
+                    && e.path()
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| HEADER_EXTENSIONS.contains(&ext))
+            });
+    if !has_headers {
+        bail!(
+            "sharedDir {} contains no headers in the downloaded sources",
+            config.shared_dir.display().file_path_color()
+        );
+    }
+
+    // dependents restoring other ABIs download the links of the published sources
+    let abis = Abi::ALL
+        .iter()
+        .copied()
+        .filter(|&abi| !AbiLinks::of(config, &src_path, abi).is_empty())
+        .collect_vec();
+    QPMRepository::download_package_to(config, base_path, &abis)?;
+
+    for abi in abis {
+        verify_binaries(config, &src_path, &base_path.join("lib"), abi)?;
+    }
+
+    Ok(())
+}
+
+/// Ensures the binaries linked for `abi` downloaded and are built for it
+fn verify_binaries(config: &PackageConfig, src_path: &Path, lib: &Path, abi: Abi) -> Result<()> {
+    let links = AbiLinks::of(config, src_path, abi);
+    let lib_path = abi.cache_libs_path(lib);
+    let so_name = config.info.get_so_name2();
+    let debug_so_name = so_name.with_extension("debug.so");
+    let binaries = [
+        (links.so_link, lib_path.join(&so_name)),
+        (
+            links.debug_so_link,
+            lib_path.join(debug_so_name.file_name().unwrap()),
+        ),
+    ];
+
+    for (link, path) in binaries {
+        let Some(link) = link else { continue };
+        if !path.exists() {
+            bail!(
+                "{abi} binary {} did not download",
+                link.download_file_name_color()
+            );
+        }
+        elf::ensure_elf(&path, abi)
+            .with_context(|| format!("Binary downloaded from {link} is unusable"))?;
+    }
+
+    Ok(())
+}
@@ -1,21 +1,38 @@
 use clap::Args;
-use color_eyre::eyre::{Context, anyhow, bail};
+use color_eyre::{
+    Section,
+    eyre::{anyhow, bail},
+};
 use owo_colors::OwoColorize;
 use qpm_package::models::{dependency::SharedPackageConfig, package::PackageConfig};
 
 use crate::{
-    models::{config::get_publish_keyring, package::PackageConfigExtensions},
-    repository::{Repository, qpackages::QPMRepository},
+    models::{config::get_registry_keyring, package::PackageConfigExtensions},
+    repository::{
+        self, Repository,
+        qpackages::QPMRepository,
+        registry::{self, DEFAULT_REGISTRY},
+    },
     terminal::colors::QPMColor,
 };
 
 use super::Command;
 
+pub mod check;
+
 #[derive(Args, Debug, Clone)]
 
 pub struct PublishCommand {
     /// the authorization header to use for publishing, if present
     pub publish_auth: Option<String>,
+
+    /// Download the package like dependents would and verify it, without publishing
+    #[clap(long, default_value = "false")]
+    pub check: bool,
+
+    /// Name of the registry to publish to, see qpm config registry
+    #[clap(long, default_value = DEFAULT_REGISTRY)]
+    pub registry: String,
 }
 
 impl Command for PublishCommand {
@@ -25,21 +42,28 @@ impl Command for PublishCommand {
             bail!("Package without url can not be published!");
         }
 
-        let qpackages = QPMRepository::default();
+        let registry_config = registry::get_registry(&self.registry)?;
+
+        // packages on qpackages may only depend on packages on qpackages
+        let dependency_repo: Box<dyn Repository> = match self.registry.as_str() {
+            DEFAULT_REGISTRY => Box::new(QPMRepository::default()),
+            _ => Box::new(repository::useful_default_new(false)?),
+        };
 
         let shared_package = SharedPackageConfig::read(".")?;
         let resolved_deps = &shared_package.restored_dependencies;
 
-        // check if all dependencies are available off of qpackages
+        // check if all dependencies are available to dependents
         for shared_dependency in resolved_deps {
-            match qpackages
+            match dependency_repo
                 .get_package(&shared_dependency.dependency.id, &shared_dependency.version)?
             {
                 Option::Some(_s) => {}
                 Option::None => {
                     bail!(
-                        "dependency {} was not available on qpackages in the given version range",
-                        &shared_dependency.dependency.id
+                        "dependency {} was not available on {} in the given version range",
+                        &shared_dependency.dependency.id,
+                        self.registry
                     );
                 }
             };
@@ -88,26 +112,38 @@ impl Command for PublishCommand {
             );
         }
 
-        // TODO: Implement a check that gets the repo and checks if the shared folder and subfolder exists, if not it throws an error and won't let you publish
-
-        if let Some(key) = &self.publish_auth {
-            QPMRepository::publish_package(&shared_package, key)?;
-        } else {
-            // Empty strings are None, you shouldn't be able to publish with a None
-            let publish_key = get_publish_keyring()
-                .ok_or_else(|| color_eyre::eyre::eyre!("Keyring unavailable, unable to retrieve publish key"))?;
-            QPMRepository::publish_package(
-                &shared_package,
-                &publish_key
-                    .get_password()
-                    .context("Unable to get stored publish key!")?,
-            )?;
+        if self.check {
+            check::preflight(&shared_package.config)?;
+            log::info!(
+                "Package {} v{} is ready to be published",
+                shared_package.config.info.id.dependency_id_color(),
+                shared_package.config.info.version.version_id_color()
+            );
+            return Ok(());
         }
 
-        println!(
-            "Package {} v{} published!",
+        let auth = self
+            .publish_auth
+            .or_else(|| get_registry_keyring(&self.registry).and_then(|e| e.get_password().ok()));
+
+        registry::publish(
+            &self.registry,
+            &registry_config,
+            &shared_package,
+            auth.as_deref(),
+        )
+        .with_suggestion(|| {
+            format!(
+                "Store a publish key with qpm config publish --registry {}",
+                self.registry
+            )
+        })?;
+
+        log::info!(
+            "Package {} v{} published to {}!",
             shared_package.config.info.id.dependency_id_color(),
-            shared_package.config.info.version.version_id_color()
+            shared_package.config.info.version.version_id_color(),
+            self.registry.dependency_id_color()
         );
 
         Ok(())
@@ -39,7 +39,7 @@ impl Command for QmodCommand {
         match self.op {
             QmodOperation::Create(q) => create::execute_qmod_create_operation(q),
             QmodOperation::Build(b) => {
-                println!(
+                log::info!(
                     "{} is deprecated, switch to {}",
                     "qpm qmod build".yellow(),
                     "qpm qmod manifest".green()
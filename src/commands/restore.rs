@@ -1,197 +1,174 @@
-use std::{env, fs::File, io::Read, path::Path};
+use std::path::Path;
 
 use clap::Args;
 
-use color_eyre::{
-    Section,
-    eyre::{ContextCompat, Result, bail, eyre},
-};
 use itertools::Itertools;
-use qpm_package::models::{dependency::SharedPackageConfig, package::PackageConfig};
-use semver::Version;
+use owo_colors::OwoColorize;
 
 use crate::{
-    models::{
-        config::get_combine_config,
-        package::{
-            PackageConfigExtensions, SHARED_PACKAGE_FILE_NAME, SharedPackageConfigExtensions,
-        },
-    },
-    repository::{self, Repository},
-    resolver::dependency,
+    api::{self, RestoreOptions, RestoreResult},
+    models::package::SHARED_PACKAGE_FILE_NAME,
+    repository::local::ExternChange,
     terminal::colors::QPMColor,
 };
 
 use super::Command;
 
+/// Exit code of `restore --frozen` when the lock file is missing, out of sync with qpm.json or would change
+pub const EXIT_LOCK_OUT_OF_DATE: i32 = 2;
+/// Exit code of `restore --frozen` when a dependency is not in the cache
+pub const EXIT_DOWNLOAD_REQUIRED: i32 = 3;
+/// Exit code of `restore --dry-run` when restoring would change something
+pub const EXIT_CHANGES_PENDING: i32 = 4;
+
 #[derive(Args, Default)]
 pub struct RestoreCommand {
-    #[clap(default_value = "false", long, short)]
+    #[clap(default_value = "false", long, short, conflicts_with = "frozen")]
     update: bool,
 
     #[clap(long, default_value = "false")]
     offline: bool,
-}
 
-#[cfg(feature = "gitoxide")]
-pub(crate) fn is_ignored() -> bool {
-    gix::open(".").is_ok_and(|r| {
-        let Ok(index) = r.index() else { return false };
-
-        let excludes = r.excludes(&index, None, Default::default());
-
-        excludes.is_ok_and(|mut attribute| {
-            attribute
-                .at_path(
-                    SHARED_PACKAGE_FILE_NAME,
-                    Some(gix::index::entry::Mode::FILE),
-                )
-                .is_ok_and(|e| e.is_excluded())
-        })
-    })
-}
+    /// Fail if the lock file would change (exit code 2) or anything needs to be downloaded (exit code 3)
+    #[clap(long, default_value = "false")]
+    frozen: bool,
 
-#[cfg(not(feature = "gitoxide"))]
-pub(crate) fn is_ignored() -> bool {
-    false
+    /// Print the resolution, downloads and file changes without applying them.
+    /// Exits with code 4 if restoring would change anything
+    #[clap(long, default_value = "false")]
+    dry_run: bool,
 }
 
 impl Command for RestoreCommand {
     fn execute(self) -> color_eyre::Result<()> {
-        let package = PackageConfig::read(".")?;
-        // optionally does not exist
-        let mut shared_package_opt = SharedPackageConfig::exists(".")
-            .then(|| SharedPackageConfig::read("."))
-            .transpose()?;
-
-        let mut repo = repository::useful_default_new(self.offline)?;
-
-        // only update if:
-        // manually
-        // no shared.qpm.json
-        // dependencies have been updated
-        let unlocked = self.update
-            || shared_package_opt.is_none()
-            || shared_package_opt.as_ref().is_some_and(|shared_package| {
-                shared_package.config.dependencies != package.dependencies
-            });
-
-        if !unlocked && is_ignored() {
-            eprintln!(
-                "It seems that the current repository has {SHARED_PACKAGE_FILE_NAME} ignored. "
-            );
-            eprintln!(
-                "Please commit it to avoid inconsistent dependency resolving. git add {SHARED_PACKAGE_FILE_NAME} --force"
-            );
-        }
-
-        if unlocked && env::var("CI") == Ok("true".to_string()) {
-            eprintln!("Running in CI and using unlocked resolve, this seems like a bug!");
-            eprintln!(
-                "Make sure {SHARED_PACKAGE_FILE_NAME} is not gitignore'd and is comitted in the repository"
-            );
-        }
-
-        let resolved_deps = match &mut shared_package_opt {
-            // locked resolve
-            // only if shared_package is Some() and locked
-            Some(shared_package) if !unlocked => {
-                // if the same, restore as usual
-                println!("Using lock file for restoring");
-
-                // update config
-                shared_package.config = package;
-                // make additional data use cached data
-                shared_package
-                    .restored_dependencies
-                    .iter_mut()
-                    .try_for_each(|d| -> color_eyre::Result<()> {
-                        let package = repo
-                            .get_package(&d.dependency.id, &d.version)
-                            .ok()
-                            .flatten()
-                            .with_context(|| {
-                                format!(
-                                    "Unable to fetch {}:{}",
-                                    d.dependency.id.dependency_id_color(),
-                                    d.version.version_id_color()
-                                )
-                            })?;
-                        d.dependency.additional_data = package.config.info.additional_data;
-                        Ok(())
-                    })?;
-                dependency::locked_resolve(shared_package, &repo)?.collect_vec()
-            }
-            // Unlocked resolve
-            _ => {
-                println!("Resolving packages");
-
-                let (spc_result, restored_deps) =
-                    SharedPackageConfig::resolve_from_package(package, &repo)?;
-                // update shared_package
-                shared_package_opt = Some(spc_result);
-
-                restored_deps
-            }
+        let options = RestoreOptions {
+            update: self.update,
+            offline: self.offline,
+            frozen: self.frozen,
+            dry_run: self.dry_run,
         };
 
-        // write the ndk path to a file if available
-        let _config = get_combine_config();
-
-        let shared_package = shared_package_opt.expect("SharedPackage is None somehow!");
+        let restored = match api::restore(Path::new("."), &options) {
+            Err(api::Error::LockOutOfDate(reason)) => exit_frozen(EXIT_LOCK_OUT_OF_DATE, &reason),
+            Err(api::Error::DownloadRequired(missing)) => exit_frozen(
+                EXIT_DOWNLOAD_REQUIRED,
+                &format!(
+                    "Dependencies are missing from the cache: {}",
+                    missing
+                        .iter()
+                        .map(|(id, version)| format!(
+                            "{}:{}",
+                            id.dependency_id_color(),
+                            version.version_id_color()
+                        ))
+                        .join(", ")
+                ),
+            ),
+            restored => restored.map_err(api::Error::into_report)?,
+        };
 
-        // always write to reflect config changes
-        dependency::restore(".", &shared_package, &resolved_deps, &mut repo)?;
-        shared_package.write(".")?;
+        if self.dry_run {
+            print_dry_run(&restored);
 
-        validate_ndk(&shared_package.config)?;
+            if restored.has_changes() {
+                std::process::exit(EXIT_CHANGES_PENDING);
+            }
+        }
 
         Ok(())
     }
 }
 
-pub fn validate_ndk(package: &PackageConfig) -> Result<()> {
-    let Some(ndk_req) = package.workspace.ndk.as_ref() else {
-        return Ok(());
-    };
-
-    let mut ndk_path_str = String::new();
+fn exit_frozen(code: i32, reason: &str) -> ! {
+    log::error!("{} {reason}", "Frozen restore failed:".bright_red());
+    std::process::exit(code);
+}
 
-    // early return, the file doesn't exist nothing to validate
-    let ndk_path = Path::new("./ndkpath.txt");
-    if ndk_path.exists() {
-        let mut ndk_file = File::open(ndk_path)?;
+/// Prints what a restore would do
+fn print_dry_run(restored: &RestoreResult) {
+    crate::output!("Resolution:");
+    for dep in &restored.resolved {
+        let locked_version = restored.original_lock.as_ref().and_then(|lock| {
+            lock.restored_dependencies
+                .iter()
+                .find(|d| d.dependency.id == dep.config.info.id)
+                .map(|d| &d.version)
+        });
+
+        match locked_version {
+            Some(v) if v == &dep.config.info.version => crate::output!(
+                " - {}:{}",
+                dep.config.info.id.dependency_id_color(),
+                dep.config.info.version.version_id_color()
+            ),
+            Some(v) => crate::output!(
+                " ~ {}:{} (locked {})",
+                dep.config.info.id.dependency_id_color(),
+                dep.config.info.version.version_id_color(),
+                v.alternate_dependency_version_color()
+            ),
+            None => crate::output!(
+                " + {}:{}",
+                dep.config.info.id.dependency_id_color(),
+                dep.config.info.version.version_id_color()
+            ),
+        }
+    }
 
-        ndk_file.read_to_string(&mut ndk_path_str)?;
-        // validate environment variable if possible
-    } else if let Some(ndk_path_env) =
-        std::env::var_os("ANDROID_NDK_HOME").or_else(|| std::env::var_os("ANDROID_NDK_LATEST_HOME"))
-    {
-        ndk_path_str = ndk_path_env.to_str().unwrap().to_string();
+    if restored.lock_changed {
+        crate::output!(
+            "{} would be written",
+            SHARED_PACKAGE_FILE_NAME.file_path_color()
+        );
     }
 
-    let ndk_path = Path::new(ndk_path_str.trim());
-    if ndk_path.is_empty() {
-        eprintln!("NDK Path is empty, skipping validate NDK version!");
-        return Ok(());
+    for path in &restored.generated_changes {
+        crate::output!("{} would be written", path.display().file_path_color());
     }
 
-    if !ndk_path.exists() {
-        bail!("NDK Path {} does not exist!", ndk_path.display());
+    crate::output!("Downloads:");
+    for dep in &restored.downloads {
+        crate::output!(
+            " + {}:{}",
+            dep.config.info.id.dependency_id_color(),
+            dep.config.info.version.version_id_color()
+        );
     }
 
-    let ndk_version_str = ndk_path.file_name().unwrap().to_str().unwrap();
-    let Ok(ndk_version) = Version::parse(ndk_version_str) else {
-        println!("Unable to validate {ndk_version_str} is a valid NDK version, skipping");
-        return Ok(());
+    // the cache must be complete to know what ends up in extern
+    let Some(changes) = &restored.extern_changes else {
+        crate::output!(
+            "File changes in {} will be known once downloads complete",
+            restored
+                .shared_package
+                .config
+                .dependencies_dir
+                .display()
+                .file_path_color()
+        );
+        return;
     };
 
-    if !ndk_req.matches(&ndk_version) {
-        return Err(
-            eyre!("NDK Version {ndk_version} does not satisfy {ndk_req}")
-                .suggestion("qpm ndk resolve".to_string()),
-        );
+    crate::output!("File changes:");
+    for change in changes {
+        match change {
+            ExternChange::Link {
+                source,
+                destination,
+            } => crate::output!(
+                " + {} -> {}",
+                destination.display().file_path_color(),
+                source.display()
+            ),
+            ExternChange::Copy {
+                source,
+                destination,
+            } => crate::output!(
+                " + {} <- {}",
+                destination.display().file_path_color(),
+                source.display()
+            ),
+            ExternChange::Remove(path) => crate::output!(" - {}", path.display().file_path_color()),
+        }
     }
-
-    Ok(())
 }
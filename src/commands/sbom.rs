@@ -0,0 +1,191 @@
+use std::{
+    collections::HashSet,
+    env, fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use clap::Args;
+use color_eyre::{Result, eyre::Context};
+use itertools::Itertools;
+use qpm_package::models::{
+    dependency::{SharedDependency, SharedPackageConfig},
+    extra::AdditionalPackageMetadata,
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    models::{
+        package::{PackageConfigExtensions, SharedPackageConfigExtensions},
+        sbom::{Sbom, SbomComponent, SbomStandard},
+    },
+    repository::{self, Repository, local::FileRepository},
+    terminal::colors::QPMColor,
+    utils::license,
+};
+
+use super::Command;
+
+#[derive(Args)]
+pub struct SbomCommand {
+    /// Standard of the generated SBOM, the global --format selects how query results are printed
+    #[clap(long, value_enum, default_value_t = SbomStandard::Cyclonedx)]
+    pub sbom_format: SbomStandard,
+
+    /// Write the SBOM to this file instead of stdout
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+
+    #[clap(long, default_value = "false")]
+    pub offline: bool,
+}
+
+impl Command for SbomCommand {
+    fn execute(self) -> Result<()> {
+        let shared_package = SharedPackageConfig::read(".")?;
+        let repo = repository::useful_default_new(self.offline)?;
+
+        // the same libraries qmod manifest puts into libraryFiles
+        let bundled: HashSet<&str> = shared_package
+            .qmod_libraries()
+            .into_iter()
+            .map(|dep| dep.dependency.id.as_str())
+            .collect();
+
+        let components = shared_package
+            .restored_dependencies
+            .iter()
+            .map(|restored| {
+                dependency_component(
+                    &repo,
+                    restored,
+                    bundled.contains(restored.dependency.id.as_str()),
+                )
+            })
+            .try_collect()?;
+
+        let config = &shared_package.config;
+        let root_license =
+            license::read_package_license(&config.info.id, &config.info.version, Path::new("."))?;
+        let root = SbomComponent {
+            id: config.info.id.clone(),
+            version: config.info.version.clone(),
+            url: config.info.url.clone(),
+            branch: config.info.additional_data.branch_name.clone(),
+            license: root_license.license().map(str::to_string),
+            binary_links: binary_links(&config.info.additional_data),
+            sha256: None,
+            bundled: false,
+            depends_on: config.dependencies.iter().map(|d| d.id.clone()).collect(),
+        };
+
+        let sbom = Sbom {
+            root,
+            components,
+            timestamp: timestamp(),
+        };
+        let json = serde_json::to_string_pretty(&sbom.render(self.sbom_format))?;
+
+        match self.output {
+            Some(path) => {
+                fs::write(&path, json)
+                    .with_context(|| format!("Unable to write {}", path.display()))?;
+                log::info!("Wrote SBOM to {}", path.display().file_path_color());
+            }
+            None => crate::output!("{json}"),
+        }
+        Ok(())
+    }
+}
+
+fn dependency_component(
+    repo: &impl Repository,
+    restored: &SharedDependency,
+    bundled: bool,
+) -> Result<SbomComponent> {
+    let id = &restored.dependency.id;
+    let package = repo.get_package(id, &restored.version)?;
+    if package.is_none() {
+        log::warn!(
+            "{}:{} is not cached, its url and dependencies are unknown",
+            id.dependency_id_color(),
+            restored.version.version_id_color()
+        );
+    }
+
+    // the lock keeps the additional data of the restored version
+    let additional_data = &restored.dependency.additional_data;
+    let license = license::dependency_license(restored)?;
+    // the binary a qmod ships, debug binaries and other ABIs are not hashed
+    let sha256 = package
+        .as_ref()
+        .and_then(|p| FileRepository::collect_files_of_package(&p.config).ok())
+        .and_then(|files| files.release_binary)
+        .filter(|binary| binary.exists())
+        .map(|binary| sha256_file(&binary))
+        .transpose()?;
+
+    Ok(SbomComponent {
+        id: id.clone(),
+        version: restored.version.clone(),
+        url: package.as_ref().and_then(|p| p.config.info.url.clone()),
+        branch: additional_data.branch_name.clone(),
+        license: license.license().map(str::to_string),
+        binary_links: binary_links(additional_data),
+        sha256,
+        bundled,
+        depends_on: package
+            .iter()
+            .flat_map(|p| &p.config.dependencies)
+            .map(|d| d.id.clone())
+            .collect(),
+    })
+}
+
+fn binary_links(additional_data: &AdditionalPackageMetadata) -> Vec<(&'static str, String)> {
+    [
+        ("soLink", &additional_data.so_link),
+        ("debugSoLink", &additional_data.debug_so_link),
+        ("staticLink", &additional_data.static_link),
+        ("modLink", &additional_data.mod_link),
+    ]
+    .into_iter()
+    .filter_map(|(field, link)| Some((field, link.clone()?)))
+    .collect()
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let data = fs::read(path).with_context(|| format!("Unable to read {}", path.display()))?;
+    Ok(format!("{:x}", Sha256::digest(data)))
+}
+
+/// Current UTC time in RFC 3339, or `SOURCE_DATE_EPOCH` for reproducible builds
+fn timestamp() -> String {
+    let secs = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs())
+        });
+
+    // days since the epoch to a civil date, see https://howardhinnant.github.io/date_algorithms.html
+    let (days, rem) = (secs / 86400, secs % 86400);
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
@@ -1,13 +1,11 @@
-use std::process::{Stdio, exit};
+use std::{path::Path, process::exit};
 
 use clap::Args;
 
-use color_eyre::eyre::{anyhow, bail};
-use itertools::Itertools;
-use qpm_arg_tokenizer::arg::Expression;
+use color_eyre::eyre::bail;
 use qpm_package::models::package::PackageConfig;
 
-use crate::{models::package::PackageConfigExtensions, utils::ndk};
+use crate::{models::package::PackageConfigExtensions, utils::scripts};
 
 use super::Command;
 
@@ -42,60 +40,15 @@ impl Command for ScriptsCommand {
     }
 }
 
+/// Runs a script in the current directory, exiting with its exit code if it fails
 pub fn invoke_script(
     script_commands: &[String],
     supplied_args: &[String],
     package: &PackageConfig,
 ) -> Result<(), color_eyre::eyre::Error> {
-    let android_ndk_home = ndk::resolve_ndk_version(package);
-
-    for command_str in script_commands {
-        let split = command_str.split_once(' ');
-
-        let exec = match split {
-            Some(s) => s.0,
-            None => command_str,
-        };
-
-        let args: Vec<String> = match split {
-            Some(s) => {
-                let expression = s.1;
-                let tokenized_args = Expression::parse(expression);
-
-                let formatted_args = tokenized_args
-                    .replace(
-                        supplied_args
-                            .iter()
-                            .map(|s| s.as_str())
-                            .collect_vec()
-                            .as_slice(),
-                    )
-                    .map_err(|e| anyhow!("{}", e))?;
-
-                formatted_args
-                    .split(' ')
-                    .map(|s| s.to_string())
-                    .filter(|s| s.trim() != "")
-                    .collect_vec()
-            }
-            None => vec![],
-        };
-
-        let mut c = std::process::Command::new(exec);
-        c.args(args)
-            .stdin(Stdio::inherit())
-            .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit());
-
-        // Set the environment variable for Android NDK home if provided
-        if let Some(path) = &android_ndk_home {
-            c.env("ANDROID_NDK_HOME", path);
-        }
-
-        let code = c.spawn()?.wait()?.code().unwrap_or_else(|| 1);
-        if code != 0 {
-            exit(code);
-        }
+    let code = scripts::run_script(script_commands, supplied_args, package, Path::new("."))?;
+    if code != 0 {
+        exit(code);
     }
     Ok(())
 }
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use clap::Args;
+use color_eyre::Result;
+use itertools::Itertools;
+use owo_colors::OwoColorize;
+
+use crate::{
+    models::{
+        output::{SearchOutput, SearchResult},
+        schemas::SchemaLinks,
+    },
+    repository::{self, Repository, local::FileRepository},
+    terminal::{colors::QPMColor, output},
+    utils::fuzzy,
+};
+
+use super::Command;
+
+#[derive(Args, Debug, Clone)]
+pub struct SearchCommand {
+    /// Text to fuzzy match against package ids and names
+    pub query: String,
+
+    /// Maximum number of results
+    #[clap(short, long, default_value_t = 20)]
+    pub limit: usize,
+
+    #[clap(long, default_value = "false")]
+    pub offline: bool,
+}
+
+impl Command for SearchCommand {
+    fn execute(self) -> Result<()> {
+        let repo = repository::useful_default_new(self.offline)?;
+
+        let results = search_packages(&repo, &self.query, self.limit)?;
+
+        if output::is_json() {
+            return output::print_json(SchemaLinks::OUTPUT_SEARCH, SearchOutput { results });
+        }
+
+        if results.is_empty() {
+            crate::output!("No packages match {}", self.query.bright_red());
+            return Ok(());
+        }
+
+        for result in &results {
+            let version = result
+                .latest_version
+                .as_ref()
+                .map(|v| format!(":{}", v.version_id_color()))
+                .unwrap_or_default();
+            let name = result
+                .name
+                .as_ref()
+                .filter(|name| *name != &result.id)
+                .map(|name| format!(" ({name})"))
+                .unwrap_or_default();
+            let url = result
+                .url
+                .as_ref()
+                .map(|url| format!(" {}", url.download_file_name_color()))
+                .unwrap_or_default();
+
+            crate::output!("{}{version}{name}{url}", result.id.dependency_id_color());
+        }
+        Ok(())
+    }
+}
+
+/// Uncached packages whose display name is fetched because their id shares a word with the query
+const MAX_NAME_LOOKUPS: usize = 50;
+
+/// Packages of `repo` best matching `query`, best first
+pub fn search_packages(
+    repo: &impl Repository,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SearchResult>> {
+    // names are only known without a request for cached packages
+    let cached_names: HashMap<String, String> = FileRepository::read()?
+        .artifacts
+        .into_iter()
+        .filter_map(|(id, versions)| {
+            let latest = versions.into_iter().max_by(|a, b| a.0.cmp(&b.0))?;
+            Some((id, latest.1.config.info.name))
+        })
+        .collect();
+
+    rank_packages(repo, query, limit, &cached_names)
+}
+
+/// [`search_packages`] with the display names of cached packages, by id
+pub fn rank_packages(
+    repo: &impl Repository,
+    query: &str,
+    limit: usize,
+    cached_names: &HashMap<String, String>,
+) -> Result<Vec<SearchResult>> {
+    let (matching, unmatched): (Vec<_>, Vec<_>) = repo
+        .get_package_names()?
+        .into_iter()
+        .map(|id| {
+            let score = fuzzy::package_score(query, &id, cached_names.get(&id).map(String::as_str));
+            (id, score)
+        })
+        .partition(|(_, score)| score.is_some());
+
+    let best = matching
+        .into_iter()
+        .filter_map(|(id, score)| Some((id, score?)))
+        .sorted_by(|(a_id, a), (b_id, b)| b.cmp(a).then_with(|| a_id.cmp(b_id)))
+        .take(limit)
+        .map(|(id, score)| (id, Some(score)));
+
+    // the display name of an uncached package may match although its id does not,
+    // e.g. "Beat Saber Hook" for bs-hook, so look up those sharing a word with the query
+    let words = query
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|word| word.len() > 1)
+        .collect_vec();
+    let by_name = unmatched
+        .into_iter()
+        .filter(|(id, _)| !cached_names.contains_key(id))
+        .filter_map(|(id, _)| {
+            let score = words
+                .iter()
+                .filter_map(|word| fuzzy::fuzzy_score(word, &id))
+                .max()?;
+            Some((id, score))
+        })
+        .sorted_by(|(a_id, a), (b_id, b)| b.cmp(a).then_with(|| a_id.cmp(b_id)))
+        .take(MAX_NAME_LOOKUPS)
+        .map(|(id, _)| (id, None));
+
+    // fetch the metadata of the candidates, which may match better by name
+    let results = best
+        .chain(by_name)
+        .map(|(id, id_score)| -> Result<Option<SearchResult>> {
+            let latest_version = repo
+                .get_package_versions(&id)?
+                .and_then(|versions| versions.into_iter().map(|v| v.version).max());
+            let package = match &latest_version {
+                Some(version) => repo.get_package(&id, version)?,
+                None => None,
+            };
+            let name = package.as_ref().map(|p| p.config.info.name.clone());
+            let Some(score) = fuzzy::package_score(query, &id, name.as_deref()).max(id_score)
+            else {
+                return Ok(None);
+            };
+
+            Ok(Some(SearchResult {
+                url: package.and_then(|p| p.config.info.url),
+                id,
+                name,
+                latest_version,
+                score,
+            }))
+        })
+        .filter_map(Result::transpose)
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .sorted_by(|a, b| b.score.cmp(&a.score).then_with(|| a.id.cmp(&b.id)))
+        .take(limit)
+        .collect_vec();
+    Ok(results)
+}
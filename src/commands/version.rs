@@ -12,8 +12,9 @@ use owo_colors::OwoColorize;
 use zip::ZipArchive;
 
 use crate::{
+    models::{output::VersionCheckOutput, schemas::SchemaLinks},
     network::{agent::download_file_report, github},
-    terminal::colors::QPMColor,
+    terminal::{colors::QPMColor, output},
 };
 
 use super::Command;
@@ -48,12 +49,35 @@ impl Command for VersionCommand {
                 let input_branch = b.branch.unwrap_or(env!("VERGEN_GIT_BRANCH").to_string());
                 let latest_branch = github::get_github_branch(&input_branch)?;
 
-                println!(
+                if output::is_json() {
+                    let up_to_date = latest_branch.commit.sha == base_commit;
+                    let diff = match up_to_date {
+                        true => None,
+                        false => Some(github::get_github_commit_diff(base_commit, &input_branch)?),
+                    };
+
+                    return output::print_json(
+                        SchemaLinks::OUTPUT_VERSION_CHECK,
+                        VersionCheckOutput {
+                            branch: base_branch.to_string(),
+                            commit: base_commit.to_string(),
+                            latest_branch: input_branch,
+                            latest_commit: latest_branch.commit.sha,
+                            up_to_date,
+                            behind_by: diff.as_ref().map_or(0, |d| d.behind_by.max(0) as usize),
+                            changelog: diff
+                                .map(|d| d.commits.into_iter().map(|c| c.commit.message).collect())
+                                .unwrap_or_default(),
+                        },
+                    );
+                }
+
+                crate::output!(
                     "Running branch {}@{}",
                     base_branch.dependency_version_color(),
                     base_commit.version_id_color()
                 );
-                println!(
+                crate::output!(
                     "The latest branch {input_branch} commit is {}",
                     latest_branch
                         .commit
@@ -62,7 +86,7 @@ impl Command for VersionCommand {
                 );
 
                 if latest_branch.commit.sha == base_commit {
-                    println!("Using the latest version");
+                    crate::output!("Using the latest version");
                     return Ok(());
                 }
 
@@ -73,26 +97,26 @@ impl Command for VersionCommand {
                     bail!("Selected an older branch")
                 }
 
-                println!(
+                crate::output!(
                     "Current QPM-RS build is behind {} commits",
                     diff.behind_by.version_id_color()
                 );
-                println!("Changelog:");
+                crate::output!("Changelog:");
 
                 for commit in diff.commits {
-                    println!("- {}", commit.commit.message);
+                    crate::output!("- {}", commit.commit.message);
                 }
             }
 
             VersionOperation::Current => {
-                println!("{}@{}", env!("VERGEN_GIT_BRANCH"), env!("VERGEN_GIT_SHA"))
+                crate::output!("{}@{}", env!("VERGEN_GIT_BRANCH"), env!("VERGEN_GIT_SHA"))
             }
 
             VersionOperation::Update(u) => {
                 let base_branch = env!("VERGEN_GIT_BRANCH");
                 let base_commit = env!("VERGEN_GIT_SHA");
 
-                println!(
+                log::info!(
                     "Running branch {}@{}",
                     base_branch.dependency_version_color(),
                     base_commit.version_id_color()
@@ -103,7 +127,7 @@ impl Command for VersionCommand {
                         let latest_branch = github::get_github_branch(&input_branch)?;
 
                         if base_commit == latest_branch.commit.sha {
-                            println!("Already running commit");
+                            log::info!("Already running commit");
                             return Ok(());
                         }
                         github::download_github_artifact_url(&input_branch)
@@ -111,7 +135,7 @@ impl Command for VersionCommand {
                     None => github::bleeding_release_github_artifact_url(),
                 };
 
-                println!("Downloading {download_url}");
+                log::info!("Downloading {download_url}");
 
                 let path = env::current_exe()?;
                 let tmp_path = path.with_extension("tmp");
@@ -123,7 +147,7 @@ impl Command for VersionCommand {
                 let buf_reader = BufReader::new(zip.by_index(0)?);
                 let bytes = buf_reader.bytes();
 
-                println!("Finished downloading, writing to temp file");
+                log::info!("Finished downloading, writing to temp file");
                 let tmp_file = File::create(&tmp_path)?;
                 let perms = fs::metadata(&path)?.permissions();
                 fs::set_permissions(&tmp_path, perms)?;
@@ -139,10 +163,10 @@ impl Command for VersionCommand {
                     if cfg!(windows) { "-Force" } else { "" }
                 );
 
-                println!("Renaming tmp file");
+                log::info!("Renaming tmp file");
                 fs::rename(&path, path.with_extension("old")).suggestion(suggestion.clone())?;
                 fs::rename(&tmp_path, &path).suggestion(suggestion)?;
-                println!("Finished updating")
+                log::info!("Finished updating")
             }
         }
         Ok(())
@@ -0,0 +1,81 @@
+use clap::Args;
+use color_eyre::{Result, Section};
+use semver::Version;
+
+use crate::{
+    models::config::get_registry_keyring, repository::registry, terminal::colors::QPMColor,
+};
+
+use super::Command;
+
+#[derive(Args, Debug, Clone)]
+pub struct YankArgs {
+    /// Id of the package
+    pub id: String,
+
+    /// Version of the package
+    pub version: Version,
+
+    /// Git registry the package was published to, other registries do not support yanking
+    #[clap(long)]
+    pub registry: String,
+
+    /// the authorization header to use, if present
+    #[clap(long)]
+    pub publish_auth: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct YankCommand {
+    #[clap(flatten)]
+    pub args: YankArgs,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct UnyankCommand {
+    #[clap(flatten)]
+    pub args: YankArgs,
+}
+
+impl Command for YankCommand {
+    fn execute(self) -> Result<()> {
+        set_yanked(self.args, true)
+    }
+}
+
+impl Command for UnyankCommand {
+    fn execute(self) -> Result<()> {
+        set_yanked(self.args, false)
+    }
+}
+
+fn set_yanked(args: YankArgs, yanked: bool) -> Result<()> {
+    let registry_config = registry::get_registry(&args.registry)?;
+    let auth = args
+        .publish_auth
+        .or_else(|| get_registry_keyring(&args.registry).and_then(|e| e.get_password().ok()));
+
+    registry::set_yanked(
+        &args.registry,
+        &registry_config,
+        &args.id,
+        &args.version,
+        yanked,
+        auth.as_deref(),
+    )
+    .with_suggestion(|| {
+        format!(
+            "Store a publish key with qpm config publish --registry {}",
+            args.registry
+        )
+    })?;
+
+    log::info!(
+        "{} {}:{} on {}",
+        if yanked { "Yanked" } else { "Unyanked" },
+        args.id.dependency_id_color(),
+        args.version.version_id_color(),
+        args.registry.dependency_id_color()
+    );
+    Ok(())
+}
@@ -5,6 +5,7 @@
 #![feature(path_add_extension)]
 #![feature(path_is_empty)]
 
+pub mod api;
 #[cfg(feature = "cli")]
 pub mod commands;
 
@@ -7,6 +7,7 @@ use clap_complete::{Generator, Shell, generate};
 use color_eyre::Result;
 use commands::Command;
 
+pub mod api;
 #[cfg(feature = "cli")]
 pub mod commands;
 
@@ -35,15 +36,15 @@ fn print_completions<G: Generator>(generator: G, cmd: &mut clap::Command) {
 /// Suggests the location where to pipe the auto-generated completion script
 /// based on the shell type.
 fn suggest_completion_location(shell: Shell) {
-    eprintln!("To add this to your shell, you may use the following command:");
+    log::info!("To add this to your shell, you may use the following command:");
 
     let file_name = shell.file_name("qpm");
 
     // powershell is unique so
     // we make it its own suggestion
     if shell == Shell::PowerShell {
-        eprintln!("\tqpm --generate {shell} | Set-Content \"$HOME\\qpm_autocomplete.ps1\"");
-        eprintln!(
+        log::info!("\tqpm --generate {shell} | Set-Content \"$HOME\\qpm_autocomplete.ps1\"");
+        log::info!(
             "\t'if (Test-Path \"$HOME\\qpm_autocomplete.ps1\") {{ . \"$HOME\\qpm_autocomplete.ps1\" }}' | Add-Content -Path $PROFILE"
         );
     } else {
@@ -55,30 +56,42 @@ fn suggest_completion_location(shell: Shell) {
             _ => todo!(),
         };
 
-        eprintln!("\tqpm --generate {shell} > {loc}")
+        log::info!("\tqpm --generate {shell} > {loc}")
     }
 }
 
 fn main() -> Result<()> {
-    color_eyre::config::HookBuilder::default()
-        .panic_section(concat!(
-            "version ",
-            env!("CARGO_PKG_VERSION"),
-            " consider reporting the bug on github ",
-            env!("CARGO_PKG_REPOSITORY"),
-            "/issues/new"
-        ))
-        .install()?;
     let command_result = commands::Opt::parse();
+    terminal::output::set_output_format(command_result.format);
+    terminal::colors::set_color_choice(command_result.color);
+
+    let mut hook = color_eyre::config::HookBuilder::default().panic_section(concat!(
+        "version ",
+        env!("CARGO_PKG_VERSION"),
+        " consider reporting the bug on github ",
+        env!("CARGO_PKG_REPOSITORY"),
+        "/issues/new"
+    ));
+    if !terminal::colors::colors_enabled(&io::stderr()) {
+        hook = hook.theme(color_eyre::config::Theme::new());
+    }
+    hook.install()?;
+
+    terminal::logging::init(
+        terminal::logging::verbosity_level(command_result.quiet, command_result.verbose),
+        command_result.log_file.as_deref(),
+    )?;
 
     if let Some(generator) = command_result.generator {
         let mut cmd = commands::Opt::command();
-        eprintln!("Generating completion file for {generator:?}...");
+        log::info!("Generating completion file for {generator:?}...");
         print_completions(generator, &mut cmd);
         suggest_completion_location(generator);
     }
     if let Some(command) = command_result.command {
-        command.execute()?;
+        command
+            .execute()
+            .inspect_err(terminal::logging::record_error)?;
     }
 
     Ok(())
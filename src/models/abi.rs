@@ -0,0 +1,189 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use color_eyre::eyre::{Report, eyre};
+use itertools::Itertools;
+use qpm_package::models::package::PackageConfig;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    config::get_combine_config,
+    package::{PACKAGE_FILE_NAME, SHARED_PACKAGE_FILE_NAME},
+};
+
+/// Android ABI binaries are built for.
+/// Only 64 bit ABIs, the Quest and the emulators used for testing are 64 bit
+#[derive(
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub enum Abi {
+    #[default]
+    #[serde(rename = "arm64-v8a")]
+    Arm64V8a,
+    #[serde(rename = "x86_64")]
+    X86_64,
+}
+
+impl Abi {
+    pub const ALL: &[Abi] = &[Abi::Arm64V8a, Abi::X86_64];
+
+    /// Name used by the NDK, e.g. for `ANDROID_ABI`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Abi::Arm64V8a => "arm64-v8a",
+            Abi::X86_64 => "x86_64",
+        }
+    }
+
+    /// ABIs selected with `qpm config abis`, arm64-v8a if none are
+    pub fn selected() -> Vec<Abi> {
+        let abis = get_combine_config()
+            .abis
+            .iter()
+            .flatten()
+            .copied()
+            .unique()
+            .collect_vec();
+
+        match abis.is_empty() {
+            true => vec![Abi::default()],
+            false => abis,
+        }
+    }
+
+    /// Folder of the binaries for this ABI inside the `lib` folder of a cached package
+    pub fn cache_libs_path(&self, lib: &Path) -> PathBuf {
+        lib.join(self.as_str())
+    }
+
+    /// Folder of the binaries for this ABI inside `extern/libs`.
+    /// arm64-v8a binaries stay at the top level so existing projects keep linking them
+    pub fn extern_libs_path(&self, libs: &Path) -> PathBuf {
+        match self {
+            Abi::Arm64V8a => libs.to_path_buf(),
+            _ => libs.join(self.as_str()),
+        }
+    }
+
+    /// The first selected ABI, built when `ANDROID_ABI` is not given
+    /// and the one `qpm install` expects in `build/`
+    pub fn primary() -> Abi {
+        Self::selected()[0]
+    }
+}
+
+impl fmt::Display for Abi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Abi {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|abi| abi.as_str() == s)
+            .copied()
+            .ok_or_else(|| {
+                eyre!(
+                    "Unknown ABI {s}, expected one of {}",
+                    Self::ALL.iter().join(", ")
+                )
+            })
+    }
+}
+
+/// Binary links of a package for one ABI
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AbiLinks {
+    /// Link to the release `.so`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub so_link: Option<String>,
+    /// Link to the `.so` with debug symbols
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_so_link: Option<String>,
+    /// Link to the `.a` of statically linked packages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub static_link: Option<String>,
+}
+
+/// `abiLinks` of `qpm.json` and `qpm.shared.json`, binary links by ABI.
+/// qpm_package does not know them, so they are a top level field next to its own
+pub type AbiLinksMap = BTreeMap<Abi, AbiLinks>;
+
+/// Fields qpm adds to `qpm.json` and `qpm.shared.json` next to the qpm_package ones
+#[derive(JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageFileExtensions {
+    /// Binary links by ABI. arm64-v8a uses the regular links of `additionalData` unless it is listed
+    pub abi_links: Option<AbiLinksMap>,
+}
+
+impl AbiLinks {
+    /// Links of `package` for `abi`, read from `abiLinks.<abi>` of the package sources at `src_path`.
+    /// arm64-v8a falls back to the regular links
+    pub fn of(package: &PackageConfig, src_path: &Path, abi: Abi) -> Self {
+        if let Some(links) = Self::read_map(src_path).remove(&abi) {
+            return links;
+        }
+
+        match abi {
+            Abi::Arm64V8a => {
+                let data = &package.info.additional_data;
+                AbiLinks {
+                    so_link: data.so_link.clone(),
+                    debug_so_link: data.debug_so_link.clone(),
+                    static_link: data.static_link.clone(),
+                }
+            }
+            _ => AbiLinks::default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.so_link.is_none() && self.debug_so_link.is_none() && self.static_link.is_none()
+    }
+
+    /// `abiLinks` of the package sources at `src_path`, from `qpm.json` or else `qpm.shared.json`
+    pub fn read_map(src_path: &Path) -> AbiLinksMap {
+        read_abi_links(&src_path.join(PACKAGE_FILE_NAME))
+            .or_else(|| read_abi_links(&src_path.join(SHARED_PACKAGE_FILE_NAME)))
+            .unwrap_or_default()
+    }
+}
+
+/// `abiLinks` of the package file at `path`, `None` if there is no readable file
+pub(crate) fn read_abi_links(path: &Path) -> Option<AbiLinksMap> {
+    let package: Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    let Some(links) = package.get("abiLinks") else {
+        return Some(AbiLinksMap::new());
+    };
+
+    match serde_json::from_value(links.clone()) {
+        Ok(links) => Some(links),
+        Err(e) => {
+            log::warn!("Ignoring invalid abiLinks of {}: {e}", path.display());
+            Some(AbiLinksMap::new())
+        }
+    }
+}
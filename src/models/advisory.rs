@@ -0,0 +1,247 @@
+//! Security advisories for published package versions, checked by `qpm audit`
+
+use std::{
+    fmt, fs,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    Result, Section,
+    eyre::{Context, bail},
+};
+use schemars::JsonSchema;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::{
+    models::config::get_combine_config,
+    resolver::semver::{VersionWrapper, req_to_range},
+    utils::{
+        git::{self, check_git},
+        json,
+    },
+};
+
+#[derive(
+    Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "camelCase")]
+pub enum AdvisorySeverity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for AdvisorySeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdvisorySeverity::Low => write!(f, "low"),
+            AdvisorySeverity::Medium => write!(f, "medium"),
+            AdvisorySeverity::High => write!(f, "high"),
+            AdvisorySeverity::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+/// A known problem in some versions of a package
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Advisory {
+    /// Unique id of the advisory
+    pub id: String,
+
+    /// Id of the affected package
+    pub package: String,
+
+    /// Affected version ranges
+    pub affected: Vec<VersionReq>,
+
+    pub severity: AdvisorySeverity,
+
+    pub description: String,
+
+    /// Link to more information
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Advisory {
+    /// Whether `version` of `package` is affected, using the same ranges as the resolver
+    pub fn affects(&self, package: &str, version: &Version) -> bool {
+        let version = VersionWrapper(version.clone());
+        self.package == package
+            && self
+                .affected
+                .iter()
+                .any(|req| req_to_range(req.clone()).contains(&version))
+    }
+}
+
+/// Every advisory of a database
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdvisoryDatabase {
+    pub advisories: Vec<Advisory>,
+}
+
+/// A database file holds one advisory or a list of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AdvisoryFile {
+    One(Advisory),
+    Many(Vec<Advisory>),
+}
+
+impl AdvisoryDatabase {
+    /// Loads a JSON file, or every JSON file in a directory such as a synced git repository.
+    /// Files in a directory that are not advisories are skipped with a warning
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            bail!("Advisory database {} does not exist", path.display());
+        }
+
+        let files = match path.is_dir() {
+            true => WalkDir::new(path)
+                .into_iter()
+                .filter_entry(|e| e.file_name() != ".git")
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+                .map(|e| e.into_path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .collect(),
+            false => vec![path.to_path_buf()],
+        };
+
+        let mut advisories = vec![];
+        for file in files {
+            let parsed = json::json_from_reader_fast(BufReader::new(fs::File::open(&file)?))
+                .with_context(|| format!("Unable to read advisories in {}", file.display()));
+            let parsed = match parsed {
+                Ok(parsed) => parsed,
+                // databases may keep other JSON next to the advisories, e.g. editor settings
+                Err(e) if path.is_dir() => {
+                    log::warn!("Skipping {}: {e:?}", file.display());
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            match parsed {
+                AdvisoryFile::One(advisory) => advisories.push(advisory),
+                AdvisoryFile::Many(many) => advisories.extend(many),
+            }
+        }
+        advisories.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Ok(Self { advisories })
+    }
+
+    /// Advisories affecting `version` of `package`
+    pub fn affecting<'a>(
+        &'a self,
+        package: &'a str,
+        version: &'a Version,
+    ) -> impl Iterator<Item = &'a Advisory> {
+        self.advisories
+            .iter()
+            .filter(move |a| a.affects(package, version))
+    }
+}
+
+/// Where the advisory database comes from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AdvisorySource {
+    File(PathBuf),
+    Git(String),
+}
+
+impl AdvisorySource {
+    pub fn parse(source: &str) -> Self {
+        if source.contains("://") || source.starts_with("git@") {
+            AdvisorySource::Git(source.to_string())
+        } else {
+            AdvisorySource::File(PathBuf::from(source))
+        }
+    }
+
+    /// Where the database is read from, git repositories are synced into the cache
+    pub fn local_path(&self) -> PathBuf {
+        match self {
+            AdvisorySource::File(path) => path.clone(),
+            AdvisorySource::Git(url) => {
+                let name = url
+                    .trim_end_matches('/')
+                    .trim_end_matches(".git")
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                    .collect::<String>();
+                get_combine_config()
+                    .cache
+                    .as_ref()
+                    .unwrap()
+                    .join("advisories")
+                    .join(name)
+            }
+        }
+    }
+
+    /// Fetches the latest advisories of a git database
+    pub fn sync(&self) -> Result<()> {
+        self.sync_to(&self.local_path())
+    }
+
+    /// Fetches the latest advisories of a git database into `path`.
+    /// A fresh clone only replaces `path` once it succeeded
+    pub fn sync_to(&self, path: &Path) -> Result<()> {
+        let AdvisorySource::Git(url) = self else {
+            return Ok(());
+        };
+        check_git()?;
+
+        if path.join(".git").exists() {
+            return git::run(&["pull", "--ff-only"], path)
+                .with_context(|| format!("Unable to update advisories from {url}"));
+        }
+
+        let tmp_path = path.with_added_extension("tmp");
+        for stale in [&tmp_path, path] {
+            if stale.exists() {
+                fs::remove_dir_all(stale)
+                    .with_context(|| format!("Unable to remove {}", stale.display()))?;
+            }
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let Some(out) = tmp_path.to_str() else {
+            bail!("Cache path {} is not valid UTF-8", path.display());
+        };
+        let cloned = git::run(&["clone", "--depth", "1", url, out], Path::new("."))
+            .with_suggestion(|| format!("Check that {url} exists and you have access to it"));
+        if let Err(e) = cloned {
+            let _ = fs::remove_dir_all(&tmp_path);
+            return Err(e);
+        }
+
+        fs::rename(&tmp_path, path)
+            .with_context(|| format!("Unable to move advisories to {}", path.display()))
+    }
+
+    /// Loads the local copy of the database
+    pub fn load(&self) -> Result<AdvisoryDatabase> {
+        self.load_from(&self.local_path())
+    }
+
+    /// Loads the copy of the database at `path`, git databases must have been synced to it
+    pub fn load_from(&self, path: &Path) -> Result<AdvisoryDatabase> {
+        if let AdvisorySource::Git(url) = self
+            && !path.join(".git").exists()
+        {
+            bail!("Advisories from {url} were never synced");
+        }
+        AdvisoryDatabase::load(path)
+    }
+}
@@ -1,4 +1,6 @@
 use std::{
+    collections::BTreeMap,
+    fmt,
     fs::{self, File},
     io::BufReader,
     path::{Path, PathBuf},
@@ -10,9 +12,12 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
-use crate::utils::json;
+use crate::{repository::registry::DEFAULT_REGISTRY, utils::json};
 
-use super::schemas::{SchemaLinks, WithSchema};
+use super::{
+    abi::Abi,
+    schemas::{SchemaLinks, WithSchema},
+};
 
 static COMBINED_CONFIG: sync::OnceLock<UserConfig> = sync::OnceLock::new();
 
@@ -40,6 +45,57 @@ pub struct UserConfig {
     /// Path where ndk downloads are stored
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ndk_download_path: Option<PathBuf>,
+
+    /// Registries that can be published to, by name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registries: Option<BTreeMap<String, RegistryConfig>>,
+
+    /// Advisory database used by `qpm audit`, a local path or a git url
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub advisory_db: Option<String>,
+
+    /// ABIs dependency binaries are restored for, the first one is built by default.
+    /// Every dependency needs binaries for the first, missing ones for the others are a warning.
+    /// Defaults to arm64-v8a
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abis: Option<Vec<Abi>>,
+}
+
+/// A registry packages can be published to
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Hash, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RegistryConfig {
+    /// A qpackages compatible HTTP API
+    Http {
+        /// Base url of the API
+        url: String,
+    },
+    /// A git repository storing `<id>/<version>.json` for every release and the yanked
+    /// versions in `<id>/yanked.json`, dependencies are also resolved from it
+    Git {
+        /// Url of the repository
+        url: String,
+
+        /// Branch to push to, the default branch if unset
+        #[serde(skip_serializing_if = "Option::is_none")]
+        branch: Option<String>,
+    },
+    /// The local file repository
+    Local,
+}
+
+impl fmt::Display for RegistryConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryConfig::Http { url } => write!(f, "http {url}"),
+            RegistryConfig::Git {
+                url,
+                branch: Some(branch),
+            } => write!(f, "git {url} ({branch})"),
+            RegistryConfig::Git { url, branch: None } => write!(f, "git {url}"),
+            RegistryConfig::Local => f.write_str("local"),
+        }
+    }
 }
 
 impl UserConfig {
@@ -130,6 +186,16 @@ impl UserConfig {
                 timeout: local.timeout.or(global.timeout),
                 symlink: local.symlink.or(global.symlink),
                 ndk_download_path: local.ndk_download_path.or(global.ndk_download_path),
+                // workspace registries override global ones with the same name
+                registries: match (local.registries, global.registries) {
+                    (Some(local), Some(mut global)) => {
+                        global.extend(local);
+                        Some(global)
+                    }
+                    (local, global) => local.or(global),
+                },
+                advisory_db: local.advisory_db.or(global.advisory_db),
+                abis: local.abis.or(global.abis),
             },
             None => global,
         })
@@ -151,6 +217,9 @@ impl Default for UserConfig {
             cache: Some(dirs::data_dir().unwrap().join("QPM-RS").join("cache")),
             timeout: Some(60000),
             ndk_download_path: Some(dirs::data_dir().unwrap().join("QPM-RS").join("ndk")),
+            registries: None,
+            advisory_db: None,
+            abis: None,
         }
     }
 }
@@ -164,3 +233,12 @@ pub fn get_keyring() -> Option<keyring::v1::Entry> {
 pub fn get_publish_keyring() -> Option<keyring::v1::Entry> {
     keyring::v1::Entry::new("qpm", "publish").ok()
 }
+
+/// Credential used to publish to the registry `name`, qpackages keeps using the original publish key
+#[inline]
+pub fn get_registry_keyring(name: &str) -> Option<keyring::v1::Entry> {
+    match name {
+        DEFAULT_REGISTRY => get_publish_keyring(),
+        _ => keyring::v1::Entry::new("qpm", &format!("publish-{name}")).ok(),
+    }
+}
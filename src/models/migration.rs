@@ -0,0 +1,272 @@
+use color_eyre::{
+    Result,
+    eyre::{Context, OptionExt, bail},
+};
+use qpm_package::models::{dependency::SharedPackageConfig, package::PackageConfig};
+use semver::Version;
+use serde_json::{Map, Value};
+
+/// Keys of `additionalData` that are no longer read by qpm
+const DEPRECATED_ADDITIONAL_DATA: &[&str] = &["styles", "style"];
+
+/// A config upgraded to the current schema
+#[derive(Debug, Clone)]
+pub struct Migration<T> {
+    /// Schema version the config was written with, `None` for the legacy C# QPM layout
+    pub from: Option<Version>,
+    pub config: T,
+    /// Human readable description of each step applied
+    pub notes: Vec<String>,
+}
+
+/// A difference between two JSON documents
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonChange {
+    Removed {
+        path: String,
+        value: Value,
+    },
+    Changed {
+        path: String,
+        old: Value,
+        new: Value,
+    },
+    Added {
+        path: String,
+        value: Value,
+    },
+}
+
+/// Upgrades a raw qpm.json of any schema version, including the manifests of the C# QPM
+pub fn migrate_package_config(mut raw: Value) -> Result<Migration<PackageConfig>> {
+    let mut notes = vec![];
+    let object = raw
+        .as_object_mut()
+        .ok_or_eyre("qpm.json must contain a JSON object")?;
+
+    let current = PackageConfig::default().version;
+    let from = schema_version(object)?;
+    match &from {
+        None => migrate_legacy(object, &mut notes),
+        // downgrading would drop whatever the newer schema added
+        Some(from) if *from > current => bail!(
+            "qpm.json uses schema version {from}, newer than the supported {current}, upgrade qpm"
+        ),
+        Some(_) => {}
+    }
+
+    // no field was renamed between schema versions, older configs only miss
+    // fields of the current schema and may contain deprecated ones
+    remove_deprecated(object, &mut notes);
+    fill_defaults(object, &mut notes)?;
+
+    object.insert("version".to_string(), Value::String(current.to_string()));
+    if from.as_ref() != Some(&current) {
+        notes.push(format!(
+            "updated schema version from {} to {current}",
+            from.as_ref()
+                .map_or("<none>".to_string(), Version::to_string)
+        ));
+    }
+
+    let config = serde_json::from_value(raw).context("Unable to read the migrated qpm.json")?;
+    Ok(Migration {
+        from,
+        config,
+        notes,
+    })
+}
+
+/// Upgrades a raw qpm.shared.json, migrating the embedded config and the restored dependencies
+pub fn migrate_shared_package_config(mut raw: Value) -> Result<Migration<SharedPackageConfig>> {
+    let object = raw
+        .as_object_mut()
+        .ok_or_eyre("qpm.shared.json must contain a JSON object")?;
+
+    let config = object
+        .remove("config")
+        .ok_or_eyre("qpm.shared.json has no config")?;
+    let Migration {
+        from,
+        config,
+        mut notes,
+    } = migrate_package_config(config)?;
+
+    if let Some(Value::Array(restored)) = object.get_mut("restoredDependencies") {
+        for (i, restored) in restored.iter_mut().enumerate() {
+            if let Some(additional_data) = restored.pointer_mut("/dependency/additionalData") {
+                remove_deprecated_keys(
+                    additional_data,
+                    &format!("restoredDependencies[{i}].dependency.additionalData"),
+                    &mut notes,
+                );
+            }
+        }
+    }
+
+    object.insert("config".to_string(), serde_json::to_value(&config)?);
+    let config =
+        serde_json::from_value(raw).context("Unable to read the migrated qpm.shared.json")?;
+    Ok(Migration {
+        from,
+        config,
+        notes,
+    })
+}
+
+/// Lists the paths that differ between `old` and `new`, descending into objects and arrays
+pub fn json_changes(old: &Value, new: &Value) -> Vec<JsonChange> {
+    let mut changes = vec![];
+    collect_changes("", old, new, &mut changes);
+    changes
+}
+
+fn collect_changes(path: &str, old: &Value, new: &Value, changes: &mut Vec<JsonChange>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                let key_path = join_path(path, key);
+                match new.get(key) {
+                    Some(new_value) => collect_changes(&key_path, old_value, new_value, changes),
+                    None => changes.push(JsonChange::Removed {
+                        path: key_path,
+                        value: old_value.clone(),
+                    }),
+                }
+            }
+            for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+                changes.push(JsonChange::Added {
+                    path: join_path(path, key),
+                    value: new_value.clone(),
+                });
+            }
+        }
+        (Value::Array(old), Value::Array(new)) if old.len() == new.len() => {
+            for (i, (old, new)) in old.iter().zip(new).enumerate() {
+                collect_changes(&format!("{path}[{i}]"), old, new, changes);
+            }
+        }
+        _ if old != new => changes.push(JsonChange::Changed {
+            path: path.to_string(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    match path {
+        "" => key.to_string(),
+        _ => format!("{path}.{key}"),
+    }
+}
+
+/// `None` when the config predates the schema version field
+fn schema_version(object: &Map<String, Value>) -> Result<Option<Version>> {
+    let Some(version) = object.get("version") else {
+        return Ok(None);
+    };
+    let version = version
+        .as_str()
+        .ok_or_eyre("Schema version must be a string")?;
+    Ok(Some(Version::parse(version).with_context(|| {
+        format!("Invalid schema version {version}")
+    })?))
+}
+
+/// The C# QPM kept package properties in a top-level `additionalData`
+/// and wrote no schema version or workspace
+fn migrate_legacy(object: &mut Map<String, Value>, notes: &mut Vec<String>) {
+    if let Some(Value::Object(legacy)) = object.remove("additionalData") {
+        let info_data = object
+            .entry("info")
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .and_then(|info| {
+                info.entry("additionalData")
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+            });
+
+        if let Some(info_data) = info_data {
+            for (key, value) in legacy {
+                info_data.entry(key).or_insert(value);
+            }
+        }
+        notes.push("moved top-level additionalData into info.additionalData".to_string());
+    }
+
+    // defaults of the C# QPM
+    for (key, default) in [("sharedDir", "shared"), ("dependenciesDir", "extern")] {
+        if !object.contains_key(key) {
+            object.insert(key.to_string(), Value::String(default.to_string()));
+            notes.push(format!("set {key} to the C# QPM default {default}"));
+        }
+    }
+}
+
+fn remove_deprecated(object: &mut Map<String, Value>, notes: &mut Vec<String>) {
+    if let Some(additional_data) = object
+        .get_mut("info")
+        .and_then(|info| info.get_mut("additionalData"))
+    {
+        remove_deprecated_keys(additional_data, "info.additionalData", notes);
+    }
+
+    if let Some(Value::Array(dependencies)) = object.get_mut("dependencies") {
+        for (i, dependency) in dependencies.iter_mut().enumerate() {
+            if let Some(additional_data) = dependency.get_mut("additionalData") {
+                remove_deprecated_keys(
+                    additional_data,
+                    &format!("dependencies[{i}].additionalData"),
+                    notes,
+                );
+            }
+        }
+    }
+}
+
+fn remove_deprecated_keys(additional_data: &mut Value, path: &str, notes: &mut Vec<String>) {
+    let Some(additional_data) = additional_data.as_object_mut() else {
+        return;
+    };
+    for key in DEPRECATED_ADDITIONAL_DATA {
+        if additional_data.remove(*key).is_some() {
+            notes.push(format!("removed deprecated {path}.{key}"));
+        }
+    }
+}
+
+/// Adds the top-level and workspace fields of the current schema that are missing
+fn fill_defaults(object: &mut Map<String, Value>, notes: &mut Vec<String>) -> Result<()> {
+    let Value::Object(mut defaults) = serde_json::to_value(PackageConfig::default())? else {
+        return Ok(());
+    };
+
+    // the schema version is set by the caller
+    defaults.remove("version");
+    let workspace_defaults = defaults.remove("workspace");
+    for (key, value) in defaults {
+        if !object.contains_key(&key) {
+            notes.push(format!("added {key}"));
+            object.insert(key, value);
+        }
+    }
+
+    let Some(Value::Object(workspace_defaults)) = workspace_defaults else {
+        return Ok(());
+    };
+    let workspace = object
+        .entry("workspace")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Some(workspace) = workspace.as_object_mut() {
+        for (key, value) in workspace_defaults {
+            if !workspace.contains_key(&key) {
+                notes.push(format!("added workspace.{key}"));
+                workspace.insert(key, value);
+            }
+        }
+    }
+    Ok(())
+}
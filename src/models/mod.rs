@@ -1,6 +1,11 @@
+pub mod abi;
+pub mod advisory;
 pub mod android_repo;
 pub mod config;
+pub mod migration;
 pub mod mod_json;
+pub mod output;
 pub mod package;
+pub mod sbom;
 pub(crate) mod schemas;
 pub mod toolchain;
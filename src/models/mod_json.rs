@@ -18,7 +18,8 @@ pub trait ModJsonExtensions: Sized {
     fn get_template_name() -> &'static str;
     fn get_result_name() -> &'static str;
     fn get_template_path() -> PathBuf;
-    fn read_and_preprocess(preprocess_data: PreProcessingData) -> Result<Self>;
+    /// Reads the template at `path` and fills in its placeholders
+    fn read_and_preprocess(path: &Path, preprocess_data: PreProcessingData) -> Result<Self>;
 
     fn read(path: &Path) -> Result<Self>;
     fn write(&self, path: &Path) -> Result<()>;
@@ -50,8 +51,8 @@ impl ModJsonExtensions for ModJson {
         PathBuf::new().join(Self::get_template_name())
     }
 
-    fn read_and_preprocess(preprocess_data: PreProcessingData) -> Result<Self> {
-        let mut file = File::open(Self::get_template_name()).context("Opening mod.json failed")?;
+    fn read_and_preprocess(path: &Path, preprocess_data: PreProcessingData) -> Result<Self> {
+        let mut file = File::open(path).context("Opening mod.json failed")?;
 
         // Get data
         let mut json = String::with_capacity(file.metadata()?.len() as usize);
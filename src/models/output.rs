@@ -0,0 +1,339 @@
+//! Documents printed by query commands when run with `--format json`
+
+use std::path::PathBuf;
+
+use schemars::JsonSchema;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::models::advisory::AdvisorySeverity;
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm list packages`")]
+pub struct PackageListOutput {
+    /// Sorted ids of all packages known to the repositories
+    pub packages: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm list versions`")]
+pub struct PackageVersionsOutput {
+    /// Id of the package
+    pub id: String,
+
+    /// Versions of the package, newest first. Only the latest when `--latest` is used
+    pub versions: Vec<Version>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm cache list`")]
+pub struct CacheListOutput {
+    /// Path of the cache
+    pub path: PathBuf,
+
+    /// Packages stored in the cache
+    pub packages: Vec<CachedPackage>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedPackage {
+    /// Id of the package
+    pub id: String,
+
+    /// Names of the cached version directories
+    pub versions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm collapse`")]
+pub struct CollapseOutput {
+    /// Every package in the resolved dependency graph
+    pub packages: Vec<CollapsedPackage>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CollapsedPackage {
+    /// Id of the package
+    pub id: String,
+
+    /// Resolved version of the package
+    pub version: Version,
+
+    /// Dependencies restored by this package
+    pub restored_dependencies: Vec<CollapsedDependency>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CollapsedDependency {
+    /// Id of the dependency
+    pub id: String,
+
+    /// Version range requested by the package
+    pub version_range: String,
+
+    /// Version the range was resolved to
+    pub version: Version,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm ndk list`")]
+pub struct NdkListOutput {
+    /// NDKs installed in the NDK download path
+    pub ndks: Vec<InstalledNdk>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledNdk {
+    /// Version of the NDK, taken from its directory name
+    pub version: String,
+
+    /// Path of the NDK
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm ndk available`")]
+pub struct NdkAvailableOutput {
+    /// Page that was requested
+    pub page: usize,
+
+    /// NDKs on this page, newest first
+    pub ndks: Vec<AvailableNdk>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableNdk {
+    /// Version of the NDK
+    pub version: String,
+
+    /// Name of the NDK in the Android repository
+    pub display_name: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm doctor`")]
+pub struct DoctorOutput {
+    /// Whether CMake was found in PATH
+    pub cmake: bool,
+
+    /// Whether Ninja was found in PATH
+    pub ninja: bool,
+
+    /// Whether ADB was found in PATH
+    pub adb: bool,
+
+    /// Whether qpm was found in PATH
+    pub qpm: bool,
+
+    /// NDK path from ANDROID_NDK_HOME or ndkpath.txt, only checked inside a package
+    pub ndk: Option<PathBuf>,
+
+    /// Whether everything required was found
+    pub ok: bool,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm version check`")]
+pub struct VersionCheckOutput {
+    /// Branch this build was made from
+    pub branch: String,
+
+    /// Commit this build was made from
+    pub commit: String,
+
+    /// Branch that was checked against
+    pub latest_branch: String,
+
+    /// Latest commit of the checked branch
+    pub latest_commit: String,
+
+    /// Whether this build is the latest commit
+    pub up_to_date: bool,
+
+    /// How many commits this build is behind
+    pub behind_by: usize,
+
+    /// Messages of the commits this build is missing
+    pub changelog: Vec<String>,
+}
+
+#[derive(
+    Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum LintSeverity {
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm package lint`")]
+pub struct LintOutput {
+    /// Problems found in the package
+    pub findings: Vec<LintFinding>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LintFinding {
+    /// Stable id of the lint
+    pub id: String,
+
+    /// Errors fail the lint, warnings do not
+    pub severity: LintSeverity,
+
+    /// Description of the problem
+    pub message: String,
+
+    /// Whether `--fix` can fix the problem
+    pub fixable: bool,
+
+    /// Whether the problem was fixed by this run
+    pub fixed: bool,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm licenses`")]
+pub struct LicensesOutput {
+    /// Licenses of the restored dependencies
+    pub packages: Vec<PackageLicenseOutput>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageLicenseOutput {
+    /// Id of the package
+    pub id: String,
+
+    /// Restored version of the package
+    pub version: Version,
+
+    /// Declared license, or the SPDX id detected from the license files
+    pub license: Option<String>,
+
+    /// License files found in the cached sources
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm audit`")]
+pub struct AuditOutput {
+    /// Advisories affecting the restored dependencies
+    pub findings: Vec<AuditFinding>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditFinding {
+    /// Id of the advisory
+    pub id: String,
+
+    /// Id of the affected package
+    pub package: String,
+
+    /// Restored version of the package
+    pub version: Version,
+
+    pub severity: AdvisorySeverity,
+
+    pub description: String,
+
+    /// Link to more information
+    pub url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm search`")]
+pub struct SearchOutput {
+    /// Matching packages, best match first
+    pub results: Vec<SearchResult>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    /// Id of the package
+    pub id: String,
+
+    /// Display name of the package
+    pub name: Option<String>,
+
+    /// Latest version of the package
+    pub latest_version: Option<Version>,
+
+    /// Url of the package
+    pub url: Option<String>,
+
+    /// How well the package matches the query, higher is better
+    pub score: i64,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[schemars(description = "Output of `qpm info`")]
+pub struct InfoOutput {
+    /// Id of the package
+    pub id: String,
+
+    /// Display name of the package
+    pub name: String,
+
+    /// Version the info is about
+    pub version: Version,
+
+    /// Every version of the package, newest first
+    pub versions: Vec<Version>,
+
+    /// Url of the package
+    pub url: Option<String>,
+
+    /// Branch the sources are downloaded from
+    pub branch: Option<String>,
+
+    /// Whether the package only provides headers
+    pub headers_only: bool,
+
+    /// Whether the package is linked statically
+    pub static_linking: bool,
+
+    pub so_link: Option<String>,
+
+    pub debug_so_link: Option<String>,
+
+    pub static_link: Option<String>,
+
+    pub mod_link: Option<String>,
+
+    /// Direct dependencies of the package
+    pub dependencies: Vec<InfoDependency>,
+
+    /// Repository that serves this version
+    pub repository: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InfoDependency {
+    /// Id of the dependency
+    pub id: String,
+
+    /// Version range requested by the package
+    pub version_range: String,
+}
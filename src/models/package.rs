@@ -1,4 +1,9 @@
-use std::{collections::HashSet, fs::File, io::BufReader, path::Path};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
 
 use color_eyre::{Result, Section, eyre::Context, owo_colors::OwoColorize};
 use itertools::Itertools;
@@ -6,15 +11,17 @@ use qpm_package::{
     extensions::package_metadata::PackageMetadataExtensions,
     models::{
         dependency::{Dependency, SharedDependency, SharedPackageConfig},
-        package::PackageConfig,
+        package::{PackageConfig, PackageMetadata},
     },
 };
 use qpm_qmod::models::mod_json::{ModDependency, ModJson};
 use semver::VersionReq;
+use serde::Serialize;
 
 use crate::{repository::Repository, resolver::dependency::resolve, utils::json};
 
 use super::{
+    abi::{AbiLinksMap, read_abi_links},
     schemas::{SchemaLinks, WithSchema},
     toolchain,
 };
@@ -22,6 +29,45 @@ use super::{
 pub const PACKAGE_FILE_NAME: &str = "qpm.json";
 pub const SHARED_PACKAGE_FILE_NAME: &str = "qpm.shared.json";
 
+/// A package file with the fields qpm_package does not know about
+#[derive(Serialize)]
+struct PackageFile<'a, T> {
+    #[serde(flatten)]
+    value: &'a T,
+
+    #[serde(rename = "abiLinks", skip_serializing_if = "Option::is_none")]
+    abi_links: Option<&'a AbiLinksMap>,
+}
+
+impl<'a, T> PackageFile<'a, T> {
+    fn new(value: &'a T, abi_links: &'a AbiLinksMap) -> Self {
+        Self {
+            value,
+            abi_links: (!abi_links.is_empty()).then_some(abi_links),
+        }
+    }
+}
+
+/// Writes `config` to `qpm.json` in `dir` with `abi_links` replacing the existing ones
+pub fn write_with_abi_links<P: AsRef<Path>>(
+    config: &PackageConfig,
+    dir: P,
+    abi_links: &AbiLinksMap,
+) -> Result<()> {
+    let path = dir.as_ref().join(PACKAGE_FILE_NAME);
+    let file = File::create(&path).with_context(|| format!("{path:?} cannot be written"))?;
+
+    serde_json::to_writer_pretty(
+        file,
+        &WithSchema {
+            schema: SchemaLinks::PACKAGE_CONFIG,
+            value: PackageFile::new(config, abi_links),
+        },
+    )
+    .with_context(|| format!("Unable to write PackageConfig at {path:?}"))?;
+    Ok(())
+}
+
 pub trait PackageConfigExtensions {
     fn exists<P: AsRef<Path>>(dir: P) -> bool;
     fn read<P: AsRef<Path>>(dir: P) -> Result<Self>
@@ -43,9 +89,45 @@ pub trait SharedPackageConfigExtensions: Sized {
         repository: &impl Repository,
     ) -> Result<(Self, Vec<SharedPackageConfig>)>;
 
+    /// Mods with a qmod link the qmod should depend on
+    fn qmod_dependencies(&self) -> Vec<ModDependency>;
+
+    /// Restored dependencies whose binary is bundled into the qmod
+    fn qmod_libraries(&self) -> Vec<&SharedDependency>;
+
     fn to_mod_json(self) -> ModJson;
 
-    fn try_write_toolchain(&self, repo: &impl Repository) -> Result<()>;
+    /// Writes the toolchain file if `toolchainOut` is set, relative to `workspace`
+    fn try_write_toolchain(&self, workspace: &Path, repo: &impl Repository) -> Result<()>;
+}
+
+/// Binaries of packages that set `staticLinking` and ship a `.a` archive instead of a `.so`
+pub trait StaticLibraryExtensions {
+    /// Whether dependents link the package statically, its binary is then never bundled into qmods
+    fn is_static_linking(&self) -> bool;
+
+    /// File name of the static archive, the shared library name with an `.a` extension
+    fn get_static_name(&self) -> PathBuf;
+
+    /// File name of the release binary in the cache and the dependencies directory
+    fn get_binary_name(&self) -> PathBuf;
+}
+
+impl StaticLibraryExtensions for PackageMetadata {
+    fn is_static_linking(&self) -> bool {
+        self.additional_data.static_linking.unwrap_or(false)
+    }
+
+    fn get_static_name(&self) -> PathBuf {
+        self.get_so_name2().with_extension("a")
+    }
+
+    fn get_binary_name(&self) -> PathBuf {
+        match self.is_static_linking() {
+            true => self.get_static_name(),
+            false => self.get_so_name2(),
+        }
+    }
 }
 
 impl PackageConfigExtensions for PackageConfig {
@@ -53,29 +135,28 @@ impl PackageConfigExtensions for PackageConfig {
         let path = dir.as_ref().join(PACKAGE_FILE_NAME);
         let file = File::open(&path).with_context(|| format!("{path:?} does not exist"))?;
         let res = json::json_from_reader_fast::<_, Self>(BufReader::new(file))
-            .with_context(|| format!("Unable to read PackageConfig at {path:?}"))?;
+            .with_context(|| format!("Unable to read PackageConfig at {path:?}"))
+            .with_suggestion(|| {
+                format!(
+                    "If it was made by an older qpm, try {}",
+                    "qpm package migrate".blue()
+                )
+            })?;
         res.validate()?;
 
         Ok(res)
     }
 
+    /// Keeps the `abiLinks` of the existing file
     fn write<P: AsRef<Path>>(&self, dir: P) -> Result<()> {
         let path = dir.as_ref().join(PACKAGE_FILE_NAME);
-        let file = File::create(&path).with_context(|| format!("{path:?} cannot be written"))?;
+        let abi_links = read_abi_links(&path).unwrap_or_default();
 
-        serde_json::to_writer_pretty(
-            file,
-            &WithSchema {
-                schema: SchemaLinks::PACKAGE_CONFIG,
-                value: self,
-            },
-        )
-        .with_context(|| format!("Unable to write PackageConfig at {path:?}"))?;
-        Ok(())
+        write_with_abi_links(self, dir, &abi_links)
     }
 
     fn exists<P: AsRef<Path>>(dir: P) -> bool {
-        dir.as_ref().with_file_name(PACKAGE_FILE_NAME).exists()
+        dir.as_ref().join(PACKAGE_FILE_NAME).exists()
     }
 
     fn run_if_version(
@@ -96,10 +177,18 @@ impl PackageConfigExtensions for PackageConfig {
     fn validate(&self) -> color_eyre::Result<()> {
         let default = Self::default();
 
-        if self.version.major != default.version.major {
-            eprintln!(
+        if self.version < default.version {
+            log::warn!(
+                "Warning: using outdated qpm schema {}, the latest is {}. Run {} to upgrade",
+                self.version,
+                default.version,
+                "qpm package migrate".blue()
+            );
+        } else if self.version.major != default.version.major {
+            log::warn!(
                 "Warning: using outdate qpm schema. Current {} Latest: {:?}",
-                self.version, default.version
+                self.version,
+                default.version
             );
         }
 
@@ -115,17 +204,27 @@ impl PackageConfigExtensions for SharedPackageConfig {
 
         json::json_from_reader_fast(BufReader::new(file))
             .with_context(|| format!("Unable to read SharedPackageConfig at {path:?}"))
+            .with_suggestion(|| {
+                format!(
+                    "If it was made by an older qpm, try {}",
+                    "qpm package migrate".blue()
+                )
+            })
     }
 
+    /// Takes the `abiLinks` of `qpm.json` next to it, or keeps those of the existing file
     fn write<P: AsRef<Path>>(&self, dir: P) -> Result<()> {
         let path = dir.as_ref().join(SHARED_PACKAGE_FILE_NAME);
+        let abi_links = read_abi_links(&dir.as_ref().join(PACKAGE_FILE_NAME))
+            .or_else(|| read_abi_links(&path))
+            .unwrap_or_default();
         let file = File::create(&path).with_context(|| format!("{path:?} cannot be written"))?;
 
         serde_json::to_writer_pretty(
             file,
             &WithSchema {
                 schema: SchemaLinks::SHARED_PACKAGE_CONFIG,
-                value: self,
+                value: PackageFile::new(self, &abi_links),
             },
         )
         .with_context(|| format!("Unable to write PackageConfig at {path:?}"))?;
@@ -182,13 +281,7 @@ impl SharedPackageConfigExtensions for SharedPackageConfig {
         ))
     }
 
-    fn to_mod_json(self) -> ModJson {
-        //        Self {
-        //     id: dep.id,
-        //     version_range: dep.version_range,
-        //     mod_link: dep.additional_data.mod_link,
-        // }
-
+    fn qmod_dependencies(&self) -> Vec<ModDependency> {
         let local_deps = &self.config.dependencies;
 
         // Only bundle mods that are not specifically excluded in qpm.json or if they're not header-only
@@ -213,18 +306,10 @@ impl SharedPackageConfigExtensions for SharedPackageConfig {
             })
             .collect();
 
-        // List of dependencies we are directly referencing in qpm.json
-        let direct_dependencies: HashSet<String> = self
-            .config
-            .dependencies
-            .iter()
-            .map(|f| f.id.clone())
-            .collect();
-
         // downloadable mods links n stuff
         // mods that are header-only but provide qmods can be added as deps
         // Must be directly referenced in qpm.json
-        let mods: Vec<ModDependency> = local_deps
+        local_deps
             .iter()
             // Removes any dependency without a qmod link
             .filter_map(|dep| {
@@ -241,12 +326,24 @@ impl SharedPackageConfigExtensions for SharedPackageConfig {
                 mod_link: shared_dep.dependency.additional_data.mod_link.clone(),
                 required: dep.additional_data.required,
             })
+            .collect()
+    }
+
+    fn qmod_libraries(&self) -> Vec<&SharedDependency> {
+        let local_deps = &self.config.dependencies;
+        let mods = self.qmod_dependencies();
+
+        // List of dependencies we are directly referencing in qpm.json
+        let direct_dependencies: HashSet<String> = self
+            .config
+            .dependencies
+            .iter()
+            .map(|f| f.id.clone())
             .collect();
 
         // The rest of the mods to handle are not qmods, they are .so or .a mods
         // actual direct lib deps
-        let libs: Vec<String> = self
-            .restored_dependencies
+        self.restored_dependencies
             .iter()
             // We could just query the bmbf core mods list on GH?
             // https://github.com/BMBF/resources/blob/master/com.beatgames.beatsaber/core-mods.json
@@ -281,6 +378,14 @@ impl SharedPackageConfigExtensions for SharedPackageConfig {
                 // Only keep libs that aren't downloadable
                 !mods.iter().any(|dep| lib.dependency.id == dep.id)
             })
+            .collect()
+    }
+
+    fn to_mod_json(self) -> ModJson {
+        let mods = self.qmod_dependencies();
+        let libs = self
+            .qmod_libraries()
+            .iter()
             .map(|dep| dep.get_so_name().to_str().unwrap().to_string())
             .collect();
 
@@ -302,12 +407,12 @@ impl SharedPackageConfigExtensions for SharedPackageConfig {
         }
     }
 
-    fn try_write_toolchain(&self, repo: &impl Repository) -> Result<()> {
+    fn try_write_toolchain(&self, workspace: &Path, repo: &impl Repository) -> Result<()> {
         let Some(toolchain_path) = self.config.info.additional_data.toolchain_out.as_ref() else {
             return Ok(());
         };
 
-        toolchain::write_toolchain_file(self, repo, toolchain_path)?;
+        toolchain::write_toolchain_file(self, repo, &workspace.join(toolchain_path))?;
 
         Ok(())
     }
@@ -0,0 +1,210 @@
+//! Software bill of materials of a package and its restored dependencies
+
+use semver::Version;
+use serde_json::{Value, json};
+
+/// Supported SBOM standards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SbomStandard {
+    /// CycloneDX 1.5 JSON
+    Cyclonedx,
+    /// SPDX 2.3 JSON
+    Spdx,
+}
+
+/// A package in the bill of materials
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SbomComponent {
+    pub id: String,
+    pub version: Version,
+    /// Repository the sources are downloaded from
+    pub url: Option<String>,
+    pub branch: Option<String>,
+    /// SPDX license expression
+    pub license: Option<String>,
+    /// Download links of the binaries, keyed by the qpm.json field they come from
+    pub binary_links: Vec<(&'static str, String)>,
+    /// SHA-256 of the cached release binary of the primary ABI, the file shipped in the qmod.
+    /// Debug binaries, headers and binaries of other ABIs are not hashed
+    pub sha256: Option<String>,
+    /// Whether the binary is shipped inside the qmod
+    pub bundled: bool,
+    /// Ids of the components this one depends on
+    pub depends_on: Vec<String>,
+}
+
+impl SbomComponent {
+    fn bom_ref(&self) -> String {
+        format!("{}@{}", self.id, self.version)
+    }
+
+    fn spdx_id(&self) -> String {
+        let id = self
+            .id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect::<String>();
+        format!("SPDXRef-Package-{id}")
+    }
+
+    /// Location of the sources in SPDX download location syntax
+    fn download_location(&self) -> String {
+        match (&self.url, &self.branch) {
+            (Some(url), Some(branch)) => format!("git+{url}@{branch}"),
+            (Some(url), None) => format!("git+{url}"),
+            _ => "NOASSERTION".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sbom {
+    /// The package being described
+    pub root: SbomComponent,
+    pub components: Vec<SbomComponent>,
+    /// RFC 3339 creation time
+    pub timestamp: String,
+}
+
+impl Sbom {
+    pub fn render(&self, standard: SbomStandard) -> Value {
+        match standard {
+            SbomStandard::Cyclonedx => self.to_cyclonedx(),
+            SbomStandard::Spdx => self.to_spdx(),
+        }
+    }
+
+    pub fn to_cyclonedx(&self) -> Value {
+        let component = |c: &SbomComponent, kind: &str| {
+            let mut external_references = vec![];
+            if let Some(url) = &c.url {
+                external_references.push(json!({ "type": "vcs", "url": url }));
+            }
+            external_references.extend(c.binary_links.iter().map(
+                |(field, link)| json!({ "type": "distribution", "url": link, "comment": field }),
+            ));
+
+            let mut properties =
+                vec![json!({ "name": "qpm:bundled", "value": c.bundled.to_string() })];
+            if let Some(branch) = &c.branch {
+                properties.push(json!({ "name": "qpm:branch", "value": branch }));
+            }
+
+            let mut value = json!({
+                "type": kind,
+                "bom-ref": c.bom_ref(),
+                "name": c.id,
+                "version": c.version.to_string(),
+                "externalReferences": external_references,
+                "properties": properties,
+            });
+            if let Some(sha256) = &c.sha256 {
+                value["hashes"] = json!([{ "alg": "SHA-256", "content": sha256 }]);
+            }
+            if let Some(license) = &c.license {
+                value["licenses"] = json!([{ "expression": license }]);
+            }
+            value
+        };
+
+        let dependencies = [&self.root]
+            .into_iter()
+            .chain(&self.components)
+            .map(|c| {
+                json!({
+                    "ref": c.bom_ref(),
+                    "dependsOn": self.refs_of(&c.depends_on).map(SbomComponent::bom_ref).collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "$schema": "http://cyclonedx.org/schema/bom-1.5.schema.json",
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "metadata": {
+                "timestamp": self.timestamp,
+                "tools": {
+                    "components": [{
+                        "type": "application",
+                        "name": "qpm",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    }]
+                },
+                "component": component(&self.root, "application"),
+            },
+            "components": self.components.iter().map(|c| component(c, "library")).collect::<Vec<_>>(),
+            "dependencies": dependencies,
+        })
+    }
+
+    pub fn to_spdx(&self) -> Value {
+        let package = |c: &SbomComponent| {
+            let mut value = json!({
+                "name": c.id,
+                "SPDXID": c.spdx_id(),
+                "versionInfo": c.version.to_string(),
+                "downloadLocation": c.download_location(),
+                "filesAnalyzed": false,
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": c.license.as_deref().unwrap_or("NOASSERTION"),
+                "copyrightText": "NOASSERTION",
+                "externalRefs": c.binary_links.iter().map(|(field, link)| json!({
+                    "referenceCategory": "OTHER",
+                    "referenceType": field,
+                    "referenceLocator": link,
+                })).collect::<Vec<_>>(),
+            });
+            if let Some(sha256) = &c.sha256 {
+                value["checksums"] = json!([{ "algorithm": "SHA256", "checksumValue": sha256 }]);
+            }
+            if c.bundled {
+                value["comment"] = json!("Bundled into the qmod");
+            }
+            value
+        };
+
+        let mut relationships = vec![json!({
+            "spdxElementId": "SPDXRef-DOCUMENT",
+            "relationshipType": "DESCRIBES",
+            "relatedSpdxElement": self.root.spdx_id(),
+        })];
+        for c in [&self.root].into_iter().chain(&self.components) {
+            for dep in self.refs_of(&c.depends_on) {
+                // the qmod ships bundled binaries, the rest is only linked against
+                let kind = if c == &self.root && dep.bundled {
+                    "CONTAINS"
+                } else {
+                    "DEPENDS_ON"
+                };
+                relationships.push(json!({
+                    "spdxElementId": c.spdx_id(),
+                    "relationshipType": kind,
+                    "relatedSpdxElement": dep.spdx_id(),
+                }));
+            }
+        }
+
+        let name = self.root.bom_ref();
+        json!({
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": name,
+            "documentNamespace": format!("https://spdx.org/spdxdocs/{name}-{}", self.timestamp),
+            "creationInfo": {
+                "created": self.timestamp,
+                "creators": [format!("Tool: qpm-{}", env!("CARGO_PKG_VERSION"))],
+            },
+            "packages": [&self.root].into_iter().chain(&self.components).map(package).collect::<Vec<_>>(),
+            "relationships": relationships,
+        })
+    }
+
+    fn refs_of<'a>(&'a self, ids: &'a [String]) -> impl Iterator<Item = &'a SbomComponent> {
+        ids.iter()
+            .filter_map(|id| self.components.iter().find(|c| &c.id == id))
+    }
+}
@@ -19,6 +19,20 @@ impl SchemaLinks {
     pub const SHARED_PACKAGE_CONFIG: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.Package/refs/heads/main/qpm.shared.schema.json";
     pub const USER_CONFIG: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.settings.schema.json";
     pub const FILE_REPOSITORY: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.repository.schema.json";
+    pub const PACKAGE_FILE_EXTENSIONS: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.package-extensions.schema.json";
     pub const TOOLCHAIN_DATA: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.toolchain.schema.json";
+    pub const OUTPUT_PACKAGE_LIST: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.packages.schema.json";
+    pub const OUTPUT_PACKAGE_VERSIONS: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.versions.schema.json";
+    pub const OUTPUT_CACHE_LIST: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.cache.schema.json";
+    pub const OUTPUT_COLLAPSE: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.collapse.schema.json";
+    pub const OUTPUT_NDK_LIST: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.ndk-list.schema.json";
+    pub const OUTPUT_NDK_AVAILABLE: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.ndk-available.schema.json";
+    pub const OUTPUT_DOCTOR: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.doctor.schema.json";
+    pub const OUTPUT_VERSION_CHECK: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.version-check.schema.json";
+    pub const OUTPUT_LINT: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.lint.schema.json";
+    pub const OUTPUT_LICENSES: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.licenses.schema.json";
+    pub const OUTPUT_AUDIT: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.audit.schema.json";
+    pub const OUTPUT_SEARCH: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.search.schema.json";
+    pub const OUTPUT_INFO: &'static str = "https://raw.githubusercontent.com/QuestPackageManager/QPM.CLI/refs/heads/main/qpm.output.info.schema.json";
     pub const MOD_CONFIG: &'static str = "https://raw.githubusercontent.com/Lauriethefish/QuestPatcher.QMod/refs/heads/main/QuestPatcher.QMod/Resources/qmod.schema.json";
 }
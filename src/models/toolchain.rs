@@ -1,4 +1,4 @@
-use std::{fs::File, path::PathBuf};
+use std::{fs, path::PathBuf};
 
 use color_eyre::eyre::Result;
 use qpm_package::models::{dependency::SharedPackageConfig, extra::CompileOptions};
@@ -29,6 +29,15 @@ pub fn write_toolchain_file(
     repo: &impl Repository,
     toolchain_path: &std::path::PathBuf,
 ) -> Result<()> {
+    fs::write(toolchain_path, make_toolchain_string(shared_config, repo)?)?;
+    Ok(())
+}
+
+/// Contents of the toolchain file of `shared_config`
+pub fn make_toolchain_string(
+    shared_config: &SharedPackageConfig,
+    repo: &impl Repository,
+) -> Result<String> {
     let extern_dir = &shared_config.config.dependencies_dir.display();
     let compile_options = shared_config
         .restored_dependencies
@@ -101,13 +110,8 @@ pub fn write_toolchain_file(
         binary_out: None,
         debug_binary_out: None,
     };
-    let file = File::create(toolchain_path)?;
-    serde_json::to_writer_pretty(
-        file,
-        &WithSchema {
-            schema: SchemaLinks::TOOLCHAIN_DATA,
-            value: toolchain,
-        },
-    )?;
-    Ok(())
+    Ok(serde_json::to_string_pretty(&WithSchema {
+        schema: SchemaLinks::TOOLCHAIN_DATA,
+        value: toolchain,
+    })?)
 }
@@ -1,6 +1,7 @@
 use std::{
     env,
     io::{ErrorKind, Read, Write},
+    net::Ipv4Addr,
     sync,
     thread::sleep,
     time::Duration,
@@ -15,31 +16,71 @@ use reqwest::header::CONTENT_LENGTH;
 use crate::models::config::get_combine_config;
 
 static AGENT: sync::OnceLock<reqwest::blocking::Client> = sync::OnceLock::new();
+static LOOPBACK_AGENT: sync::OnceLock<reqwest::blocking::Client> = sync::OnceLock::new();
 
-pub fn get_agent() -> &'static reqwest::blocking::Client {
+fn build_agent(https_only: bool) -> reqwest::blocking::Client {
     let timeout = get_combine_config().timeout.unwrap_or(5000);
 
-    AGENT.get_or_init(|| {
-        reqwest::blocking::ClientBuilder::new()
-            .connect_timeout(Duration::from_millis(timeout.into()))
-            .tcp_keepalive(Duration::from_secs(5))
-            .tcp_nodelay(false)
-            .https_only(true)
-            .user_agent(format!("questpackagemanager-rust2/{}", env!("CARGO_PKG_VERSION")).as_str())
-            .build()
-            .expect("Client agent was not buildable")
-    })
+    reqwest::blocking::ClientBuilder::new()
+        .connect_timeout(Duration::from_millis(timeout.into()))
+        .tcp_keepalive(Duration::from_secs(5))
+        .tcp_nodelay(false)
+        .https_only(https_only)
+        .user_agent(format!("questpackagemanager-rust2/{}", env!("CARGO_PKG_VERSION")).as_str())
+        .build()
+        .expect("Client agent was not buildable")
+}
+
+pub fn get_agent() -> &'static reqwest::blocking::Client {
+    AGENT.get_or_init(|| build_agent(true))
+}
+
+/// The agent to request `url` with. Plain http is only allowed for servers on this machine,
+/// like the mock server of the tests
+pub fn get_agent_for(url: &str) -> &'static reqwest::blocking::Client {
+    match is_loopback_http(url) {
+        true => LOOPBACK_AGENT.get_or_init(|| build_agent(false)),
+        false => get_agent(),
+    }
+}
+
+fn is_loopback_http(url: &str) -> bool {
+    url.starts_with("http://") && is_loopback(url)
+}
+
+/// Whether `url` points at this machine
+pub fn is_loopback(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    match url.host_str() {
+        Some("localhost" | "[::1]") => true,
+        Some(host) => host.parse::<Ipv4Addr>().is_ok_and(|ip| ip.is_loopback()),
+        None => false,
+    }
+}
+
+/// The url in `env_var` if it points at this machine, like the mock server of the tests.
+/// Other hosts are ignored, so credentials meant for `default` are never sent elsewhere
+pub fn loopback_override(env_var: &str) -> Option<String> {
+    let url = env::var(env_var).ok()?;
+    if !is_loopback(&url) {
+        log::warn!("Ignoring {env_var}={url}, only servers on this machine may be used");
+        return None;
+    }
+    Some(url.trim_end_matches('/').to_string())
 }
 
 pub fn download_file<F>(url: &str, buffer: &mut impl Write, mut callback: F) -> Result<usize>
 where
     F: FnMut(usize, usize),
 {
-    let mut request = get_agent().get(url).build()?;
+    let agent = get_agent_for(url);
+    let mut request = agent.get(url).build()?;
 
     request.timeout_mut().take(); // Set to none
 
-    let mut response = get_agent()
+    let mut response = agent
         .execute(request)
         .with_context(|| format!("Unable to download file {url}"))?
         .error_for_status()?;
@@ -47,7 +88,7 @@ where
     let expected_amount = response.content_length().unwrap_or(0) as usize;
 
     if expected_amount == 0 {
-        println!("Unable to determine content length for download from {url}");
+        log::warn!("Unable to determine content length for download from {url}");
     }
 
     let mut written: usize = 0;
@@ -77,8 +118,8 @@ where
     }
 
     if written != expected_amount {
-        println!("Downloaded size does not match expected size!");
-        println!("Read: 0x{written:x} Expected: 0x{expected_amount:x}");
+        log::warn!("Downloaded size does not match expected size!");
+        log::warn!("Read: 0x{written:x} Expected: 0x{expected_amount:x}");
     }
 
     Ok(expected_amount)
@@ -101,7 +142,12 @@ where
 {
     use pbr::ProgressBar;
 
-    let mut progress_bar = ProgressBar::new(0);
+    if crate::terminal::logging::is_quiet() {
+        return download_file(url, buffer, callback);
+    }
+
+    // keep stdout clean for command output
+    let mut progress_bar = ProgressBar::on(std::io::stderr(), 0);
     progress_bar.set_units(pbr::Units::Bytes);
 
     if env::var("CI") == Ok("true".to_string()) {
@@ -115,8 +161,9 @@ where
         callback(current, expected)
     });
 
-    progress_bar.finish_println("Finished download!");
-    println!();
+    // clear the bar, the logger reports the download like every other step
+    progress_bar.finish_println("");
+    log::info!("Finished download!");
 
     result
 }
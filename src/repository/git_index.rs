@@ -0,0 +1,247 @@
+//! Reads packages published to a git index registry, see [`super::registry`].
+//!
+//! The index holds `<id>/<version>.json` shared configs and `<id>/yanked.json`
+//! listing the yanked versions of a package.
+
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use color_eyre::{Result, eyre::Context};
+use itertools::Itertools;
+use qpm_package::models::{
+    backend::PackageVersion, dependency::SharedPackageConfig, package::PackageConfig,
+};
+use semver::Version;
+
+use crate::{
+    models::{
+        abi::Abi,
+        config::{RegistryConfig, get_combine_config, get_registry_keyring},
+    },
+    terminal::colors::QPMColor,
+    utils::{git, json},
+};
+
+use super::{Repository, local::FileRepository, qpackages::QPMRepository};
+
+/// File in a git index listing the yanked versions of a package
+pub const YANKED_FILE: &str = "yanked.json";
+
+pub struct GitIndexRepository {
+    name: String,
+    url: String,
+    branch: Option<String>,
+    /// Local clone of the index
+    path: PathBuf,
+    /// Whether the clone of the index is usable, synced on first use
+    synced: OnceLock<bool>,
+}
+
+impl GitIndexRepository {
+    pub fn new(name: String, url: String, branch: Option<String>, path: PathBuf) -> Self {
+        Self {
+            name,
+            url,
+            branch,
+            path,
+            synced: OnceLock::new(),
+        }
+    }
+
+    /// A repository for every git registry in the user config
+    pub fn configured() -> Vec<Self> {
+        get_combine_config()
+            .registries
+            .iter()
+            .flatten()
+            .filter_map(|(name, registry)| match registry {
+                RegistryConfig::Git { url, branch } => Some(Self::new(
+                    name.clone(),
+                    url.clone(),
+                    branch.clone(),
+                    Self::index_path(name),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Where the index of registry `name` is cloned to
+    pub fn index_path(name: &str) -> PathBuf {
+        get_combine_config()
+            .cache
+            .as_ref()
+            .unwrap()
+            .join("registries")
+            .join(name)
+    }
+
+    /// The synced clone, `None` if the index was never cloned successfully
+    fn index(&self) -> Option<&Path> {
+        let synced = *self.synced.get_or_init(|| {
+            if let Err(e) = self.sync(&self.path) {
+                log::warn!(
+                    "Unable to update registry {}: {e:?}",
+                    self.name.dependency_id_color()
+                );
+            }
+            self.path.join(".git").exists()
+        });
+
+        synced.then_some(self.path.as_path())
+    }
+
+    /// Clones the index, or fetches the newest commit of an existing clone
+    fn sync(&self, path: &Path) -> Result<()> {
+        git::check_git()?;
+        let auth = get_registry_keyring(&self.name).and_then(|e| e.get_password().ok());
+
+        if path.join(".git").exists() {
+            let branch = self.branch.as_deref().unwrap_or("HEAD");
+            git::run_with_auth(
+                &["fetch", "--depth", "1", "origin", branch],
+                path,
+                auth.as_deref(),
+            )?;
+            return git::run(&["reset", "--hard", "FETCH_HEAD"], path);
+        }
+
+        // clone next to the index, so a failed clone never looks like an empty index
+        let tmp_path = path.with_added_extension("tmp");
+        if tmp_path.exists() {
+            fs::remove_dir_all(&tmp_path)?;
+        }
+        if path.exists() {
+            fs::remove_dir_all(path)?;
+        }
+        fs::create_dir_all(path.parent().unwrap())?;
+
+        let tmp = tmp_path.to_string_lossy().to_string();
+        let mut clone_args = vec!["clone", "--depth", "1", self.url.as_str(), tmp.as_str()];
+        if let Some(branch) = &self.branch {
+            clone_args.extend(["--branch", branch.as_str()]);
+        }
+        let cloned = git::run_with_auth(&clone_args, Path::new("."), auth.as_deref());
+        if let Err(e) = cloned {
+            let _ = fs::remove_dir_all(&tmp_path);
+            return Err(e);
+        }
+
+        fs::rename(&tmp_path, path)
+            .with_context(|| format!("Unable to move the index to {}", path.display()))
+    }
+
+    fn read_yanked_versions(index: &Path, id: &str) -> Result<Vec<Version>> {
+        let path = index.join(id).join(YANKED_FILE);
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+
+        json::json_from_reader_fast(BufReader::new(File::open(&path)?))
+            .with_context(|| format!("Invalid {}", path.display()))
+    }
+}
+
+impl Repository for GitIndexRepository {
+    fn get_package_names(&self) -> Result<Vec<String>> {
+        let Some(index) = self.index() else {
+            return Ok(vec![]);
+        };
+
+        Ok(fs::read_dir(index)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| !name.starts_with('.'))
+            .sorted()
+            .collect())
+    }
+
+    /// Yanked versions are left out, so resolving never picks them
+    fn get_package_versions(&self, id: &str) -> Result<Option<Vec<PackageVersion>>> {
+        let Some(index) = self.index() else {
+            return Ok(None);
+        };
+        let package_dir = index.join(id);
+        if !package_dir.is_dir() {
+            return Ok(None);
+        }
+
+        let yanked = Self::read_yanked_versions(index, id)?;
+        let versions = fs::read_dir(package_dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| Version::parse(&path.file_stem()?.to_string_lossy()).ok())
+            .filter(|version| !yanked.contains(version))
+            .sorted()
+            .rev()
+            .map(|version| PackageVersion {
+                id: id.to_string(),
+                version,
+            })
+            .collect_vec();
+
+        Ok(Some(versions))
+    }
+
+    /// Yanked versions are still returned, so lock files using them keep restoring
+    fn get_package(&self, id: &str, version: &Version) -> Result<Option<SharedPackageConfig>> {
+        let Some(index) = self.index() else {
+            return Ok(None);
+        };
+        let path = index.join(id).join(format!("{version}.json"));
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let package = json::json_from_reader_fast(BufReader::new(File::open(&path)?))
+            .with_context(|| format!("Invalid {}", path.display()))?;
+        Ok(Some(package))
+    }
+
+    fn yanked_versions(&self, id: &str) -> Result<Vec<Version>> {
+        let Some(index) = self.index() else {
+            return Ok(vec![]);
+        };
+        Self::read_yanked_versions(index, id)
+    }
+
+    fn add_to_db_cache(&mut self, _config: SharedPackageConfig, _permanent: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn download_to_cache(&mut self, config: &PackageConfig) -> Result<bool> {
+        QPMRepository::download_package_to(
+            config,
+            &FileRepository::get_package_cache_path(&config.info.id, &config.info.version),
+            &Abi::selected(),
+        )
+        .with_context(|| {
+            format!(
+                "Registry {} {}:{}",
+                self.name.dependency_id_color(),
+                config.info.id.dependency_id_color(),
+                config.info.version.version_id_color()
+            )
+        })?;
+
+        Ok(true)
+    }
+
+    fn write_repo(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_online(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        format!("registry {}", self.name)
+    }
+}
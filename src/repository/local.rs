@@ -1,6 +1,6 @@
 use color_eyre::{
-    Result,
-    eyre::{Context, OptionExt, bail, ensure},
+    Result, Section,
+    eyre::{Context, OptionExt, bail, ensure, eyre},
 };
 use itertools::Itertools;
 use owo_colors::OwoColorize;
@@ -8,12 +8,13 @@ use schemars::JsonSchema;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, hash_map::Entry},
+    collections::{HashMap, HashSet, hash_map::Entry},
     fs,
     io::{BufReader, Write},
     ops::Not,
     path::{Path, PathBuf},
 };
+use walkdir::WalkDir;
 
 use qpm_package::{
     extensions::package_metadata::PackageMetadataExtensions,
@@ -22,12 +23,17 @@ use qpm_package::{
 
 use crate::{
     models::{
+        abi::{Abi, AbiLinks},
         config::get_combine_config,
-        package::PackageConfigExtensions,
+        package::{PackageConfigExtensions, StaticLibraryExtensions},
         schemas::{SchemaLinks, WithSchema},
     },
     terminal::colors::QPMColor,
-    utils::{fs::copy_things, json},
+    utils::{
+        elf::{self, SharedObjectInfo},
+        fs::{copy_equal, copy_things, files_equal, normalize_lexically, remove_path},
+        json,
+    },
 };
 
 use super::Repository;
@@ -40,6 +46,32 @@ pub struct PackageFiles {
     // pub extras: Vec<PathBuf>,
 }
 
+/// A single filesystem change to the dependencies dir
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExternChange {
+    /// Symlink `destination` to `source`
+    Link {
+        source: PathBuf,
+        destination: PathBuf,
+    },
+    /// Copy the file `source` over `destination`
+    Copy {
+        source: PathBuf,
+        destination: PathBuf,
+    },
+    /// Delete a file, folder or symlink
+    Remove(PathBuf),
+}
+
+/// A file or folder copied from the cache into the dependencies dir
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyTarget {
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    /// Package that provides the source
+    pub package_id: String,
+}
+
 // TODO: Somehow make a global singleton of sorts/cached instance to share across places
 // like resolver
 #[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default)]
@@ -92,13 +124,16 @@ impl FileRepository {
         Ok(())
     }
 
-    /// for local qpm-rs installs
+    /// for local qpm-rs installs.
+    /// `resolved_deps` are the restored dependencies of `package`, that must provide the libraries its binaries need
+    #[allow(clippy::too_many_arguments)]
     pub fn add_artifact_and_cache(
         &mut self,
         package: SharedPackageConfig,
         project_folder: PathBuf,
         binary_path: Option<PathBuf>,
         debug_binary_path: Option<PathBuf>,
+        resolved_deps: &[SharedPackageConfig],
         copy: bool,
         overwrite_existing: bool,
     ) -> Result<()> {
@@ -108,6 +143,7 @@ impl FileRepository {
                 project_folder,
                 binary_path,
                 debug_binary_path,
+                resolved_deps,
                 false,
             )?;
         }
@@ -121,13 +157,22 @@ impl FileRepository {
         project_folder: PathBuf,
         binary_path: Option<PathBuf>,
         debug_binary_path: Option<PathBuf>,
+        resolved_deps: &[SharedPackageConfig],
         validate: bool,
     ) -> Result<()> {
-        println!(
+        log::info!(
             "Adding cache for local dependency {} {}",
             package.config.info.id.bright_red(),
             package.config.info.version.bright_green()
         );
+
+        // checked before anything is copied, a bad build must not replace a cached one
+        let binaries = [&binary_path, &debug_binary_path]
+            .into_iter()
+            .flatten()
+            .filter(|binary| binary.exists())
+            .collect_vec();
+        Self::validate_local_binaries(package, &binaries, resolved_deps)?;
         let config = get_combine_config();
         let cache_path = config
             .cache
@@ -138,7 +183,8 @@ impl FileRepository {
 
         let tmp_path = cache_path.join("tmp");
         let src_path = cache_path.join("src");
-        let lib_path = cache_path.join("lib");
+        // `qpm install` only has the binaries of the ABI that was built
+        let lib_path = Abi::primary().cache_libs_path(&cache_path.join("lib"));
 
         if src_path.exists() {
             fs::remove_dir_all(&src_path).context("Failed to remove existing src folder")?;
@@ -147,7 +193,7 @@ impl FileRepository {
         fs::create_dir_all(&src_path).context("Failed to create lib path")?;
 
         if let Some(binary_path_unwrapped) = &binary_path {
-            let so_path = lib_path.join(package.config.info.get_so_name2());
+            let so_path = lib_path.join(package.config.info.get_binary_name());
 
             copy_things(binary_path_unwrapped, &so_path)?;
         }
@@ -204,6 +250,26 @@ impl FileRepository {
         Ok(())
     }
 
+    /// Validates binaries built for the primary ABI like restored ones.
+    /// Unlike cached downloads, the SONAME must match the name dependents link against
+    fn validate_local_binaries(
+        package: &SharedPackageConfig,
+        binaries: &[&PathBuf],
+        resolved_deps: &[SharedPackageConfig],
+    ) -> Result<()> {
+        let abi = Abi::primary();
+        for binary in binaries {
+            if package.config.info.is_static_linking() {
+                elf::ensure_static_archive(binary)?;
+                continue;
+            }
+
+            let info = Self::validate_binary(&package.config, binary, abi, true)?;
+            Self::validate_needed(package, &info, abi, resolved_deps)?;
+        }
+        Ok(())
+    }
+
     /// always gets the global config
     pub fn read() -> Result<Self> {
         let path = Self::global_file_repository_path();
@@ -231,7 +297,7 @@ impl FileRepository {
             .context("Failed to make config folder")?;
         let mut file = std::fs::File::create(path)?;
         file.write_all(config.as_bytes())?;
-        println!("Saved local repository Config!");
+        log::info!("Saved local repository Config!");
         Ok(())
     }
 
@@ -252,68 +318,215 @@ impl FileRepository {
         restored_deps: &[SharedPackageConfig],
         workspace_dir: &Path,
     ) -> Result<()> {
+        let changes = Self::plan_copy_from_cache(package, restored_deps, workspace_dir)?;
+
+        if changes.is_empty() {
+            log::info!(
+                "{} is up to date",
+                package.dependencies_dir.display().file_path_color()
+            );
+            return Ok(());
+        }
+
+        Self::apply_extern_changes(&changes)?;
+
+        log::info!(
+            "Updated {}: {} linked, {} copied, {} removed",
+            package.dependencies_dir.display().file_path_color(),
+            changes
+                .iter()
+                .filter(|c| matches!(c, ExternChange::Link { .. }))
+                .count(),
+            changes
+                .iter()
+                .filter(|c| matches!(c, ExternChange::Copy { .. }))
+                .count(),
+            changes
+                .iter()
+                .filter(|c| matches!(c, ExternChange::Remove(_)))
+                .count(),
+        );
+        Ok(())
+    }
+
+    /// Computes the changes required to bring the dependencies dir in sync with the cache.
+    /// Nothing is written to disk.
+    pub fn plan_copy_from_cache(
+        package: &PackageConfig,
+        restored_deps: &[SharedPackageConfig],
+        workspace_dir: &Path,
+    ) -> Result<Vec<ExternChange>> {
         let files = Self::collect_deps(package, restored_deps, workspace_dir)?;
+        let extern_dir = workspace_dir.join(&package.dependencies_dir);
 
         let config = get_combine_config();
         let symlink = config.symlink.unwrap_or(true);
 
-        let copy_dir_options = fs_extra::dir::CopyOptions {
-            overwrite: true,
-            copy_inside: true,
-            content_only: true,
-            ..Default::default()
-        };
+        Self::plan_extern_changes(&files, &extern_dir, symlink)
+    }
 
-        let copy_file_options = fs_extra::file::CopyOptions {
-            overwrite: true,
-            ..Default::default()
-        };
+    /// Diffs the desired `source -> destination` mapping against what is currently in `extern_dir`
+    pub fn plan_extern_changes(
+        files: &[(PathBuf, PathBuf)],
+        extern_dir: &Path,
+        symlink: bool,
+    ) -> Result<Vec<ExternChange>> {
+        let mut changes = vec![];
+
+        for (src, dest) in files.iter().sorted_by(|a, b| a.1.cmp(&b.1)) {
+            if !src.exists() {
+                bail!(
+                    "The file or folder\n\t'{}'\ndid not exist! what happened to the cache? you should probably run {} to make sure everything is in order...",
+                    src.display().bright_yellow(),
+                    "qpm cache clear".bright_yellow()
+                );
+            }
 
-        for (src, dest) in files {
-            fs::create_dir_all(dest.parent().unwrap())?;
-            let symlink_result = if symlink {
-                if src.is_file() {
-                    symlink::symlink_file(&src, &dest)
-                } else {
-                    symlink::symlink_dir(&src, &dest)
+            let dest_exists = dest.is_symlink() || dest.exists();
+
+            if symlink {
+                // already linked to the right place
+                if fs::read_link(dest).is_ok_and(|target| &target == src) {
+                    continue;
                 }
-            } else {
-                Ok(())
-            };
 
-            if let Err(e) = &symlink_result {
-                #[cfg(windows)]
-                eprintln!(
-                    "Failed to create symlink: {}\nfalling back to copy, did the link already exist, or did you not enable windows dev mode?\nTo disable this warning (and default to copy), use the command {}",
-                    e.bright_red(),
-                    "qpm config symlink disable".bright_yellow()
-                );
-                #[cfg(not(windows))]
-                eprintln!(
-                    "Failed to create symlink: {}\nfalling back to copy, did the link already exist?\nTo disable this warning (and default to copy), use the command {}",
-                    e.bright_red(),
-                    "qpm config symlink disable".bright_yellow()
-                );
+                // linking fell back to copying last time, e.g. on windows without developer mode
+                if dest_exists && !dest.is_symlink() && copy_equal(src, dest)? {
+                    continue;
+                }
+
+                if dest_exists {
+                    changes.push(ExternChange::Remove(dest.clone()));
+                }
+                changes.push(ExternChange::Link {
+                    source: src.clone(),
+                    destination: dest.clone(),
+                });
+                continue;
             }
 
-            if !symlink || symlink_result.is_err() {
-                // if dir, make sure it exists
-                if !src.exists() {
-                    bail!(
-                        "The file or folder\n\t'{}'\ndid not exist! what happened to the cache? you should probably run {} to make sure everything is in order...",
-                        src.display().bright_yellow(),
-                        "qpm cache clear".bright_yellow()
-                    );
-                } else if src.is_dir() {
-                    std::fs::create_dir_all(&dest)
-                        .context("Failed to create destination folder")?;
+            // copies replace links left over from a previous symlinked restore
+            // or entries that changed between file and folder
+            if dest.is_symlink() || (dest_exists && src.is_dir() != dest.is_dir()) {
+                changes.push(ExternChange::Remove(dest.clone()));
+            }
 
-                    // copy it over
-                    fs_extra::dir::copy(&src, &dest, &copy_dir_options)?;
-                } else if src.is_file() {
-                    // if it's a file, copy that over instead
+            if src.is_file() {
+                if !files_equal(src, dest)? {
+                    changes.push(ExternChange::Copy {
+                        source: src.clone(),
+                        destination: dest.clone(),
+                    });
+                }
+                continue;
+            }
 
-                    fs_extra::file::copy(&src, &dest, &copy_file_options)?;
+            for entry in WalkDir::new(src).min_depth(1) {
+                let entry = entry?;
+                if !entry.path().is_file() {
+                    continue;
+                }
+
+                let entry_dest = dest.join(entry.path().strip_prefix(src)?);
+                if !files_equal(entry.path(), &entry_dest)? {
+                    changes.push(ExternChange::Copy {
+                        source: entry.path().to_path_buf(),
+                        destination: entry_dest,
+                    });
+                }
+            }
+
+            // remove files that no longer exist in the cache
+            if dest.is_dir() && !dest.is_symlink() {
+                let mut walker = WalkDir::new(dest).min_depth(1).into_iter();
+                while let Some(entry) = walker.next() {
+                    let entry = entry?;
+                    let entry_src = src.join(entry.path().strip_prefix(dest)?);
+
+                    if entry_src.exists() {
+                        continue;
+                    }
+
+                    changes.push(ExternChange::Remove(entry.path().to_path_buf()));
+                    if entry.file_type().is_dir() {
+                        walker.skip_current_dir();
+                    }
+                }
+            }
+        }
+
+        // remove anything that is no longer restored
+        if extern_dir.exists() {
+            let destinations: HashSet<&PathBuf> = files.iter().map(|(_, dest)| dest).collect();
+
+            let mut walker = WalkDir::new(extern_dir).min_depth(1).into_iter();
+            while let Some(entry) = walker.next() {
+                let entry = entry?;
+                let path = entry.path();
+
+                let is_destination = destinations.contains(&path.to_path_buf());
+                let is_ancestor =
+                    !is_destination && destinations.iter().any(|d| d.starts_with(path));
+
+                if is_ancestor {
+                    continue;
+                }
+                if !is_destination {
+                    changes.push(ExternChange::Remove(path.to_path_buf()));
+                }
+                // destinations were handled above
+                if entry.file_type().is_dir() {
+                    walker.skip_current_dir();
+                }
+            }
+        }
+
+        Ok(changes)
+    }
+
+    pub fn apply_extern_changes(changes: &[ExternChange]) -> Result<()> {
+        for change in changes {
+            match change {
+                ExternChange::Remove(path) => {
+                    remove_path(path).with_context(|| format!("Unable to delete {path:?}"))?;
+                }
+                ExternChange::Copy {
+                    source,
+                    destination,
+                } => {
+                    fs::create_dir_all(destination.parent().unwrap())?;
+                    fs::copy(source, destination)
+                        .with_context(|| format!("Unable to copy {source:?} to {destination:?}"))?;
+                }
+                ExternChange::Link {
+                    source,
+                    destination,
+                } => {
+                    fs::create_dir_all(destination.parent().unwrap())?;
+                    let symlink_result = if source.is_file() {
+                        symlink::symlink_file(source, destination)
+                    } else {
+                        symlink::symlink_dir(source, destination)
+                    };
+
+                    let Err(e) = symlink_result else {
+                        continue;
+                    };
+
+                    #[cfg(windows)]
+                    log::warn!(
+                        "Failed to create symlink: {}\nfalling back to copy, did the link already exist, or did you not enable windows dev mode?\nTo disable this warning (and default to copy), use the command {}",
+                        e.bright_red(),
+                        "qpm config symlink disable".bright_yellow()
+                    );
+                    #[cfg(not(windows))]
+                    log::warn!(
+                        "Failed to create symlink: {}\nfalling back to copy, did the link already exist?\nTo disable this warning (and default to copy), use the command {}",
+                        e.bright_red(),
+                        "qpm config symlink disable".bright_yellow()
+                    );
+
+                    copy_things(source, destination)?;
                 }
             }
         }
@@ -335,9 +548,14 @@ impl FileRepository {
         Self::get_package_versions_cache_path(id).join(version.to_string())
     }
 
-    /// Collects all files of a package from the cache.
+    /// Collects all files of a package from the cache, with the binaries of the primary ABI.
     /// Returns a `PackageFiles` struct containing the paths to the headers, release binary, and debug binary.
     pub fn collect_files_of_package(package: &PackageConfig) -> Result<PackageFiles> {
+        Self::collect_abi_files_of_package(package, Abi::primary())
+    }
+
+    /// Collects all files of a package from the cache, with the binaries built for `abi`
+    pub fn collect_abi_files_of_package(package: &PackageConfig, abi: Abi) -> Result<PackageFiles> {
         let dep_cache_path = Self::get_package_cache_path(&package.info.id, &package.info.version);
 
         if !dep_cache_path.exists() {
@@ -348,7 +566,12 @@ impl FileRepository {
             );
         }
 
-        let libs_path = dep_cache_path.join("lib");
+        let lib_path = dep_cache_path.join("lib");
+        // caches from before per ABI folders have the arm64-v8a binaries directly in lib
+        let libs_path = match abi == Abi::Arm64V8a && !abi.cache_libs_path(&lib_path).exists() {
+            true => lib_path,
+            false => abi.cache_libs_path(&lib_path),
+        };
         let src_path = dep_cache_path.join("src");
 
         if !src_path.exists() {
@@ -369,12 +592,10 @@ impl FileRepository {
             });
         }
 
-        // get so name or release so name
-
-        // get so name or release so name
+        // get so name or release so name, the archive name for static libraries
         let release_bin_name = package
             .info
-            .get_so_name2()
+            .get_binary_name()
             .file_name()
             .unwrap()
             .to_string_lossy()
@@ -394,7 +615,7 @@ impl FileRepository {
 
         if !release_binary.exists() && !debug_binary.exists() {
             bail!(
-                "Missing binary {release_bin_name}/{debug_bin_name:?} for {}:{}",
+                "Missing {abi} binary {release_bin_name}/{debug_bin_name:?} for {}:{}",
                 package.info.id.dependency_id_color(),
                 package.info.version.dependency_version_color()
             );
@@ -410,13 +631,201 @@ impl FileRepository {
         })
     }
 
+    /// Whether the cache must hold binaries of `package` for `abi`.
+    /// Binaries of the primary ABI are required, other selected ABIs only when the package links
+    /// binaries for them. Restoring continues without the others, builds for those ABIs then
+    /// fail to link the package
+    pub fn expects_abi(package: &PackageConfig, abi: Abi) -> bool {
+        if abi == Abi::primary() {
+            return true;
+        }
+
+        let src_path =
+            Self::get_package_cache_path(&package.info.id, &package.info.version).join("src");
+        !AbiLinks::of(package, &src_path, abi).is_empty()
+    }
+
+    /// Like [`Self::collect_abi_files_of_package`], but `None` if the binaries are missing
+    /// for an ABI `package` does not provide, see [`Self::expects_abi`]
+    pub fn collect_expected_abi_files_of_package(
+        package: &PackageConfig,
+        abi: Abi,
+    ) -> Result<Option<PackageFiles>> {
+        match Self::collect_abi_files_of_package(package, abi) {
+            Ok(files) => Ok(Some(files)),
+            Err(_) if !Self::expects_abi(package, abi) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Ensures `binary` of `package` is a shared library for `abi` whose SONAME is the name it is installed as.
+    /// A SONAME mismatch only fails when `strict`, binaries that were already cached before
+    /// this was checked keep restoring with a warning
+    pub fn validate_binary(
+        package: &PackageConfig,
+        binary: &Path,
+        abi: Abi,
+        strict: bool,
+    ) -> Result<SharedObjectInfo> {
+        let info = elf::read_shared_object(binary, abi).with_context(|| {
+            format!(
+                "Invalid {abi} binary for {}:{}",
+                package.info.id.dependency_id_color(),
+                package.info.version.version_id_color()
+            )
+        })?;
+
+        let so_name = package.info.get_so_name2();
+        let expected = so_name.file_name().unwrap().to_string_lossy();
+        match &info.soname {
+            Some(soname) if soname != expected.as_ref() && strict => {
+                return Err(eyre!(
+                    "Binary {} of {}:{} has SONAME {soname} but is installed as {expected}",
+                    binary.display().file_path_color(),
+                    package.info.id.dependency_id_color(),
+                    package.info.version.version_id_color()
+                ))
+                .with_suggestion(|| {
+                    "The package must set overrideSoName to the SONAME the library was built with"
+                });
+            }
+            Some(soname) if soname != expected.as_ref() => log::warn!(
+                "Binary {} of {}:{} has SONAME {soname} but is installed as {expected}, it may fail to load",
+                binary.display().file_path_color(),
+                package.info.id.dependency_id_color(),
+                package.info.version.version_id_color()
+            ),
+            None => log::warn!(
+                "Binary {} of {}:{} has no SONAME",
+                binary.display().file_path_color(),
+                package.info.id.dependency_id_color(),
+                package.info.version.version_id_color()
+            ),
+            _ => {}
+        }
+
+        Ok(info)
+    }
+
+    /// Ensures every library `info` of `dep` needs is provided by the system
+    /// or by a package in the dependency closure of `dep`
+    pub fn validate_needed(
+        dep: &SharedPackageConfig,
+        info: &SharedObjectInfo,
+        abi: Abi,
+        resolved_deps: &[SharedPackageConfig],
+    ) -> Result<()> {
+        let by_id: HashMap<&str, &SharedPackageConfig> = resolved_deps
+            .iter()
+            .map(|p| (p.config.info.id.as_str(), p))
+            .collect();
+
+        // every package `dep` depends on, directly or transitively
+        let mut closure: HashMap<&str, &SharedPackageConfig> = HashMap::new();
+        let mut pending = vec![dep];
+        while let Some(package) = pending.pop() {
+            let ids = package
+                .config
+                .dependencies
+                .iter()
+                .map(|d| d.id.as_str())
+                .chain(
+                    package
+                        .restored_dependencies
+                        .iter()
+                        .map(|d| d.dependency.id.as_str()),
+                );
+            for id in ids {
+                if let Some(found) = by_id.get(id)
+                    && closure.insert(id, found).is_none()
+                {
+                    pending.push(found);
+                }
+            }
+        }
+
+        let provided: HashSet<String> = closure
+            .values()
+            .filter(|dep| {
+                !dep.config
+                    .info
+                    .additional_data
+                    .headers_only
+                    .unwrap_or(false)
+            })
+            // linked into their dependents, nothing loads them at runtime
+            .filter(|dep| !dep.config.info.is_static_linking())
+            .filter_map(|dep| {
+                let so_name = dep.config.info.get_so_name2();
+                Some(so_name.file_name()?.to_string_lossy().to_string())
+            })
+            .collect();
+
+        let missing = info
+            .needed
+            .iter()
+            .filter(|needed| {
+                !elf::SYSTEM_LIBRARIES.contains(&needed.as_str())
+                    && !provided.contains(needed.as_str())
+            })
+            .collect_vec();
+
+        if !missing.is_empty() {
+            return Err(eyre!(
+                "{}:{} ({abi}) needs {} which none of its dependencies provide",
+                dep.config.info.id.dependency_id_color(),
+                dep.config.info.version.version_id_color(),
+                missing.iter().map(|m| m.file_path_color()).join(", ")
+            ))
+            .with_suggestion(|| "The package is missing a dependency, report this to its author");
+        }
+
+        Ok(())
+    }
+
+    /// Validates the cached binaries of all restored dependencies
+    /// and ensures every library they need is provided by the system or their dependencies
+    pub fn validate_binaries(resolved_deps: &[SharedPackageConfig]) -> Result<()> {
+        for (abi, dep) in Abi::selected().into_iter().cartesian_product(resolved_deps) {
+            let Some(files) = Self::collect_expected_abi_files_of_package(&dep.config, abi)? else {
+                continue;
+            };
+            let binaries = [files.release_binary, files.debug_binary]
+                .into_iter()
+                .flatten()
+                .collect_vec();
+
+            for archive in binaries.iter().filter(|b| elf::is_static_archive(b)) {
+                elf::ensure_static_archive(archive).with_context(|| {
+                    format!(
+                        "Invalid binary for {}:{}",
+                        dep.config.info.id.dependency_id_color(),
+                        dep.config.info.version.version_id_color()
+                    )
+                })?;
+            }
+
+            let binaries = binaries
+                .into_iter()
+                .filter(|binary| elf::is_shared_object(binary));
+
+            for binary in binaries {
+                // fresh downloads were already checked strictly
+                let info = Self::validate_binary(&dep.config, &binary, abi, false)?;
+                Self::validate_needed(dep, &info, abi, resolved_deps)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Collects all dependencies of a package from the cache.
-    /// Returns a map of source paths to target paths for the dependencies.
+    /// Returns the source and target paths of every copy, a source may be copied more than once.
     pub fn collect_deps(
         package: &PackageConfig,
         restored_deps: &[SharedPackageConfig],
         workspace_dir: &Path,
-    ) -> Result<HashMap<PathBuf, PathBuf>> {
+    ) -> Result<Vec<(PathBuf, PathBuf)>> {
         // let package = shared_package.config;
         let restored_dependencies_map: HashMap<&String, &SharedPackageConfig> = restored_deps
             .iter()
@@ -440,21 +849,18 @@ impl FileRepository {
 
         ensure!(extern_dir != workspace_dir, "Extern dir is workspace dir!");
 
-        // delete if needed
-        if extern_dir.exists() {
-            fs::remove_dir_all(&extern_dir)
-                .with_context(|| format!("Unable to delete {extern_dir:?}"))?;
-        }
-
         let extern_binaries = extern_dir.join("libs");
+        let abis = Abi::selected();
         let extern_headers = extern_dir.join("includes");
-        let mut paths = HashMap::<PathBuf, PathBuf>::new();
+        let mut paths = Vec::<CopyTarget>::new();
+        // (package id, shared dir in cache)
+        let mut header_dirs = Vec::<(String, PathBuf)>::new();
 
         // direct deps (binaries)
 
         let deps: Vec<_> = restored_deps
             .iter()
-            .map(|p| Self::collect_files_of_package(&p.config).map(|f| (p, f)))
+            .map(|p| Self::collect_abi_files_of_package(&p.config, abis[0]).map(|f| (p, f)))
             .try_collect()?;
 
         let (direct_deps, indirect_deps): (Vec<_>, Vec<_>) =
@@ -478,25 +884,6 @@ impl FileRepository {
                 .headers_only
                 .unwrap_or(false)
                 .not();
-            let release_binary = not_header_only
-                // not header only
-                .then_some(direct_dep_files.release_binary)
-                .flatten();
-
-            let debug_binary = not_header_only
-                .then_some(direct_dep_files.debug_binary)
-                .flatten();
-
-            if not_header_only
-                && ((release_binary.is_none() || !release_binary.as_ref().unwrap().exists())
-                    && (debug_binary.is_none() || !debug_binary.as_ref().unwrap().exists()))
-            {
-                bail!(
-                    "Missing binary for {}:{}",
-                    direct_dep.config.info.id.dependency_id_color(),
-                    direct_dep.config.version.dependency_version_color()
-                );
-            }
 
             if !exposed_headers.exists() {
                 bail!(
@@ -506,22 +893,45 @@ impl FileRepository {
                 );
             }
 
-            if let Some(src_binary) = release_binary {
-                let file_name = src_binary.file_name().expect("Failed to get file name");
-
-                paths.insert(src_binary.clone(), extern_binaries.join(file_name));
-            }
-
-            if let Some(src_binary) = debug_binary {
-                let file_name = src_binary.file_name().expect("Failed to get file name");
-
-                paths.insert(src_binary.clone(), extern_binaries.join(file_name));
+            for &abi in abis.iter().filter(|_| not_header_only) {
+                let files = Self::collect_expected_abi_files_of_package(&direct_dep.config, abi)
+                    .with_suggestion(|| {
+                        "Check if the package provides binaries for every ABI set with `qpm config abis`"
+                    })?;
+                let Some(files) = files else {
+                    log::warn!(
+                        "{}:{} provides no {abi} binaries, {abi} builds will fail to link it",
+                        direct_dep.config.info.id.dependency_id_color(),
+                        direct_dep.config.info.version.version_id_color()
+                    );
+                    continue;
+                };
+                let extern_abi_binaries = abi.extern_libs_path(&extern_binaries);
+
+                for src_binary in [files.release_binary, files.debug_binary]
+                    .into_iter()
+                    .flatten()
+                {
+                    let file_name = src_binary.file_name().expect("Failed to get file name");
+
+                    paths.push(CopyTarget {
+                        destination: extern_abi_binaries.join(file_name),
+                        source: src_binary,
+                        package_id: direct_dep.config.info.id.clone(),
+                    });
+                }
             }
 
-            paths.insert(
-                exposed_headers,
-                project_deps_headers_target.join(&direct_dep.config.shared_dir),
-            );
+            header_dirs.push((direct_dep.config.info.id.clone(), exposed_headers.clone()));
+            paths.push(CopyTarget {
+                source: exposed_headers,
+                destination: Self::package_destination(
+                    &project_deps_headers_target,
+                    &direct_dep.config.shared_dir,
+                    &direct_dep.config.info.id,
+                )?,
+                package_id: direct_dep.config.info.id.clone(),
+            });
         }
 
         // Get headers of all dependencies restored
@@ -538,10 +948,16 @@ impl FileRepository {
                 );
             }
 
-            paths.insert(
-                exposed_headers,
-                project_deps_headers_target.join(&indirect_dep.config.shared_dir),
-            );
+            header_dirs.push((indirect_dep.config.info.id.clone(), exposed_headers.clone()));
+            paths.push(CopyTarget {
+                source: exposed_headers,
+                destination: Self::package_destination(
+                    &project_deps_headers_target,
+                    &indirect_dep.config.shared_dir,
+                    &indirect_dep.config.info.id,
+                )?,
+                package_id: indirect_dep.config.info.id.clone(),
+            });
         }
 
         // extra files
@@ -572,14 +988,144 @@ impl FileRepository {
                         );
                     }
 
-                    paths.insert(extra_src, extern_headers_dep.join(extra));
+                    paths.push(CopyTarget {
+                        source: extra_src,
+                        destination: Self::package_destination(
+                            &extern_headers_dep,
+                            Path::new(extra),
+                            &referenced_dependency.id,
+                        )?,
+                        package_id: referenced_dependency.id.clone(),
+                    });
                 }
             }
         }
 
-        paths.retain(|src, _| src.exists());
+        paths.retain(|target| target.source.exists());
+
+        Self::validate_copy_targets(&paths)?;
+
+        Self::warn_conflicting_includes(&header_dirs);
+
+        Ok(paths
+            .into_iter()
+            .map(|target| (target.source, target.destination))
+            .collect())
+    }
+
+    /// `relative` inside the extern folder `package_root` of `package_id`,
+    /// fails if it leaves that folder, e.g. a sharedDir of `../other/shared`
+    pub fn package_destination(
+        package_root: &Path,
+        relative: &Path,
+        package_id: &str,
+    ) -> Result<PathBuf> {
+        let package_root = normalize_lexically(package_root);
+        let destination = normalize_lexically(&package_root.join(relative));
+
+        if !destination.starts_with(&package_root) {
+            bail!(
+                "{} of package {} would be copied to {}, outside of {}",
+                relative.display().file_path_color(),
+                package_id.dependency_id_color(),
+                destination.display().file_path_color(),
+                package_root.display().file_path_color()
+            );
+        }
+
+        Ok(destination)
+    }
+
+    /// Ensures no two copies write to the same destination, or into each other.
+    /// A copy nested inside another is only allowed if it mirrors the same source layout,
+    /// e.g. an extra file that already lives inside the copied shared folder.
+    pub fn validate_copy_targets(targets: &[CopyTarget]) -> Result<()> {
+        // `..` in a sharedDir or extra file would otherwise hide an overlap
+        let targets = targets
+            .iter()
+            .map(|target| CopyTarget {
+                source: normalize_lexically(&target.source),
+                destination: normalize_lexically(&target.destination),
+                package_id: target.package_id.clone(),
+            })
+            .collect_vec();
+
+        for (a, b) in targets.iter().tuple_combinations() {
+            // order so that `outer` is the shorter (possibly containing) destination
+            let (outer, inner) =
+                match a.destination.components().count() <= b.destination.components().count() {
+                    true => (a, b),
+                    false => (b, a),
+                };
+
+            let Ok(dest_suffix) = inner.destination.strip_prefix(&outer.destination) else {
+                continue;
+            };
+
+            // same source files end up in the same place, nothing is overwritten
+            let mirrors_source = inner
+                .source
+                .strip_prefix(&outer.source)
+                .is_ok_and(|src_suffix| src_suffix == dest_suffix);
+            if mirrors_source {
+                continue;
+            }
+
+            let kind = match dest_suffix.as_os_str().is_empty() {
+                true => "the same destination",
+                false => "overlapping destinations",
+            };
+
+            bail!(
+                "Packages {} and {} copy to {kind}:\n\t{} -> {}\n\t{} -> {}\nCheck the sharedDir and extraFiles of both packages",
+                outer.package_id.dependency_id_color(),
+                inner.package_id.dependency_id_color(),
+                outer.source.display().file_path_color(),
+                outer.destination.display().file_path_color(),
+                inner.source.display().file_path_color(),
+                inner.destination.display().file_path_color(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Warns when shared dirs of different packages expose the same top level names,
+    /// which makes includes ambiguous once they are added to the include path.
+    fn warn_conflicting_includes(header_dirs: &[(String, PathBuf)]) {
+        let mut top_level_names: HashMap<String, Vec<&str>> = HashMap::new();
 
-        Ok(paths)
+        for (package_id, headers) in header_dirs {
+            let Ok(entries) = fs::read_dir(headers) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let owners = top_level_names
+                    .entry(entry.file_name().to_string_lossy().to_string())
+                    .or_default();
+
+                if !owners.contains(&package_id.as_str()) {
+                    owners.push(package_id);
+                }
+            }
+        }
+
+        for (name, owners) in top_level_names
+            .into_iter()
+            .filter(|(_, owners)| owners.len() > 1)
+            .sorted()
+        {
+            log::warn!(
+                "{}",
+                format!(
+                    "Warning: include {} is exposed by multiple packages: {}",
+                    name.file_path_color(),
+                    owners.iter().map(|id| id.dependency_id_color()).join(", ")
+                )
+                .yellow()
+            );
+        }
     }
 
     pub fn remove_package_versions(&mut self, package: &String) -> Result<()> {
@@ -644,9 +1190,11 @@ impl Repository for FileRepository {
         let exist_in_db = self
             .get_artifact(&config.info.id, &config.info.version)
             .is_some();
-        let file = FileRepository::collect_files_of_package(config);
+        let files = Abi::selected()
+            .into_iter()
+            .all(|abi| FileRepository::collect_expected_abi_files_of_package(config, abi).is_ok());
 
-        Ok(exist_in_db && file.is_ok())
+        Ok(exist_in_db && files)
     }
 
     fn write_repo(&self) -> Result<()> {
@@ -656,4 +1204,8 @@ impl Repository for FileRepository {
     fn is_online(&self) -> bool {
         false
     }
+
+    fn name(&self) -> String {
+        "local cache".to_string()
+    }
 }
@@ -1,7 +1,11 @@
 use color_eyre::Result;
 
 use semver::Version;
-use std::{cell::UnsafeCell, collections::HashMap};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock},
+};
 
 use qpm_package::models::{
     backend::PackageVersion, dependency::SharedPackageConfig, package::PackageConfig,
@@ -9,11 +13,18 @@ use qpm_package::models::{
 
 use super::Repository;
 
+/// Filled by the first request for a key, concurrent requests for the same key wait for it
+type Slot<T> = Arc<Mutex<Option<T>>>;
+
+/// Caches the metadata of `R` in memory, can be shared between threads.
+///
+/// Each distinct request is forwarded to the inner repository once,
+/// e.g. the resolver waits for a prefetch of the same package instead of repeating it.
+/// Failed and empty answers are not cached.
 pub struct MemcachedRepository<R: Repository> {
-    // interior mutability
-    packages_cache: UnsafeCell<HashMap<String, HashMap<Version, SharedPackageConfig>>>,
-    versions_cache: UnsafeCell<HashMap<String, Vec<PackageVersion>>>,
-    package_list: UnsafeCell<Option<Vec<String>>>,
+    packages_cache: RwLock<HashMap<(String, Version), Slot<SharedPackageConfig>>>,
+    versions_cache: RwLock<HashMap<String, Slot<Vec<PackageVersion>>>>,
+    package_list: Mutex<Option<Vec<String>>>,
 
     inner_repo: R,
 }
@@ -28,62 +39,70 @@ impl<R: Repository> MemcachedRepository<R> {
             packages_cache: Default::default(),
         }
     }
-}
 
-impl<R: Repository> Repository for MemcachedRepository<R> {
-    fn get_package_names(&self) -> Result<Vec<String>> {
-        let package_list_opt = self.package_list.get_mut_safe();
+    pub fn inner(&self) -> &R {
+        &self.inner_repo
+    }
+}
 
-        if package_list_opt.is_none() {
-            let inner_package_names = self.inner_repo.get_package_names()?;
-            *package_list_opt = Some(inner_package_names);
-        }
+// a panicking fetch leaves its slot empty, so poisoned locks are still consistent
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
 
-        Ok(package_list_opt.clone().unwrap())
+fn slot<K: Eq + Hash, T>(cache: &RwLock<HashMap<K, Slot<T>>>, key: K) -> Slot<T> {
+    if let Some(slot) = cache
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+    {
+        return slot.clone();
     }
 
-    fn get_package_versions(&self, id: &str) -> Result<Option<Vec<PackageVersion>>> {
-        let cache = self.versions_cache.get_mut_safe().get(id);
+    cache
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(key)
+        .or_default()
+        .clone()
+}
 
-        if let Some(c) = cache {
-            return Ok(Some(c.clone()));
-        }
+/// Returns the cached value or fetches it while holding the slot
+fn cached<T: Clone>(
+    slot: &Mutex<Option<T>>,
+    fetch: impl FnOnce() -> Result<Option<T>>,
+) -> Result<Option<T>> {
+    let mut entry = lock(slot);
+    if let Some(value) = entry.as_ref() {
+        return Ok(Some(value.clone()));
+    }
+
+    let fetched = fetch()?;
+    entry.clone_from(&fetched);
+    Ok(fetched)
+}
 
-        let versions = self.inner_repo.get_package_versions(id)?;
+impl<R: Repository> Repository for MemcachedRepository<R> {
+    fn get_package_names(&self) -> Result<Vec<String>> {
+        let package_list = cached(&self.package_list, || {
+            self.inner_repo.get_package_names().map(Some)
+        })?;
 
-        if let Some(versions) = &versions {
-            self.versions_cache
-                .get_mut_safe()
-                .entry(id.to_string())
-                .insert_entry(versions.clone());
-        }
+        Ok(package_list.unwrap_or_default())
+    }
 
-        Ok(versions)
+    fn get_package_versions(&self, id: &str) -> Result<Option<Vec<PackageVersion>>> {
+        let slot = slot(&self.versions_cache, id.to_string());
+        cached(&slot, || self.inner_repo.get_package_versions(id))
     }
 
     fn get_package(&self, id: &str, version: &Version) -> Result<Option<SharedPackageConfig>> {
-        let cache = self
-            .packages_cache
-            .get_safe()
-            .get(id)
-            .and_then(|f| f.get(version));
-
-        if let Some(c) = cache {
-            return Ok(Some(c.clone()));
-        }
-
-        let config = self.inner_repo.get_package(id, version)?;
-
-        if let Some(config) = &config {
-            self.packages_cache
-                .get_mut_safe()
-                .entry(config.config.info.id.clone())
-                .or_default()
-                .entry(config.config.info.version.clone())
-                .insert_entry(config.clone());
-        }
+        let slot = slot(&self.packages_cache, (id.to_string(), version.clone()));
+        cached(&slot, || self.inner_repo.get_package(id, version))
+    }
 
-        Ok(config)
+    fn yanked_versions(&self, id: &str) -> Result<Vec<Version>> {
+        self.inner_repo.yanked_versions(id)
     }
 
     fn add_to_db_cache(&mut self, config: SharedPackageConfig, permanent: bool) -> Result<()> {
@@ -101,20 +120,12 @@ impl<R: Repository> Repository for MemcachedRepository<R> {
     fn is_online(&self) -> bool {
         false
     }
-}
-trait UnsafeCellExt<T>: Sized {
-    fn get_safe(&self) -> &T;
-
-    #[allow(clippy::mut_from_ref)]
-    fn get_mut_safe(&self) -> &mut T;
-}
 
-impl<T> UnsafeCellExt<T> for UnsafeCell<T> {
-    fn get_safe(&self) -> &T {
-        unsafe { &*self.get() }
+    fn is_cached(&self) -> bool {
+        true
     }
 
-    fn get_mut_safe(&self) -> &mut T {
-        unsafe { &mut *self.get() }
+    fn name(&self) -> String {
+        self.inner_repo.name()
     }
 }
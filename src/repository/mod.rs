@@ -7,16 +7,20 @@ use qpm_package::models::{
 };
 
 use self::{
-    local::FileRepository, memcached::MemcachedRepository, multi::MultiDependencyRepository,
-    qpackages::QPMRepository,
+    git_index::GitIndexRepository, local::FileRepository, memcached::MemcachedRepository,
+    multi::MultiDependencyRepository, qpackages::QPMRepository,
 };
 
+pub mod git_index;
 pub mod local;
 pub mod memcached;
 pub mod multi;
 pub mod qpackages;
+pub mod registry;
 
-pub trait Repository {
+/// Source of package metadata and artifacts.
+/// Implementations must be shareable between threads so resolving and downloading can be parallelized
+pub trait Repository: Send + Sync {
     fn get_package_names(&self) -> Result<Vec<String>>;
 
     /// Get the package versions for a given package id
@@ -29,9 +33,29 @@ pub trait Repository {
     // this just stores the shared config itself, not the package
     fn add_to_db_cache(&mut self, config: SharedPackageConfig, permanent: bool) -> Result<()>;
 
+    /// Returns the yanked releases of a package.
+    /// Yanked releases are not chosen when resolving, but lock files using them still restore
+    fn yanked_versions(&self, _id: &str) -> Result<Vec<Version>> {
+        Ok(vec![])
+    }
+
+    /// Returns true if the release was yanked, see [`Repository::yanked_versions`]
+    fn is_yanked(&self, id: &str, version: &Version) -> Result<bool> {
+        Ok(self.yanked_versions(id)?.contains(version))
+    }
+
     /// Returns true if the repository uses a network connection to retrieve data
     fn is_online(&self) -> bool;
 
+    /// Returns true if answers are kept in memory, so fetching them ahead of time
+    /// while resolving saves requests later on
+    fn is_cached(&self) -> bool {
+        false
+    }
+
+    /// Human readable name of the repository, e.g. shown by `qpm info`
+    fn name(&self) -> String;
+
     // downloads if not in cache
     // What if we wanted to have a qpackages mirror or a new backend? ;)
     // Does not download dependencies
@@ -43,9 +67,17 @@ pub trait Repository {
 
 pub fn default_repositories() -> Result<Vec<Box<dyn Repository>>> {
     // TODO: Make file repository cached
-    let file_repository = Box::new(FileRepository::read()?);
+    let file_repository: Box<dyn Repository> = Box::new(FileRepository::read()?);
     let qpm_repository = Box::<QPMRepository>::default();
-    Ok(vec![file_repository, qpm_repository])
+
+    let git_indexes = GitIndexRepository::configured()
+        .into_iter()
+        .map(|repo| Box::new(repo) as Box<dyn Repository>);
+
+    Ok(std::iter::once(file_repository)
+        .chain(git_indexes)
+        .chain([qpm_repository as Box<dyn Repository>])
+        .collect())
 }
 
 pub fn useful_default_new(offline: bool) -> Result<MemcachedRepository<MultiDependencyRepository>> {
@@ -26,13 +26,20 @@ impl Repository for MultiDependencyRepository {
     // get versions of all repositories
     fn get_package_versions(&self, id: &str) -> Result<Option<Vec<PackageVersion>>> {
         // double flat map???? rust weird
-        // TODO: Propagate error
-        let result: Vec<PackageVersion> = self
+        let versions = self
             .repositories
             .iter()
-            .filter_map(|r| r.get_package_versions(id).expect("Failed to get versions"))
+            .map(|r| r.get_package_versions(id))
+            .collect::<Result<Vec<_>>>()?;
+        // a release yanked by its registry may still be known to the local cache
+        let yanked = self.yanked_versions(id)?;
+
+        let result: Vec<PackageVersion> = versions
+            .into_iter()
+            .flatten()
             .flatten()
             .unique()
+            .filter(|v| !yanked.contains(&v.version))
             .sorted_by(|a, b| a.version.cmp(&b.version))
             .rev() // highest first
             .collect();
@@ -65,25 +72,21 @@ impl Repository for MultiDependencyRepository {
         id: &str,
         version: &semver::Version,
     ) -> Result<Option<SharedPackageConfig>> {
-        let opt = self
-            .repositories
-            .iter()
-            .map(|r| r.get_package(id, version).expect("Unable to get package"))
-            .find(|r| r.is_some());
-
-        match opt {
-            Some(o) => Ok(o),
-            _ => Ok(None),
+        for repo in &self.repositories {
+            if let Some(package) = repo.get_package(id, version)? {
+                return Ok(Some(package));
+            }
         }
+        Ok(None)
     }
 
     fn get_package_names(&self) -> Result<Vec<String>> {
-        Ok(self
+        let names = self
             .repositories
             .iter()
-            .flat_map(|r| r.get_package_names().expect("Unable to get package names"))
-            .unique()
-            .collect::<Vec<String>>())
+            .map(|r| r.get_package_names())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(names.into_iter().flatten().unique().collect())
     }
 
     fn download_to_cache(&mut self, config: &PackageConfig) -> Result<bool> {
@@ -111,8 +114,7 @@ impl Repository for MultiDependencyRepository {
 
     fn add_to_db_cache(&mut self, config: SharedPackageConfig, permanent: bool) -> Result<()> {
         if permanent {
-            #[cfg(debug_assertions)]
-            println!("Warning, adding to cache permanently to multiple repos!",);
+            log::debug!("Warning, adding to cache permanently to multiple repos!");
         }
         self.repositories
             .iter_mut()
@@ -120,6 +122,15 @@ impl Repository for MultiDependencyRepository {
         Ok(())
     }
 
+    // yanked by any of the repositories
+    fn yanked_versions(&self, id: &str) -> Result<Vec<semver::Version>> {
+        let mut yanked = vec![];
+        for repo in &self.repositories {
+            yanked.extend(repo.yanked_versions(id)?);
+        }
+        Ok(yanked.into_iter().unique().collect())
+    }
+
     fn write_repo(&self) -> Result<()> {
         self.repositories.iter().try_for_each(|r| r.write_repo())?;
         Ok(())
@@ -128,4 +139,8 @@ impl Repository for MultiDependencyRepository {
     fn is_online(&self) -> bool {
         self.repositories.iter().any(|r| r.is_online())
     }
+
+    fn name(&self) -> String {
+        self.repositories.iter().map(|r| r.name()).join(", ")
+    }
 }
@@ -22,27 +22,57 @@ use qpm_package::{
 };
 
 use crate::{
-    models::{config::get_combine_config, package::PackageConfigExtensions},
-    network::agent::{download_file_report, get_agent},
+    models::{
+        abi::{Abi, AbiLinks},
+        config::get_combine_config,
+        package::{PackageConfigExtensions, StaticLibraryExtensions},
+    },
+    network::agent::{download_file_report, get_agent_for, loopback_override},
     terminal::colors::QPMColor,
-    utils::git,
+    utils::{elf, git},
 };
 
-use super::Repository;
+use super::{Repository, local::FileRepository};
 
-const API_URL: &str = "https://qpackages.com";
+pub struct QPMRepository {
+    api_url: String,
+}
 
-#[derive(Default)]
-pub struct QPMRepository {}
+impl Default for QPMRepository {
+    fn default() -> Self {
+        Self::new(Self::default_api_url())
+    }
+}
 
 impl QPMRepository {
-    fn run_request<T>(path: &str) -> Result<Option<T>>
+    pub const API_URL: &str = "https://qpackages.com";
+    /// Environment variable overriding [`Self::API_URL`] with a test server on this machine
+    pub const API_URL_ENV: &str = "QPM_QPACKAGES_URL";
+
+    /// A qpackages compatible API at `api_url`
+    pub fn new(api_url: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// [`Self::API_URL`] unless overridden through [`Self::API_URL_ENV`].
+    /// Publish keys are sent to this url, so only servers on this machine may override it
+    pub fn default_api_url() -> String {
+        loopback_override(Self::API_URL_ENV).unwrap_or_else(|| Self::API_URL.to_string())
+    }
+
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    fn run_request<T>(&self, path: &str) -> Result<Option<T>>
     where
         T: for<'a> Deserialize<'a>,
     {
-        let url = format!("{API_URL}/{path}");
+        let url = format!("{}/{path}", self.api_url);
 
-        let response = get_agent()
+        let response = get_agent_for(&url)
             .get(&url)
             .send()
             .with_context(|| format!("Unable to make request to qpackages.com {url}"))?;
@@ -61,13 +91,17 @@ impl QPMRepository {
     }
 
     /// Requests the appriopriate package info from qpackage.com
-    pub fn get_versions(id: &str) -> Result<Option<Vec<PackageVersion>>> {
-        Self::run_request(&format!("{id}?limit=0"))
+    pub fn get_versions(&self, id: &str) -> Result<Option<Vec<PackageVersion>>> {
+        self.run_request(&format!("{id}?limit=0"))
             .with_context(|| format!("Getting list of versions for {}", id.dependency_id_color()))
     }
 
-    pub fn get_shared_package(id: &str, ver: &Version) -> Result<Option<SharedPackageConfig>> {
-        Self::run_request(&format!("{id}/{ver}")).with_context(|| {
+    pub fn get_shared_package(
+        &self,
+        id: &str,
+        ver: &Version,
+    ) -> Result<Option<SharedPackageConfig>> {
+        self.run_request(&format!("{id}/{ver}")).with_context(|| {
             format!(
                 "Getting shared package config {}:{}",
                 id.dependency_id_color(),
@@ -76,20 +110,30 @@ impl QPMRepository {
         })
     }
 
-    pub fn get_packages() -> Result<Vec<String>> {
-        let vec = Self::run_request("")
+    pub fn get_packages(&self) -> Result<Vec<String>> {
+        let vec = self
+            .run_request("")
             .context("qpackages.com packages list failed")?
             .ok_or_eyre("No packages found?")?;
         Ok(vec)
     }
 
-    pub fn publish_package(package: &SharedPackageConfig, auth: &str) -> Result<()> {
+    pub fn publish_package(&self, package: &SharedPackageConfig, auth: &str) -> Result<()> {
+        Self::publish_package_to(&self.api_url, package, auth)
+    }
+
+    /// Publishes to any qpackages compatible API at `api_url`
+    pub fn publish_package_to(
+        api_url: &str,
+        package: &SharedPackageConfig,
+        auth: &str,
+    ) -> Result<()> {
         let url = format!(
             "{}/{}/{}",
-            API_URL, &package.config.info.id, &package.config.info.version
+            api_url, &package.config.info.id, &package.config.info.version
         );
 
-        let resp = get_agent()
+        let resp = get_agent_for(&url)
             .post(&url)
             .header("Authorization", auth)
             .json(&package)
@@ -99,7 +143,7 @@ impl QPMRepository {
         if resp.status() == StatusCode::UNAUTHORIZED {
             bail!(
                 "Could not publish to {}: Unauthorized! Did you provide the correct key?",
-                API_URL
+                api_url
             );
         }
         resp.error_for_status()?;
@@ -107,6 +151,23 @@ impl QPMRepository {
     }
 
     fn download_package(&self, config: &PackageConfig) -> Result<()> {
+        let user_config = get_combine_config();
+        let base_path = user_config
+            .cache
+            .as_ref()
+            .unwrap()
+            .join(&config.info.id)
+            .join(config.info.version.to_string());
+
+        Self::download_package_to(config, &base_path, &Abi::selected())
+    }
+
+    /// Downloads the sources and the binaries for `abis` of `config` into `base_path`, laid out like the cache
+    pub(crate) fn download_package_to(
+        config: &PackageConfig,
+        base_path: &Path,
+        abis: &[Abi],
+    ) -> Result<()> {
         // Check if already cached
         // if true, don't download repo / header files
         // else cache to tmp folder in package id folder @ cache path
@@ -118,27 +179,16 @@ impl QPMRepository {
         // Download release .so and possibly debug .so to libs folder, if from github use token if available
         // Now it should be cached!
 
-        println!(
+        log::info!(
             "Checking cache for dependency {} {}",
             config.info.id.dependency_id_color(),
             config.info.version.version_id_color()
         );
-        let user_config = get_combine_config();
-        let base_path = user_config
-            .cache
-            .as_ref()
-            .unwrap()
-            .join(&config.info.id)
-            .join(config.info.version.to_string());
 
         let src_path = base_path.join("src");
         let lib_path = base_path.join("lib");
         let tmp_path = base_path.join("tmp");
 
-        let so_path = lib_path.join(config.info.get_so_name2());
-        let debug_bin_name = config.info.get_so_name2().with_extension("debug.so");
-        let debug_so_path = lib_path.join(debug_bin_name.file_name().unwrap());
-
         let src_exists = src_path.join("qpm.shared.json").exists();
         if src_exists {
             // ensure is valid
@@ -161,7 +211,7 @@ impl QPMRepository {
             }
 
             // src did not exist, this means that we need to download the repo/zip file from packageconfig.info.url
-            fs::create_dir_all(&base_path)
+            fs::create_dir_all(base_path)
                 .with_context(|| format!("Failed to create lib path {base_path:?}"))?;
             let url = config.info.url.as_ref().unwrap();
             if url.contains("github.com") {
@@ -202,9 +252,7 @@ impl QPMRepository {
             };
 
             if sub_package_path.exists() {
-                // only log this on debug builds
-                #[cfg(debug_assertions)]
-                println!(
+                log::debug!(
                     "Moving from: {}\nto: {}",
                     sub_package_path.display().bright_yellow(),
                     src_path.display().bright_yellow()
@@ -212,7 +260,7 @@ impl QPMRepository {
 
                 if src_path.exists() {
                     let mut line = String::new();
-                    println!(
+                    log::warn!(
                         "Confirm deletion of folder {}: (y/N)",
                         src_path.display().bright_yellow()
                     );
@@ -262,7 +310,7 @@ impl QPMRepository {
                     }
                 }
 
-                Err(e) => println!(
+                Err(e) => log::warn!(
                     "Unable to validate shared package of {}:{} due to: \"{}\", continuing",
                     config.info.name.dependency_id_color(),
                     config.info.version.dependency_version_color(),
@@ -271,16 +319,11 @@ impl QPMRepository {
             }
         }
 
-        if !lib_path.exists() {
-            fs::create_dir_all(&lib_path).context("Failed to create lib path")?;
-        }
-
         // libs didn't exist or the release object didn't exist, we need to download from packageconfig.info.additional_data.so_link and packageconfig.info.additional_data.debug_so_link
-        let download_binary = |path: &Path, url_opt: Option<&String>| -> Result<_> {
+        let download_binary = |path: &Path, url_opt: Option<&String>, abi: Abi| -> Result<_> {
             // only download if file doesn't exist already
             if path.exists() {
-                #[cfg(debug_assertions)]
-                println!(
+                log::debug!(
                     "{} already exists, skipping download",
                     path.display().bright_yellow()
                 );
@@ -296,7 +339,7 @@ impl QPMRepository {
             }
 
             if !temp_path.exists() || File::open(&temp_path).is_err() {
-                println!(
+                log::info!(
                     "Downloading {} from {} to {}",
                     path.file_name()
                         .unwrap()
@@ -318,33 +361,79 @@ impl QPMRepository {
                 }
             }
 
+            // an html error page must not end up in the cache as a binary
+            if elf::is_shared_object(path)
+                && let Err(e) = FileRepository::validate_binary(config, &temp_path, abi, true)
+            {
+                fs::remove_file(&temp_path)?;
+                return Err(e.wrap_err(format!(
+                    "{} downloaded from {url} is unusable",
+                    path.file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .download_file_name_color()
+                )));
+            }
+
+            if elf::is_static_archive(path)
+                && let Err(e) = elf::ensure_static_archive(&temp_path)
+            {
+                fs::remove_file(&temp_path)?;
+                return Err(e.wrap_err(format!(
+                    "{} downloaded from {url} is unusable",
+                    path.file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .download_file_name_color()
+                )));
+            }
+
             std::fs::rename(&temp_path, path)
                 .with_context(|| format!("Unable to rename {temp_path:?} to {path:?}"))?;
 
             if path.exists() {
-                #[cfg(debug_assertions)]
-                println!("{} downloaded successfully", path.display().bright_green());
+                log::debug!("{} downloaded successfully", path.display().bright_green());
             }
 
             Ok(())
         };
 
-        download_binary(&so_path, config.info.additional_data.so_link.as_ref())?;
-        download_binary(
-            &debug_so_path,
-            config.info.additional_data.debug_so_link.as_ref(),
-        )?;
-
-        if config.info.additional_data.so_link.is_none()
-            && config.info.additional_data.debug_so_link.is_none()
-            && config.info.additional_data.static_link.is_none()
-            && !config.info.additional_data.headers_only.unwrap_or(false)
-        {
-            eprintln!(
-                "No binaries are provided for {}:{} but is also not header only!",
-                config.info.id.dependency_id_color(),
-                config.info.version.version_id_color()
-            );
+        let debug_bin_name = config.info.get_so_name2().with_extension("debug.so");
+        let headers_only = config.info.additional_data.headers_only.unwrap_or(false);
+
+        for &abi in abis {
+            let links = AbiLinks::of(config, &src_path, abi);
+            if links.is_empty() {
+                if !headers_only {
+                    log::warn!(
+                        "No {abi} binaries are provided for {}:{} but is also not header only!",
+                        config.info.id.dependency_id_color(),
+                        config.info.version.version_id_color()
+                    );
+                }
+                continue;
+            }
+
+            let abi_lib_path = abi.cache_libs_path(&lib_path);
+            if !abi_lib_path.exists() {
+                fs::create_dir_all(&abi_lib_path).context("Failed to create lib path")?;
+            }
+
+            download_binary(
+                &abi_lib_path.join(config.info.get_so_name2()),
+                links.so_link.as_ref(),
+                abi,
+            )?;
+            download_binary(
+                &abi_lib_path.join(debug_bin_name.file_name().unwrap()),
+                links.debug_so_link.as_ref(),
+                abi,
+            )?;
+            download_binary(
+                &abi_lib_path.join(config.info.get_static_name()),
+                links.static_link.as_ref(),
+                abi,
+            )?;
         }
         Ok(())
     }
@@ -352,12 +441,12 @@ impl QPMRepository {
 
 impl Repository for QPMRepository {
     fn get_package_names(&self) -> Result<Vec<String>> {
-        Self::get_packages()
+        self.get_packages()
     }
 
     /// Sorted descending order
     fn get_package_versions(&self, id: &str) -> Result<Option<Vec<PackageVersion>>> {
-        let versions = Self::get_versions(id)?.map(|versions| {
+        let versions = self.get_versions(id)?.map(|versions| {
             versions
                 .into_iter()
                 .sorted_by(|a, b| a.version.cmp(&b.version))
@@ -369,7 +458,7 @@ impl Repository for QPMRepository {
     }
 
     fn get_package(&self, id: &str, version: &Version) -> Result<Option<SharedPackageConfig>> {
-        let config = Self::get_shared_package(id, version)?;
+        let config = self.get_shared_package(id, version)?;
 
         Ok(config)
     }
@@ -397,4 +486,11 @@ impl Repository for QPMRepository {
     fn is_online(&self) -> bool {
         true
     }
+
+    fn name(&self) -> String {
+        match self.api_url == Self::API_URL {
+            true => "qpackages.com".to_string(),
+            false => self.api_url.clone(),
+        }
+    }
 }
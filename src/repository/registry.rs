@@ -0,0 +1,214 @@
+//! Targets for `qpm publish` and `qpm yank`, configured as [`RegistryConfig`]
+
+use std::{
+    env,
+    fs::{self, File},
+    io::BufReader,
+    path::Path,
+    process,
+};
+
+use color_eyre::{
+    Result, Section,
+    eyre::{Context, ContextCompat, bail},
+};
+use qpm_package::models::dependency::SharedPackageConfig;
+use semver::Version;
+
+use crate::{
+    models::{
+        abi::Abi,
+        config::{RegistryConfig, get_combine_config},
+    },
+    terminal::colors::QPMColor,
+    utils::{
+        git::{self, check_git},
+        json,
+    },
+};
+
+use super::{git_index::YANKED_FILE, local::FileRepository, qpackages::QPMRepository};
+
+/// Name of the qpackages.com registry, used when no registry is given
+pub const DEFAULT_REGISTRY: &str = "qpackages";
+/// Name of the local file repository
+pub const LOCAL_REGISTRY: &str = "local";
+
+/// Looks up a registry by name, configured registries take precedence over the builtin ones
+pub fn get_registry(name: &str) -> Result<RegistryConfig> {
+    let configured = get_combine_config()
+        .registries
+        .as_ref()
+        .and_then(|registries| registries.get(name));
+
+    let registry = match (configured, name) {
+        (Some(registry), _) => registry.clone(),
+        (None, DEFAULT_REGISTRY) => RegistryConfig::Http {
+            url: QPMRepository::default_api_url(),
+        },
+        (None, LOCAL_REGISTRY) => RegistryConfig::Local,
+        (None, _) => bail!(
+            "No registry named {} is configured",
+            name.dependency_id_color()
+        ),
+    };
+
+    Ok(registry)
+}
+
+/// Publishes `package` to `registry`
+pub fn publish(
+    name: &str,
+    registry: &RegistryConfig,
+    package: &SharedPackageConfig,
+    auth: Option<&str>,
+) -> Result<()> {
+    let id = &package.config.info.id;
+    let version = &package.config.info.version;
+
+    match registry {
+        RegistryConfig::Http { url } => {
+            let auth = auth.with_context(|| format!("Registry {name} requires a publish key"))?;
+            QPMRepository::publish_package_to(url, package, auth)
+        }
+        RegistryConfig::Git { url, branch } => update_git_index(
+            name,
+            url,
+            branch.as_ref(),
+            auth,
+            &format!("Publish {id} {version}"),
+            |index| {
+                let package_dir = index.join(id);
+                fs::create_dir_all(&package_dir)?;
+
+                let file = File::create(package_dir.join(format!("{version}.json")))?;
+                serde_json::to_writer_pretty(file, package)?;
+                Ok(())
+            },
+        ),
+        RegistryConfig::Local => {
+            // fetch exactly what dependents would fetch
+            QPMRepository::download_package_to(
+                &package.config,
+                &FileRepository::get_package_cache_path(id, version),
+                &Abi::selected(),
+            )?;
+
+            let mut repo = FileRepository::read()?;
+            repo.add_artifact_to_map(package.clone(), true)?;
+            repo.write()
+        }
+    }
+}
+
+/// Marks a release as yanked or restores it, on registries that support it
+pub fn set_yanked(
+    name: &str,
+    registry: &RegistryConfig,
+    id: &str,
+    version: &Version,
+    yanked: bool,
+    auth: Option<&str>,
+) -> Result<()> {
+    match registry {
+        // qpackages has no API for yanking
+        RegistryConfig::Http { .. } => bail!(
+            "Registry {} does not support yanking, only git registries do",
+            name.dependency_id_color()
+        ),
+        RegistryConfig::Git { url, branch } => update_git_index(
+            name,
+            url,
+            branch.as_ref(),
+            auth,
+            &format!("{} {id} {version}", if yanked { "Yank" } else { "Unyank" }),
+            |index| {
+                let package_dir = index.join(id);
+                if !package_dir.join(format!("{version}.json")).exists() {
+                    bail!(
+                        "{}:{} is not published to {}",
+                        id.dependency_id_color(),
+                        version.version_id_color(),
+                        name.dependency_id_color()
+                    );
+                }
+
+                let yanked_path = package_dir.join(YANKED_FILE);
+                let mut yanked_versions: Vec<Version> = match yanked_path.exists() {
+                    true => json::json_from_reader_fast(BufReader::new(File::open(&yanked_path)?))?,
+                    false => vec![],
+                };
+
+                yanked_versions.retain(|v| v != version);
+                if yanked {
+                    yanked_versions.push(version.clone());
+                }
+                yanked_versions.sort();
+
+                serde_json::to_writer_pretty(File::create(&yanked_path)?, &yanked_versions)?;
+                Ok(())
+            },
+        ),
+        RegistryConfig::Local => bail!(
+            "The local registry does not support yanking, use {} instead",
+            "qpm cache clear <id> <version>".file_path_color()
+        ),
+    }
+}
+
+/// Clones the index, applies `edit` and pushes the result as a single commit
+fn update_git_index(
+    name: &str,
+    url: &str,
+    branch: Option<&String>,
+    auth: Option<&str>,
+    message: &str,
+    edit: impl FnOnce(&Path) -> Result<()>,
+) -> Result<()> {
+    check_git()?;
+
+    // a fresh clone, the index used for resolving is only updated by fetching
+    let index_path = env::temp_dir().join(format!("qpm-registry-{name}-{}", process::id()));
+    if index_path.exists() {
+        fs::remove_dir_all(&index_path)
+            .with_context(|| format!("Failed to remove old index {}", index_path.display()))?;
+    }
+
+    let result = clone_edit_push(name, url, branch, auth, message, &index_path, edit);
+
+    if index_path.exists() {
+        fs::remove_dir_all(&index_path)
+            .with_context(|| format!("Failed to remove {}", index_path.display()))?;
+    }
+    result
+}
+
+fn clone_edit_push(
+    name: &str,
+    url: &str,
+    branch: Option<&String>,
+    auth: Option<&str>,
+    message: &str,
+    index_path: &Path,
+    edit: impl FnOnce(&Path) -> Result<()>,
+) -> Result<()> {
+    let index = index_path
+        .to_str()
+        .context("Index path is not valid UTF-8")?;
+    let mut clone_args = vec!["clone", "--depth", "1", url, index];
+    if let Some(branch) = branch {
+        clone_args.extend(["--branch", branch.as_str()]);
+    }
+    // https remotes authenticate with `auth`, ssh remotes use the user's keys
+    git::run_with_auth(&clone_args, Path::new("."), auth)
+        .with_suggestion(|| format!("Check that {url} exists and you have access to it"))?;
+
+    edit(index_path)?;
+
+    git::run(&["add", "-A"], index_path)?;
+    git::run(&["commit", "-m", message], index_path)?;
+    git::run_with_auth(&["push", "origin", "HEAD"], index_path, auth)
+        .with_suggestion(|| format!("Configure a key with qpm config publish --registry {name}"))?;
+
+    Ok(())
+}
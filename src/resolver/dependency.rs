@@ -2,15 +2,16 @@ use std::{
     cmp::Reverse,
     error::Error,
     fmt::{Display, Formatter},
-    path::Path,
+    path::{Path, PathBuf},
+    thread,
     time::Instant,
 };
 
 use crate::{
-    models::package::SharedPackageConfigExtensions,
+    models::{abi::Abi, package::SharedPackageConfigExtensions, toolchain},
     repository::{Repository, local::FileRepository},
     terminal::colors::QPMColor,
-    utils::cmake::write_cmake,
+    utils::cmake::{cmake_files, write_cmake},
 };
 use color_eyre::{
     Result,
@@ -24,13 +25,26 @@ use pubgrub::{
 };
 use qpm_package::models::{dependency::SharedPackageConfig, package::PackageConfig};
 
-use super::semver::{VersionWrapper, req_to_range};
+use super::{
+    prefetch::{FinishGuard, PREFETCH_THREADS, Prefetcher},
+    semver::{VersionWrapper, req_to_range},
+};
 pub struct PackageDependencyResolver<'a, 'b, R>
 where
     R: Repository,
 {
     root: &'a PackageConfig,
     repo: &'b R,
+    /// Fetches the metadata of dependencies ahead of time if the repository caches it
+    prefetcher: Option<&'b Prefetcher<'b, R>>,
+}
+
+impl<R: Repository> PackageDependencyResolver<'_, '_, R> {
+    fn prefetch(&self, dependencies: &[(String, pubgrub::Ranges<VersionWrapper>)]) {
+        if let Some(prefetcher) = self.prefetcher {
+            prefetcher.enqueue(dependencies.iter().cloned());
+        }
+    }
 }
 impl<R: Repository> DependencyProvider for PackageDependencyResolver<'_, '_, R> {
     type P = String;
@@ -65,8 +79,9 @@ impl<R: Repository> DependencyProvider for PackageDependencyResolver<'_, '_, R>
                     let range = req_to_range(dep.version_range.clone());
                     (id.clone(), range)
                 })
-                .collect();
-            return Ok(Dependencies::Available(deps));
+                .collect_vec();
+            self.prefetch(&deps);
+            return Ok(Dependencies::Available(deps.into_iter().collect()));
         }
 
         // Find dependencies of dependencies
@@ -84,7 +99,7 @@ impl<R: Repository> DependencyProvider for PackageDependencyResolver<'_, '_, R>
             .filter(|dep| !dep.additional_data.is_private.unwrap_or(false))
             .inspect(|dep| {
                 if dep.id == self.root.info.id {
-                    println!(
+                    log::warn!(
                         "{}",
                         format!(
                             "Warning: Package {} depends on root package {}",
@@ -102,8 +117,9 @@ impl<R: Repository> DependencyProvider for PackageDependencyResolver<'_, '_, R>
                 let range = req_to_range(dep.version_range);
                 (id, range)
             })
-            .collect();
-        Ok(Dependencies::Available(deps))
+            .collect_vec();
+        self.prefetch(&deps);
+        Ok(Dependencies::Available(deps.into_iter().collect()))
     }
 
     fn choose_version(
@@ -163,13 +179,27 @@ pub fn resolve<'a>(
     root: &'a PackageConfig,
     repository: &'a impl Repository,
 ) -> Result<impl Iterator<Item = SharedPackageConfig> + 'a> {
+    let prefetcher = repository
+        .is_cached()
+        .then(|| Prefetcher::new(repository, &root.info.id));
     let resolver = PackageDependencyResolver {
         root,
         repo: repository,
+        prefetcher: prefetcher.as_ref(),
     };
     let time = Instant::now();
-    let result = match pubgrub::resolve(&resolver, root.info.id.clone(), root.info.version.clone())
-    {
+    let resolved = thread::scope(|scope| {
+        let _finish = prefetcher.as_ref().map(|prefetcher| {
+            for _ in 0..PREFETCH_THREADS {
+                scope.spawn(move || prefetcher.run_worker());
+            }
+            FinishGuard(prefetcher)
+        });
+
+        pubgrub::resolve(&resolver, root.info.id.clone(), root.info.version.clone())
+    });
+
+    let result = match resolved {
         Ok(deps) => Ok(deps.into_iter().filter_map(move |(id, version)| {
             if id == root.info.id && version == root.info.version {
                 return None;
@@ -188,7 +218,7 @@ pub fn resolve<'a>(
     };
 
     let sw = time.elapsed();
-    println!("Took {}ms to dependency resolve", sw.as_millis());
+    log::info!("Took {}ms to dependency resolve", sw.as_millis());
     result
 }
 
@@ -199,7 +229,7 @@ pub fn restore<P: AsRef<Path>>(
     repository: &mut impl Repository,
 ) -> Result<()> {
     for dep in resolved_deps {
-        println!(
+        log::info!(
             "Pulling {}:{}",
             &dep.config.info.id.dependency_id_color(),
             &dep.config
@@ -220,15 +250,48 @@ pub fn restore<P: AsRef<Path>>(
 
     repository.write_repo()?;
 
-    println!("Copying now");
+    FileRepository::validate_binaries(resolved_deps)?;
+
+    log::info!("Copying now");
     FileRepository::copy_from_cache(&shared_package.config, resolved_deps, workspace.as_ref())?;
 
-    write_cmake(shared_package, repository)?;
-    shared_package.try_write_toolchain(repository)?;
+    write_cmake(workspace.as_ref(), shared_package, repository)?;
+    shared_package.try_write_toolchain(workspace.as_ref(), repository)?;
 
     Ok(())
 }
 
+/// The files [`restore`] generates in `workspace` besides the dependencies directory,
+/// the cmake files and the toolchain file, with their contents
+pub fn generated_files(
+    workspace: &Path,
+    shared_package: &SharedPackageConfig,
+    repository: &impl Repository,
+) -> Result<Vec<(PathBuf, String)>> {
+    let mut files = cmake_files(workspace, shared_package, repository)?;
+    if let Some(toolchain_path) = &shared_package.config.info.additional_data.toolchain_out {
+        files.push((
+            workspace.join(toolchain_path),
+            toolchain::make_toolchain_string(shared_package, repository)?,
+        ));
+    }
+    Ok(files)
+}
+
+/// Returns the dependencies that are not fully available in the local cache yet,
+/// see [`FileRepository::expects_abi`] for the binaries they need
+pub fn missing_from_cache(resolved_deps: &[SharedPackageConfig]) -> Vec<&SharedPackageConfig> {
+    let abis = Abi::selected();
+    resolved_deps
+        .iter()
+        .filter(|dep| {
+            abis.iter().any(|&abi| {
+                FileRepository::collect_expected_abi_files_of_package(&dep.config, abi).is_err()
+            })
+        })
+        .collect()
+}
+
 pub fn locked_resolve<'a, R: Repository>(
     root: &'a SharedPackageConfig,
     repository: &'a R,
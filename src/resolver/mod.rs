@@ -1,2 +1,3 @@
 pub mod dependency;
+pub mod prefetch;
 pub mod semver;
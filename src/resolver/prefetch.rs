@@ -0,0 +1,159 @@
+//! Fetches the metadata the resolver will likely ask for next on background threads,
+//! so a cold resolve does not wait on one request at a time
+
+use std::{
+    collections::{HashSet, VecDeque},
+    panic::{self, AssertUnwindSafe},
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+use pubgrub::Ranges;
+
+use crate::repository::Repository;
+
+use super::semver::{VersionWrapper, req_to_range};
+
+/// Number of background threads fetching metadata
+pub const PREFETCH_THREADS: usize = 4;
+
+#[derive(Default)]
+struct PrefetchState {
+    queue: VecDeque<(String, Ranges<VersionWrapper>)>,
+    /// Packages that were queued once already
+    seen: HashSet<String>,
+    finished: bool,
+}
+
+/// Queue of packages whose version list and newest matching shared config should be fetched.
+///
+/// Only useful in front of a repository that caches, see [`Repository::is_cached`].
+/// Failures and panics are ignored, the resolver repeats the request and reports them.
+pub struct Prefetcher<'a, R: Repository> {
+    repo: &'a R,
+    root_id: &'a str,
+    state: Mutex<PrefetchState>,
+    ready: Condvar,
+}
+
+impl<'a, R: Repository> Prefetcher<'a, R> {
+    pub fn new(repo: &'a R, root_id: &'a str) -> Self {
+        Self {
+            repo,
+            root_id,
+            state: Default::default(),
+            ready: Condvar::new(),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, PrefetchState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Queues the dependencies of a package, packages that were queued before are skipped
+    pub fn enqueue(
+        &self,
+        dependencies: impl IntoIterator<Item = (String, Ranges<VersionWrapper>)>,
+    ) {
+        let mut state = self.state();
+        if state.finished {
+            return;
+        }
+
+        for (id, range) in dependencies {
+            if id != self.root_id && state.seen.insert(id.clone()) {
+                state.queue.push_back((id, range));
+            }
+        }
+        self.ready.notify_all();
+    }
+
+    /// Stops the workers. Requests in flight are completed, but no further ones are made
+    pub fn finish(&self) {
+        let mut state = self.state();
+        state.finished = true;
+        state.queue.clear();
+        self.ready.notify_all();
+    }
+
+    /// Processes the queue until [`Self::finish`] is called
+    pub fn run_worker(&self) {
+        loop {
+            let (id, range) = {
+                let mut state = self
+                    .ready
+                    .wait_while(self.state(), |state| {
+                        state.queue.is_empty() && !state.finished
+                    })
+                    .unwrap_or_else(PoisonError::into_inner);
+
+                match state.queue.pop_front() {
+                    Some(next) if !state.finished => next,
+                    _ => return,
+                }
+            };
+
+            // a panicking repository must not take the resolve down with it
+            let prefetched = panic::catch_unwind(AssertUnwindSafe(|| self.prefetch(&id, &range)));
+            if prefetched.is_err() {
+                log::debug!("Prefetching {id} panicked");
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.state().finished
+    }
+
+    /// Fetches the version the resolver most likely chooses and queues its dependencies
+    fn prefetch(&self, id: &str, range: &Ranges<VersionWrapper>) {
+        let versions = match self.repo.get_package_versions(id) {
+            Ok(Some(versions)) => versions,
+            Ok(None) => return,
+            Err(e) => {
+                log::debug!("Prefetching versions of {id} failed: {e}");
+                return;
+            }
+        };
+
+        // same choice as the resolver, the newest matching version
+        let Some(version) = versions
+            .into_iter()
+            .map(|v| VersionWrapper::from(v.version))
+            .find(|v| range.contains(v))
+        else {
+            return;
+        };
+
+        // the resolver may be done while the versions were fetched
+        if self.is_finished() {
+            return;
+        }
+
+        let package = match self.repo.get_package(id, &version.clone().into()) {
+            Ok(Some(package)) => package,
+            Ok(None) => return,
+            Err(e) => {
+                log::debug!("Prefetching {id}:{version} failed: {e}");
+                return;
+            }
+        };
+
+        self.enqueue(
+            package
+                .config
+                .dependencies
+                .into_iter()
+                .filter(|dep| !dep.additional_data.is_private.unwrap_or(false))
+                .map(|dep| (dep.id, req_to_range(dep.version_range))),
+        );
+    }
+}
+
+/// Calls [`Prefetcher::finish`] when dropped, so the workers also stop when resolving panics
+pub struct FinishGuard<'p, 'a, R: Repository>(pub &'p Prefetcher<'a, R>);
+
+impl<R: Repository> Drop for FinishGuard<'_, '_, R> {
+    fn drop(&mut self) {
+        self.0.finish();
+    }
+}
@@ -0,0 +1,230 @@
+//! Logging for qpm.
+//!
+//! Diagnostics are written with the `log` macros and go to stderr, filtered by `-v`/`-q`.
+//! Command results go through [`crate::output!`] to stdout. Both are mirrored,
+//! without colors, to the `--log-file` if one was given.
+
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::Path,
+    sync::{Mutex, OnceLock, RwLock},
+    time::Instant,
+};
+
+use color_eyre::{Result, eyre::Context};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use owo_colors::OwoColorize;
+
+use super::colors::{colors_enabled, strip_colors};
+
+static LOGGER: OnceLock<QpmLogger> = OnceLock::new();
+
+/// Receives the progress messages of qpm, see [`set_listener`]
+pub type Listener = Box<dyn Fn(Level, &str) + Send + Sync>;
+
+static LISTENER: RwLock<Option<Listener>> = RwLock::new(None);
+
+struct QpmLogger {
+    console_level: LevelFilter,
+    file: Option<Mutex<File>>,
+    start: Instant,
+}
+
+/// Whether the record comes from qpm itself rather than a dependency.
+/// Only the crates of qpm count, not e.g. `qpm_package` or `qpm_qmod`
+pub fn is_qpm_target(target: &str) -> bool {
+    let krate = target.split("::").next().unwrap_or(target);
+    krate == "qpm_cli" || krate == "qpm"
+}
+
+/// Maps `-q` and the amount of `-v` flags to the console level
+pub fn verbosity_level(quiet: bool, verbose: u8) -> LevelFilter {
+    match (quiet, verbose) {
+        (true, _) => LevelFilter::Warn,
+        (false, 0) => LevelFilter::Info,
+        (false, 1) => LevelFilter::Debug,
+        (false, _) => LevelFilter::Trace,
+    }
+}
+
+/// Installs the logger, `log_file` receives every record of qpm regardless of `console_level`
+pub fn init(console_level: LevelFilter, log_file: Option<&Path>) -> Result<()> {
+    let file = log_file
+        .map(|path| {
+            File::create(path)
+                .with_context(|| format!("Unable to create log file {}", path.display()))
+        })
+        .transpose()?
+        .map(Mutex::new);
+
+    // the listener of the daemon receives progress even under -q
+    let max_level = match file {
+        Some(_) => LevelFilter::Trace,
+        None => console_level.max(LevelFilter::Info),
+    };
+
+    let logger = LOGGER.get_or_init(|| QpmLogger {
+        console_level,
+        file,
+        start: Instant::now(),
+    });
+    log::set_logger(logger).context("Logger was already initialized")?;
+    log::set_max_level(max_level);
+
+    log::debug!(
+        "qpm {} ({}@{})",
+        env!("CARGO_PKG_VERSION"),
+        option_env!("VERGEN_GIT_BRANCH").unwrap_or("unknown"),
+        option_env!("VERGEN_GIT_SHA").unwrap_or("unknown")
+    );
+    Ok(())
+}
+
+/// Forwards every record of qpm up to `Info`, without colors, to `listener` until it is unset.
+/// Used to report progress to clients of `qpm daemon`
+pub fn set_listener(listener: Option<Listener>) {
+    if let Ok(mut current) = LISTENER.write() {
+        *current = listener;
+    }
+}
+
+fn notify_listener(level: Level, message: &str) {
+    if let Ok(listener) = LISTENER.read()
+        && let Some(listener) = listener.as_ref()
+    {
+        listener(level, &strip_colors(message));
+    }
+}
+
+/// Whether only warnings and errors are shown, used to hide progress bars
+pub fn is_quiet() -> bool {
+    LOGGER
+        .get()
+        .is_some_and(|logger| logger.console_level < LevelFilter::Info)
+}
+
+impl QpmLogger {
+    fn console_enabled(&self, metadata: &Metadata) -> bool {
+        match is_qpm_target(metadata.target()) {
+            true => metadata.level() <= self.console_level,
+            // dependencies are chatty, only show their output with -vv
+            false => metadata.level() <= Level::Warn || self.console_level == LevelFilter::Trace,
+        }
+    }
+
+    fn listener_enabled(&self, metadata: &Metadata) -> bool {
+        is_qpm_target(metadata.target())
+            && metadata.level() <= Level::Info
+            && LISTENER.read().is_ok_and(|listener| listener.is_some())
+    }
+
+    fn file_enabled(&self, metadata: &Metadata) -> bool {
+        self.file.is_some()
+            && (is_qpm_target(metadata.target()) || metadata.level() <= Level::Debug)
+    }
+
+    fn write_file(&self, label: &str, target: &str, message: &str) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        let Ok(mut file) = file.lock() else {
+            return;
+        };
+
+        let elapsed = self.start.elapsed().as_secs_f64();
+        for line in strip_colors(message).lines() {
+            let _ = writeln!(file, "{elapsed:>10.3}s {label:<6} {target}: {line}");
+        }
+    }
+}
+
+impl Log for QpmLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.console_enabled(metadata)
+            || self.file_enabled(metadata)
+            || self.listener_enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        let metadata = record.metadata();
+        if !self.enabled(metadata) {
+            return;
+        }
+
+        let message = record.args().to_string();
+
+        // independent of the console level, clients of the daemon choose what to show
+        if self.listener_enabled(metadata) {
+            notify_listener(record.level(), &message);
+        }
+
+        if self.file_enabled(metadata) {
+            self.write_file(record.level().as_str(), record.target(), &message);
+        }
+
+        if !self.console_enabled(metadata) {
+            return;
+        }
+
+        let line = match record.level() {
+            Level::Error | Level::Warn | Level::Info => message,
+            Level::Debug => format!("{} {message}", "[debug]".dimmed()),
+            Level::Trace => format!("{} {message}", format!("[{}]", record.target()).dimmed()),
+        };
+
+        let mut stderr = io::stderr().lock();
+        let _ = match colors_enabled(&stderr) {
+            true => writeln!(stderr, "{line}"),
+            false => writeln!(stderr, "{}", strip_colors(&line)),
+        };
+    }
+
+    fn flush(&self) {
+        if let Some(Ok(mut file)) = self.file.as_ref().map(|f| f.lock()) {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// Copies the error that ends the process into the log file, the console already gets it from color_eyre
+pub fn record_error(report: &color_eyre::Report) {
+    if let Some(logger) = LOGGER.get() {
+        logger.write_file("ERROR", "qpm", &format!("{report:?}"));
+    }
+}
+
+/// Writes a command result to stdout and mirrors it to the log file
+pub fn write_output(args: std::fmt::Arguments) {
+    let message = args.to_string();
+
+    if let Some(logger) = LOGGER.get() {
+        logger.write_file("OUTPUT", "stdout", &message);
+    }
+
+    print_stdout(&message);
+}
+
+/// Writes a secret, e.g. a token, to stdout without mirroring it to the log file
+pub fn write_secret(args: std::fmt::Arguments) {
+    print_stdout(&args.to_string());
+}
+
+fn print_stdout(message: &str) {
+    let mut stdout = io::stdout().lock();
+    let _ = match colors_enabled(&stdout) {
+        true => writeln!(stdout, "{message}"),
+        false => writeln!(stdout, "{}", strip_colors(&message)),
+    };
+}
+
+/// Prints a command result to stdout, unlike the `log` macros it is never hidden by `-q`
+#[macro_export]
+macro_rules! output {
+    () => {
+        $crate::terminal::logging::write_output(format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::terminal::logging::write_output(format_args!($($arg)*))
+    };
+}
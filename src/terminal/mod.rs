@@ -1 +1,3 @@
 pub mod colors;
+pub mod logging;
+pub mod output;
@@ -0,0 +1,41 @@
+use std::sync::OnceLock;
+
+use color_eyre::Result;
+use serde::Serialize;
+
+use crate::models::schemas::WithSchema;
+
+/// How query commands print their results
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum OutputFormat {
+    /// Human readable, colored text
+    #[default]
+    Text,
+    /// JSON documents described by the `qpm.output.*.schema.json` schemas
+    Json,
+}
+
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Sets the output format for the rest of the process, only the first call has an effect
+pub fn set_output_format(format: OutputFormat) {
+    let _ = OUTPUT_FORMAT.set(format);
+}
+
+pub fn output_format() -> OutputFormat {
+    OUTPUT_FORMAT.get().copied().unwrap_or_default()
+}
+
+pub fn is_json() -> bool {
+    output_format() == OutputFormat::Json
+}
+
+/// Prints `value` to stdout as pretty JSON tagged with its schema
+pub fn print_json<T: Serialize>(schema: &str, value: T) -> Result<()> {
+    crate::output!(
+        "{}",
+        serde_json::to_string_pretty(&WithSchema { schema, value })?
+    );
+    Ok(())
+}
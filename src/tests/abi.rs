@@ -0,0 +1,110 @@
+use std::{fs, path::Path};
+
+use color_eyre::Result;
+use qpm_package::models::package::PackageConfig;
+use semver::Version;
+
+use qpm_cli::models::{
+    abi::{Abi, AbiLinks, AbiLinksMap},
+    package::{PackageConfigExtensions, write_with_abi_links},
+};
+
+use super::mocks::repo::build_artifact_nodeps;
+
+#[test]
+fn parse_abi_names() {
+    for abi in Abi::ALL {
+        assert_eq!(abi.to_string().parse::<Abi>().unwrap(), *abi);
+    }
+    assert_eq!(
+        serde_json::to_string(&Abi::Arm64V8a).unwrap(),
+        "\"arm64-v8a\""
+    );
+
+    let err = "armeabi-v7a".parse::<Abi>().unwrap_err();
+    assert!(format!("{err}").contains("arm64-v8a, x86_64"));
+}
+
+#[test]
+fn abi_libs_path() {
+    let lib = Path::new("cache/foo/1.0.0/lib");
+    assert_eq!(Abi::Arm64V8a.cache_libs_path(lib), lib.join("arm64-v8a"));
+    assert_eq!(Abi::X86_64.cache_libs_path(lib), lib.join("x86_64"));
+
+    let libs = Path::new("extern/libs");
+    assert_eq!(Abi::Arm64V8a.extern_libs_path(libs), libs);
+    assert_eq!(Abi::X86_64.extern_libs_path(libs), libs.join("x86_64"));
+}
+
+#[test]
+fn read_abi_links() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let mut package = build_artifact_nodeps("foo", Version::new(1, 0, 0)).config;
+    package.info.additional_data.so_link = Some("https://example.com/arm64/libfoo.so".into());
+
+    // without abiLinks only arm64-v8a has binaries
+    assert_eq!(
+        AbiLinks::of(&package, temp.path(), Abi::Arm64V8a).so_link,
+        package.info.additional_data.so_link
+    );
+    assert!(AbiLinks::of(&package, temp.path(), Abi::X86_64).is_empty());
+
+    fs::write(
+        temp.path().join("qpm.json"),
+        r#"{ "abiLinks": {
+            "x86_64": {
+                "soLink": "https://example.com/x86_64/libfoo.so",
+                "debugSoLink": "https://example.com/x86_64/debug_libfoo.so"
+            }
+        } }"#,
+    )?;
+
+    let links = AbiLinks::of(&package, temp.path(), Abi::X86_64);
+    assert_eq!(
+        links.so_link.as_deref(),
+        Some("https://example.com/x86_64/libfoo.so")
+    );
+    assert_eq!(
+        links.debug_so_link.as_deref(),
+        Some("https://example.com/x86_64/debug_libfoo.so")
+    );
+    assert_eq!(links.static_link, None);
+
+    // arm64-v8a is not listed, so it keeps the regular links
+    assert_eq!(
+        AbiLinks::of(&package, temp.path(), Abi::Arm64V8a).so_link,
+        package.info.additional_data.so_link
+    );
+    Ok(())
+}
+
+#[test]
+fn package_files_keep_abi_links() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let shared = build_artifact_nodeps("foo", Version::new(1, 0, 0));
+    let links = AbiLinks {
+        so_link: Some("https://example.com/x86_64/libfoo.so".into()),
+        ..Default::default()
+    };
+
+    write_with_abi_links(
+        &shared.config,
+        temp.path(),
+        &AbiLinksMap::from([(Abi::X86_64, links.clone())]),
+    )?;
+    // qpm_package does not know abiLinks, rewriting the package must not drop them
+    let mut package = PackageConfig::read(temp.path())?;
+    package.info.additional_data.branch_name = Some("main".into());
+    package.write(temp.path())?;
+    shared.write(temp.path())?;
+
+    assert_eq!(AbiLinks::of(&package, temp.path(), Abi::X86_64), links);
+    // the shared package carries them to dependents
+    fs::remove_file(temp.path().join("qpm.json"))?;
+    assert_eq!(AbiLinks::of(&package, temp.path(), Abi::X86_64), links);
+
+    // without any, the files look like before
+    write_with_abi_links(&package, temp.path(), &AbiLinksMap::new())?;
+    assert!(!fs::read_to_string(temp.path().join("qpm.json"))?.contains("abiLinks"));
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use std::fs;
+
+use color_eyre::Result;
+use qpm_package::models::dependency::SharedPackageConfig;
+use semver::{Version, VersionReq};
+
+use qpm_cli::{
+    api::{self, Error, NdkResolveOptions, QmodManifestOptions, RestoreOptions},
+    models::package::{PackageConfigExtensions, SharedPackageConfigExtensions},
+    resolver::dependency,
+};
+
+use super::mocks::repo::{build_artifact_and_depend, build_artifact_nodeps, get_mock_repository};
+
+#[test]
+fn missing_workspace_files() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+
+    let restored = api::restore(temp.path(), &RestoreOptions::default());
+    assert!(matches!(restored, Err(Error::MissingPackage(path)) if path == temp.path()));
+
+    let package = build_artifact_nodeps("api-mod", Version::new(1, 0, 0)).config;
+    package.write(temp.path())?;
+
+    let manifest = api::qmod_manifest(temp.path(), &QmodManifestOptions::default());
+    assert!(matches!(manifest, Err(Error::MissingLock(_))));
+
+    let ndk = api::resolve_ndk(temp.path(), &NdkResolveOptions::default());
+    assert!(matches!(ndk, Err(Error::NoNdkRequirement)));
+
+    Ok(())
+}
+
+#[test]
+fn validate_ndk_from_ndk_path() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let ndk_path = temp.path().join("ndk").join("27.2.12479018");
+    fs::create_dir_all(&ndk_path)?;
+    api::write_ndk_path(temp.path(), &ndk_path)?;
+
+    let mut package = build_artifact_nodeps("api-mod", Version::new(1, 0, 0)).config;
+
+    package.workspace.ndk = Some(VersionReq::parse("^27.0.0")?);
+    api::validate_ndk(temp.path(), &package)?;
+
+    package.workspace.ndk = Some(VersionReq::parse("^28.0.0")?);
+    let validated = api::validate_ndk(temp.path(), &package);
+    assert!(matches!(
+        validated,
+        Err(Error::NdkMismatch { version, .. }) if version == Version::new(27, 2, 12479018)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn frozen_restore_ignores_unrelated_edits() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let mut repo = get_mock_repository();
+
+    let lock = build_artifact_nodeps("api-mod", Version::new(1, 0, 0));
+    lock.write(temp.path())?;
+
+    // only the name changed, the locked dependencies are the same
+    let mut package = lock.config.clone();
+    package.info.name = "Renamed mod".to_string();
+    package.write(temp.path())?;
+
+    let options = RestoreOptions {
+        frozen: true,
+        dry_run: true,
+        ..Default::default()
+    };
+    let restored = api::restore_with_repository(temp.path(), &options, &mut repo)?;
+    assert!(!restored.lock_changed);
+    assert_eq!(restored.shared_package.config.info.name, "Renamed mod");
+
+    Ok(())
+}
+
+#[test]
+fn frozen_restore_without_lock() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let mut repo = get_mock_repository();
+
+    let package = build_artifact_nodeps("api-mod", Version::new(1, 0, 0)).config;
+    package.write(temp.path())?;
+
+    let options = RestoreOptions {
+        frozen: true,
+        ..Default::default()
+    };
+    let restored = api::restore_with_repository(temp.path(), &options, &mut repo);
+    assert!(matches!(restored, Err(Error::LockOutOfDate(_))));
+    assert!(!SharedPackageConfig::exists(temp.path()));
+
+    Ok(())
+}
+
+#[test]
+fn frozen_restore_with_lock_out_of_sync() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let mut repo = get_mock_repository();
+
+    let lock = build_artifact_nodeps("api-mod", Version::new(1, 0, 0));
+    lock.write(temp.path())?;
+
+    // a dependency was added to qpm.json since the lock file was written
+    let artifact1 = build_artifact_nodeps("artifact1", Version::new(0, 1, 0));
+    let package = build_artifact_and_depend(
+        "api-mod",
+        Version::new(1, 0, 0),
+        &artifact1,
+        VersionReq::STAR,
+    )
+    .config;
+    package.write(temp.path())?;
+
+    let options = RestoreOptions {
+        frozen: true,
+        ..Default::default()
+    };
+    let restored = api::restore_with_repository(temp.path(), &options, &mut repo);
+    assert!(matches!(restored, Err(Error::LockOutOfDate(_))));
+
+    Ok(())
+}
+
+#[test]
+fn frozen_restore_requiring_download() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let mut repo = get_mock_repository();
+
+    // artifact1 is only in the mock repository, not in the cache
+    let artifact1 = build_artifact_nodeps("artifact1", Version::new(0, 1, 0));
+    let lock = build_artifact_and_depend(
+        "api-mod",
+        Version::new(1, 0, 0),
+        &artifact1,
+        VersionReq::STAR,
+    );
+    lock.write(temp.path())?;
+    lock.config.write(temp.path())?;
+
+    let options = RestoreOptions {
+        frozen: true,
+        ..Default::default()
+    };
+    let restored = api::restore_with_repository(temp.path(), &options, &mut repo);
+    assert!(matches!(
+        restored,
+        Err(Error::DownloadRequired(downloads))
+            if downloads == [("artifact1".to_string(), Version::new(0, 1, 0))]
+    ));
+    assert!(!temp.path().join("extern").exists());
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_reports_generated_files() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let mut repo = get_mock_repository();
+
+    let package = build_artifact_nodeps("api-mod", Version::new(1, 0, 0)).config;
+    package.write(temp.path())?;
+
+    let options = RestoreOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+    let restored = api::restore_with_repository(temp.path(), &options, &mut repo)?;
+    assert!(restored.lock_changed);
+    assert!(restored.has_changes());
+    assert!(
+        restored
+            .generated_changes
+            .contains(&temp.path().join("extern.cmake"))
+    );
+    assert!(
+        restored
+            .generated_changes
+            .contains(&temp.path().join("qpm_defines.cmake"))
+    );
+    assert!(!SharedPackageConfig::exists(temp.path()));
+    assert!(!temp.path().join("extern.cmake").exists());
+
+    // once written, the generated files are up to date
+    for (path, content) in
+        dependency::generated_files(temp.path(), &restored.shared_package, &repo)?
+    {
+        fs::write(path, content)?;
+    }
+    let restored = api::restore_with_repository(temp.path(), &options, &mut repo)?;
+    assert!(restored.generated_changes.is_empty());
+
+    Ok(())
+}
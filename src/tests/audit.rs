@@ -0,0 +1,98 @@
+use std::fs;
+
+use color_eyre::Result;
+use semver::Version;
+
+use qpm_cli::models::advisory::{AdvisoryDatabase, AdvisorySeverity, AdvisorySource};
+
+#[test]
+fn load_advisory_database() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    fs::create_dir_all(temp.path().join("beatsaber-hook"))?;
+    fs::write(
+        temp.path().join("beatsaber-hook").join("QPM-0002.json"),
+        r#"{
+            "id": "QPM-0002",
+            "package": "beatsaber-hook",
+            "affected": [">=5.0.0, <5.1.2", "^3.0.0"],
+            "severity": "critical",
+            "description": "Crashes on startup"
+        }"#,
+    )?;
+    fs::write(
+        temp.path().join("more.json"),
+        r#"[{
+            "id": "QPM-0001",
+            "package": "paper",
+            "affected": ["<1.2.0"],
+            "severity": "low",
+            "description": "Leaks file handles",
+            "url": "https://example.com/QPM-0001"
+        }]"#,
+    )?;
+    fs::write(temp.path().join("README.md"), "not an advisory")?;
+    fs::write(temp.path().join("settings.json"), r#"{ "tabSize": 4 }"#)?;
+
+    let db = AdvisoryDatabase::load(temp.path())?;
+    assert_eq!(
+        db.advisories
+            .iter()
+            .map(|a| a.id.as_str())
+            .collect::<Vec<_>>(),
+        ["QPM-0001", "QPM-0002"]
+    );
+
+    let affected = |id: &str, version: &str| {
+        db.affecting(id, &Version::parse(version).unwrap())
+            .map(|a| a.severity)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        affected("beatsaber-hook", "5.1.1"),
+        [AdvisorySeverity::Critical]
+    );
+    assert_eq!(
+        affected("beatsaber-hook", "3.4.0"),
+        [AdvisorySeverity::Critical]
+    );
+    assert!(affected("beatsaber-hook", "5.1.2").is_empty());
+    assert!(affected("beatsaber-hook", "4.0.0").is_empty());
+    assert_eq!(affected("paper", "1.1.9"), [AdvisorySeverity::Low]);
+    assert!(affected("paper", "1.2.0").is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn advisory_source_kind() {
+    assert!(matches!(
+        AdvisorySource::parse("https://github.com/QuestPackageManager/advisories.git"),
+        AdvisorySource::Git(_)
+    ));
+    assert!(matches!(
+        AdvisorySource::parse("git@github.com:QuestPackageManager/advisories.git"),
+        AdvisorySource::Git(_)
+    ));
+    assert!(matches!(
+        AdvisorySource::parse("./advisories.json"),
+        AdvisorySource::File(_)
+    ));
+    assert!(AdvisorySeverity::Critical > AdvisorySeverity::High);
+}
+
+#[test]
+fn failed_advisory_sync() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let source = AdvisorySource::Git(temp.path().join("missing").to_string_lossy().to_string());
+    let path = temp.path().join("advisories");
+
+    assert!(source.sync_to(&path).is_err());
+    // a failed clone leaves nothing behind that could be mistaken for an empty database
+    assert!(!path.exists());
+    assert!(!path.with_extension("tmp").exists());
+
+    fs::create_dir_all(&path)?;
+    let err = source.load_from(&path).unwrap_err();
+    assert!(format!("{err:?}").contains("never synced"));
+    Ok(())
+}
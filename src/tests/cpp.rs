@@ -0,0 +1,129 @@
+use std::path::Path;
+
+use qpm_cli::utils::cpp::{ApiChange, DeclarationKind, diff_declarations, scan_header};
+
+const WIDGET_HEADER: &str = r#"
+#pragma once
+#define MY_API_VERSION 3
+#define LOG(msg, ...) log(msg)
+
+namespace foo {
+/// adds things
+int add(int a, int b = 2);
+
+class Widget : public Base {
+  public:
+    Widget();
+    virtual void draw(const Canvas& canvas) const override;
+    int width;
+  private:
+    void secret();
+};
+
+enum class Color : int { Red, Green = 2 };
+using Callback = std::function<void(int)>;
+}
+"#;
+
+#[test]
+fn scan_public_declarations() {
+    let declarations = scan_header(WIDGET_HEADER, Path::new("widget.hpp"));
+    let found = declarations
+        .iter()
+        .map(|d| (d.kind, d.name.as_str(), d.key.as_str()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        found,
+        [
+            (
+                DeclarationKind::Macro,
+                "MY_API_VERSION",
+                "#define MY_API_VERSION"
+            ),
+            (DeclarationKind::Macro, "LOG", "#define LOG(msg, ...)"),
+            (
+                DeclarationKind::Function,
+                "foo::add",
+                "int add(int, int = 2)"
+            ),
+            (
+                DeclarationKind::Class,
+                "foo::Widget",
+                "class Widget : public Base"
+            ),
+            (DeclarationKind::Function, "foo::Widget::Widget", "Widget()"),
+            (
+                DeclarationKind::Function,
+                "foo::Widget::draw",
+                "virtual void draw(const Canvas&) const"
+            ),
+            (DeclarationKind::Variable, "foo::Widget::width", "int width"),
+            (
+                DeclarationKind::Enum,
+                "foo::Color",
+                "enum class Color : int"
+            ),
+            (DeclarationKind::Enumerator, "foo::Color::Red", "Red"),
+            (
+                DeclarationKind::Enumerator,
+                "foo::Color::Green",
+                "Green = 2"
+            ),
+            (
+                DeclarationKind::Alias,
+                "foo::Callback",
+                "using Callback = std::function<void(int)>"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn diff_header_versions() {
+    let old = scan_header(
+        r#"
+        namespace foo {
+        int add(int a, int b);
+        double area(const Shape& s);
+        void removed();
+        struct Gone { int x; };
+        }
+        "#,
+        Path::new("foo.hpp"),
+    );
+    let new = scan_header(
+        r#"
+        namespace foo {
+        int add(int lhs, int rhs);
+        int add(int a, int b, int c);
+        float area(const Shape& shape);
+        float scale(float f);
+        }
+        "#,
+        Path::new("foo.hpp"),
+    );
+
+    let changes = diff_declarations(&old, &new);
+    let summary = changes
+        .iter()
+        .map(|change| match change {
+            ApiChange::Removed(d) => ("-", d.name.as_str()),
+            ApiChange::Changed { new, .. } => ("~", new.name.as_str()),
+            ApiChange::Added(d) => ("+", d.name.as_str()),
+        })
+        .collect::<Vec<_>>();
+
+    // renamed parameters are not a change, members of removed classes are implied
+    assert_eq!(
+        summary,
+        [
+            ("-", "foo::Gone"),
+            ("+", "foo::add"),
+            ("~", "foo::area"),
+            ("-", "foo::removed"),
+            ("+", "foo::scale"),
+        ]
+    );
+    assert!(changes.iter().any(ApiChange::is_breaking));
+}
@@ -0,0 +1,92 @@
+use std::{
+    io::{Cursor, Write},
+    sync::{Arc, Mutex},
+};
+
+use color_eyre::Result;
+use serde_json::{Value, json};
+
+use qpm_cli::commands::daemon::{
+    self,
+    rpc::{self, Framing, METHOD_NOT_FOUND, PARSE_ERROR},
+};
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs the daemon on `input` and returns every message it answered with
+fn serve(input: &str) -> Result<Vec<(Value, Framing)>> {
+    let output = SharedBuffer::default();
+    daemon::serve(Cursor::new(input.to_string()), output.clone(), true)?;
+
+    let written = output.0.lock().unwrap().clone();
+    let mut reader = Cursor::new(written);
+    let mut messages = vec![];
+    while let Some((message, framing)) = rpc::read_message(&mut reader)? {
+        messages.push((serde_json::from_str(&message)?, framing));
+    }
+    Ok(messages)
+}
+
+#[test]
+fn daemon_answers_requests() -> Result<()> {
+    let messages = serve(concat!(
+        r#"{"jsonrpc":"2.0","id":1,"method":"version"}"#,
+        "\n",
+        r#"{"jsonrpc":"2.0","id":2,"method":"frobnicate"}"#,
+        "\n",
+        r#"{"jsonrpc":"2.0","method":"version"}"#,
+        "\n",
+        "{ not json\n",
+    ))?;
+
+    // the notification is not answered
+    assert_eq!(messages.len(), 3);
+    let (version, framing) = &messages[0];
+    assert_eq!(*framing, Framing::Lines);
+    assert_eq!(version["id"], json!(1));
+    assert_eq!(
+        version["result"]["version"],
+        json!(env!("CARGO_PKG_VERSION"))
+    );
+
+    assert_eq!(messages[1].0["id"], json!(2));
+    assert_eq!(messages[1].0["error"]["code"], json!(METHOD_NOT_FOUND));
+
+    assert_eq!(messages[2].0["id"], Value::Null);
+    assert_eq!(messages[2].0["error"]["code"], json!(PARSE_ERROR));
+
+    Ok(())
+}
+
+#[test]
+fn daemon_content_length_framing() -> Result<()> {
+    let request = r#"{"jsonrpc":"2.0","id":"a","method":"version"}"#;
+    let shutdown = r#"{"jsonrpc":"2.0","id":"b","method":"shutdown"}"#;
+    let input = format!(
+        "Content-Length: {}\r\n\r\n{request}Content-Length: {}\r\n\r\n{shutdown}{request}\n",
+        request.len(),
+        shutdown.len()
+    );
+
+    let messages = serve(&input)?;
+
+    // nothing is answered after shutdown
+    assert_eq!(messages.len(), 2);
+    assert!(messages.iter().all(|(_, f)| *f == Framing::Headers));
+    assert_eq!(messages[0].0["id"], json!("a"));
+    assert_eq!(messages[1].0["id"], json!("b"));
+    assert_eq!(messages[1].0["result"], Value::Null);
+
+    Ok(())
+}
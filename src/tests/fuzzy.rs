@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use color_eyre::Result;
+use semver::Version;
+
+use qpm_cli::{
+    commands::search::rank_packages,
+    utils::fuzzy::{fuzzy_score, package_score},
+};
+
+use super::mocks::repo::{build_artifact_nodeps, build_repository};
+
+#[test]
+fn fuzzy_matches_subsequences() {
+    assert_eq!(fuzzy_score("bs-hook", "bs-hook"), Some(1000));
+    assert!(fuzzy_score("bshook", "beatsaber-hook").is_some());
+    assert!(fuzzy_score("HOOK", "beatsaber-hook").is_some());
+    assert_eq!(fuzzy_score("hookz", "beatsaber-hook"), None);
+    assert_eq!(fuzzy_score("", "anything"), Some(0));
+}
+
+#[test]
+fn fuzzy_ranks_better_matches_higher() {
+    let score = |candidate: &str| fuzzy_score("hook", candidate).unwrap();
+
+    // prefix beats substring beats scattered
+    assert!(score("hooks-lib") > score("beatsaber-hook"));
+    assert!(score("beatsaber-hook") > score("h-o-o-k"));
+    // shorter candidates win ties
+    assert!(score("beatsaber-hook") > score("beatsaber-hook-extras-for-testing"));
+}
+
+#[test]
+fn package_score_uses_name() {
+    assert!(package_score("custom types", "custom-types", None).is_some());
+    assert_eq!(package_score("Beat Saber Hook", "bs-hook", None), None);
+    assert!(package_score("Beat Saber Hook", "bs-hook", Some("Beat Saber Hook")).is_some());
+    assert_eq!(
+        package_score("Beat Saber Hook", "bs-hook", Some("Beat Saber Hook")),
+        Some(1000)
+    );
+}
+
+#[test]
+fn search_finds_uncached_packages_by_name() -> Result<()> {
+    let named = |version: Version| {
+        let mut package = build_artifact_nodeps("bs-hook", version);
+        package.config.info.name = "Beat Saber Hook".to_string();
+        package
+    };
+    let repo = build_repository([
+        named(Version::new(1, 2, 0)),
+        named(Version::new(1, 10, 0)),
+        named(Version::new(1, 9, 0)),
+        build_artifact_nodeps("paper", Version::new(1, 0, 0)),
+    ]);
+
+    let results = rank_packages(&repo, "Beat Saber Hook", 20, &HashMap::new())?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, "bs-hook");
+    assert_eq!(results[0].name.as_deref(), Some("Beat Saber Hook"));
+    assert_eq!(results[0].latest_version, Some(Version::new(1, 10, 0)));
+    Ok(())
+}
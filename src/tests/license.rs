@@ -0,0 +1,64 @@
+use std::fs;
+
+use color_eyre::Result;
+use semver::Version;
+
+use qpm_cli::utils::license::{detect_spdx_id, read_package_license, third_party_notices};
+
+const MIT: &str = "MIT License\n\nCopyright (c) 2024 someone\n\nPermission is hereby granted, free of charge,\nto any person obtaining a copy";
+
+#[test]
+fn detect_license_texts() {
+    assert_eq!(detect_spdx_id(MIT), Some("MIT"));
+    assert_eq!(
+        detect_spdx_id("Apache License\n   Version 2.0, January 2004"),
+        Some("Apache-2.0")
+    );
+    assert_eq!(
+        detect_spdx_id("GNU LESSER GENERAL PUBLIC LICENSE\nVersion 3, 29 June 2007"),
+        Some("LGPL-3.0")
+    );
+    assert_eq!(detect_spdx_id("All rights reserved"), None);
+}
+
+#[test]
+fn read_license_of_package() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    fs::write(temp.path().join("LICENSE.md"), MIT)?;
+    fs::write(temp.path().join("license-third-party"), "zlib")?;
+    fs::write(temp.path().join("README.md"), "readme")?;
+    fs::create_dir(temp.path().join("COPYING"))?;
+
+    let license = read_package_license("foo", &Version::new(1, 0, 0), temp.path())?;
+    assert_eq!(license.detected.as_deref(), Some("MIT"));
+    assert_eq!(license.declared, None);
+    assert_eq!(
+        license
+            .files
+            .iter()
+            .map(|f| f.file_name().unwrap().to_str().unwrap())
+            .collect::<Vec<_>>(),
+        ["LICENSE.md", "license-third-party"]
+    );
+
+    // declared licenses win over detected ones
+    fs::write(
+        temp.path().join("qpm.json"),
+        r#"{ "info": { "additionalData": { "license": "MIT OR Apache-2.0" } } }"#,
+    )?;
+    let license = read_package_license("foo", &Version::new(1, 0, 0), temp.path())?;
+    assert_eq!(license.license(), Some("MIT OR Apache-2.0"));
+
+    let notices = third_party_notices(&[license])?;
+    assert!(notices.contains("foo 1.0.0 - MIT OR Apache-2.0"));
+    assert!(notices.contains("Permission is hereby granted"));
+    Ok(())
+}
+
+#[test]
+fn missing_license_is_unknown() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let license = read_package_license("foo", &Version::new(1, 0, 0), temp.path())?;
+    assert!(license.is_unknown());
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use std::{fs, path::PathBuf};
+
+use color_eyre::Result;
+use qpm_package::models::package::PackageDependency;
+use semver::{Version, VersionReq};
+
+use qpm_cli::{
+    models::output::LintSeverity,
+    utils::lint::{Fix, has_upper_bound, lint_package, lower_bound_caret},
+};
+
+use super::mocks::repo::{build_artifact_and_depend, build_artifact_nodeps};
+
+#[test]
+fn version_range_upper_bound() -> Result<()> {
+    for bounded in ["^1.2.0", "~1.2", "=1.2.3", ">=1.0.0, <2.0.0", "1.*"] {
+        assert!(has_upper_bound(&VersionReq::parse(bounded)?), "{bounded}");
+    }
+    for unbounded in ["*", ">=1.2", ">1.0.0"] {
+        assert!(
+            !has_upper_bound(&VersionReq::parse(unbounded)?),
+            "{unbounded}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn unbounded_range_fix() -> Result<()> {
+    for (unbounded, fixed) in [
+        (">=1.2", "^1.2.0"),
+        (">=1.2.3", "^1.2.3"),
+        (">1.2.3", "^1.2.4"),
+        (">1.2", "^1.3.0"),
+        (">1", "^2.0.0"),
+        (">1.2.3-rc.1", "^1.2.3"),
+    ] {
+        assert_eq!(
+            lower_bound_caret(&VersionReq::parse(unbounded)?),
+            Some(VersionReq::parse(fixed)?),
+            "{unbounded}"
+        );
+    }
+    assert_eq!(lower_bound_caret(&VersionReq::STAR), None);
+    Ok(())
+}
+
+#[test]
+fn lint_manifest_mistakes() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let dep = build_artifact_nodeps("dep", Version::new(1, 4, 0));
+    let mut shared = build_artifact_and_depend(
+        "pkg",
+        Version::new(0, 1, 0),
+        &dep,
+        VersionReq::parse(">=1.2")?,
+    );
+    shared.config.dependencies.push(PackageDependency {
+        id: "anything".to_string(),
+        version_range: VersionReq::STAR,
+        additional_data: Default::default(),
+    });
+    shared.config.dependencies_dir = PathBuf::from("extern/..");
+    fs::write(temp.path().join("CMakeLists.txt"), "")?;
+
+    let findings = lint_package(&shared.config, Some(&shared), temp.path());
+    let found = findings
+        .iter()
+        .map(|f| (f.id, f.severity, f.fix.clone()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        found,
+        [
+            ("missing-binary-link", LintSeverity::Error, None),
+            (
+                "missing-shared-dir",
+                LintSeverity::Error,
+                Some(Fix::CreateSharedDir)
+            ),
+            ("cmake-unset", LintSeverity::Warning, Some(Fix::EnableCmake)),
+            ("missing-url", LintSeverity::Warning, None),
+            (
+                "dependencies-dir-ancestor",
+                LintSeverity::Error,
+                Some(Fix::SetDependenciesDir(PathBuf::from("extern")))
+            ),
+            (
+                "unbounded-range",
+                LintSeverity::Warning,
+                Some(Fix::SetVersionRange {
+                    dependency: "dep".to_string(),
+                    range: VersionReq::parse("^1.2.0")?,
+                })
+            ),
+            // not restored, so there is no version to suggest
+            ("unbounded-range", LintSeverity::Warning, None),
+        ]
+    );
+
+    let mut package = shared.config.clone();
+    for fix in findings.iter().filter_map(|f| f.fix.as_ref()) {
+        fix.apply(&mut package, temp.path())?;
+    }
+    let remaining = lint_package(&package, Some(&shared), temp.path())
+        .into_iter()
+        .map(|f| f.id)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        remaining,
+        ["missing-binary-link", "missing-url", "unbounded-range"]
+    );
+    Ok(())
+}
@@ -0,0 +1,389 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::Result;
+use qpm_package::{
+    extensions::package_metadata::PackageMetadataExtensions,
+    models::dependency::SharedPackageConfig,
+};
+use semver::{Version, VersionReq};
+
+use qpm_cli::{
+    models::{
+        abi::Abi,
+        package::{SharedPackageConfigExtensions, StaticLibraryExtensions},
+    },
+    repository::local::{CopyTarget, ExternChange, FileRepository},
+    utils::elf,
+};
+
+use super::mocks::{
+    elf::{EM_AARCH64, EM_X86_64, shared_object},
+    repo::{build_artifact_and_depend, build_artifact_nodeps},
+};
+
+fn target(package_id: &str, source: &str, destination: &str) -> CopyTarget {
+    CopyTarget {
+        source: PathBuf::from(source),
+        destination: PathBuf::from(destination),
+        package_id: package_id.to_string(),
+    }
+}
+
+#[test]
+fn copy_targets_distinct() -> Result<()> {
+    let targets = [
+        target("a", "cache/a/src/shared", "extern/includes/a/shared"),
+        target("b", "cache/b/src/shared", "extern/includes/b/shared"),
+        target("a", "cache/a/lib/liba.so", "extern/libs/liba.so"),
+        target("b", "cache/b/lib/libb.so", "extern/libs/libb.so"),
+    ];
+
+    FileRepository::validate_copy_targets(&targets)
+}
+
+#[test]
+fn copy_targets_same_destination() {
+    let targets = [
+        target("foo", "cache/foo/lib/libfoo.so", "extern/libs/libfoo.so"),
+        target(
+            "foo-fork",
+            "cache/foo-fork/lib/libfoo.so",
+            "extern/libs/libfoo.so",
+        ),
+    ];
+
+    let err = FileRepository::validate_copy_targets(&targets).unwrap_err();
+    let message = format!("{err}");
+    assert!(message.contains("foo") && message.contains("foo-fork"));
+}
+
+#[test]
+fn copy_targets_overlapping_destination() {
+    let targets = [
+        target("a", "cache/a/src/shared", "extern/includes/a/shared"),
+        target("a", "cache/a/src/extra", "extern/includes/a/shared/extra"),
+    ];
+
+    assert!(FileRepository::validate_copy_targets(&targets).is_err());
+}
+
+#[test]
+fn copy_targets_overlapping_other_package() {
+    let targets = [
+        target("a", "cache/a/src/shared", "extern/includes/a/shared"),
+        target("b", "cache/b/src/include", "extern/includes/a/shared/b"),
+    ];
+
+    let err = FileRepository::validate_copy_targets(&targets).unwrap_err();
+    let message = format!("{err}");
+    assert!(message.contains("a") && message.contains("b"));
+    assert!(message.contains("overlapping destinations"));
+}
+
+#[test]
+fn copy_targets_parent_components() {
+    // a misconfigured sharedDir of `../b/shared`
+    let targets = [
+        target("a", "cache/a/src/shared", "extern/includes/a/../b/shared"),
+        target("b", "cache/b/src/shared", "extern/includes/b/shared"),
+    ];
+
+    let err = FileRepository::validate_copy_targets(&targets).unwrap_err();
+    assert!(format!("{err}").contains("the same destination"));
+}
+
+#[test]
+fn package_destination_stays_in_package() -> Result<()> {
+    let root = Path::new("extern/includes/a");
+    assert_eq!(
+        FileRepository::package_destination(root, Path::new("./shared/../include"), "a")?,
+        root.join("include")
+    );
+
+    let err = FileRepository::package_destination(root, Path::new("../b/shared"), "a").unwrap_err();
+    assert!(format!("{err}").contains("outside of"));
+    Ok(())
+}
+
+#[test]
+fn copy_targets_nested_mirror() -> Result<()> {
+    // extra file already inside the shared folder ends up in the same place
+    let targets = [
+        target("a", "cache/a/src/shared", "extern/includes/a/shared"),
+        target(
+            "a",
+            "cache/a/src/shared/extra.hpp",
+            "extern/includes/a/shared/extra.hpp",
+        ),
+    ];
+
+    FileRepository::validate_copy_targets(&targets)
+}
+
+#[test]
+fn extern_changes_incremental() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let cache = temp.path().join("cache");
+    let extern_dir = temp.path().join("extern");
+
+    std::fs::create_dir_all(cache.join("shared/nested"))?;
+    std::fs::write(cache.join("shared/a.hpp"), "a")?;
+    std::fs::write(cache.join("shared/nested/b.hpp"), "b")?;
+
+    let files = [(cache.join("shared"), extern_dir.join("includes/a/shared"))];
+
+    // fresh restore copies everything
+    let changes = FileRepository::plan_extern_changes(&files, &extern_dir, false)?;
+    assert_eq!(changes.len(), 2);
+    FileRepository::apply_extern_changes(&changes)?;
+
+    // nothing changed, nothing to do
+    let header = extern_dir.join("includes/a/shared/a.hpp");
+    let modified = std::fs::metadata(&header)?.modified()?;
+    assert!(FileRepository::plan_extern_changes(&files, &extern_dir, false)?.is_empty());
+
+    // stale files are removed, changed files are copied, unchanged files are untouched
+    std::fs::write(extern_dir.join("includes/stale.hpp"), "stale")?;
+    std::fs::write(cache.join("shared/nested/b.hpp"), "changed")?;
+
+    let changes = FileRepository::plan_extern_changes(&files, &extern_dir, false)?;
+    assert!(changes.contains(&ExternChange::Remove(extern_dir.join("includes/stale.hpp"))));
+    assert!(changes.contains(&ExternChange::Copy {
+        source: cache.join("shared/nested/b.hpp"),
+        destination: extern_dir.join("includes/a/shared/nested/b.hpp"),
+    }));
+    assert_eq!(changes.len(), 2);
+
+    FileRepository::apply_extern_changes(&changes)?;
+    assert_eq!(std::fs::metadata(&header)?.modified()?, modified);
+    assert!(FileRepository::plan_extern_changes(&files, &extern_dir, false)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn extern_copies_kept_when_linking() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let cache = temp.path().join("cache");
+    let extern_dir = temp.path().join("extern");
+
+    std::fs::create_dir_all(cache.join("shared"))?;
+    std::fs::write(cache.join("shared/a.hpp"), "a")?;
+    std::fs::write(cache.join("libfoo.so"), "so")?;
+
+    let files = [
+        (cache.join("shared"), extern_dir.join("includes/foo/shared")),
+        (cache.join("libfoo.so"), extern_dir.join("libs/libfoo.so")),
+    ];
+
+    // what is left behind when creating symlinks failed and restore fell back to copying
+    let changes = FileRepository::plan_extern_changes(&files, &extern_dir, false)?;
+    FileRepository::apply_extern_changes(&changes)?;
+
+    assert!(FileRepository::plan_extern_changes(&files, &extern_dir, true)?.is_empty());
+
+    // outdated copies are linked again
+    std::fs::write(cache.join("shared/a.hpp"), "changed")?;
+    let changes = FileRepository::plan_extern_changes(&files, &extern_dir, true)?;
+    assert_eq!(
+        changes,
+        [
+            ExternChange::Remove(extern_dir.join("includes/foo/shared")),
+            ExternChange::Link {
+                source: cache.join("shared"),
+                destination: extern_dir.join("includes/foo/shared"),
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn html_binary_rejected() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let binary = temp.path().join("libfoo.so");
+    std::fs::write(&binary, "<!DOCTYPE html><html>Not Found</html>")?;
+
+    let package = build_artifact_nodeps("foo", Version::new(1, 0, 0));
+    let err =
+        FileRepository::validate_binary(&package.config, &binary, Abi::Arm64V8a, true).unwrap_err();
+    let message = format!("{err:?}");
+    assert!(message.contains("foo") && message.contains("html"));
+
+    Ok(())
+}
+
+fn so_name(package: &SharedPackageConfig) -> String {
+    let so_name = package.config.info.get_so_name2();
+    so_name.file_name().unwrap().to_string_lossy().to_string()
+}
+
+#[test]
+fn binary_of_other_architecture_rejected() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let package = build_artifact_nodeps("foo", Version::new(1, 0, 0));
+    let binary = temp.path().join("libfoo.so");
+    std::fs::write(
+        &binary,
+        shared_object(EM_X86_64, Some(&so_name(&package)), &[]),
+    )?;
+
+    let err = FileRepository::validate_binary(&package.config, &binary, Abi::Arm64V8a, false)
+        .unwrap_err();
+    assert!(format!("{err:?}").contains("X86_64"));
+
+    FileRepository::validate_binary(&package.config, &binary, Abi::X86_64, true)?;
+    Ok(())
+}
+
+#[test]
+fn soname_mismatch_strict() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let package = build_artifact_nodeps("foo", Version::new(1, 0, 0));
+    let binary = temp.path().join("libfoo.so");
+    std::fs::write(&binary, shared_object(EM_AARCH64, Some("libother.so"), &[]))?;
+
+    let err =
+        FileRepository::validate_binary(&package.config, &binary, Abi::Arm64V8a, true).unwrap_err();
+    assert!(format!("{err:?}").contains("libother.so"));
+
+    // already cached binaries only warn
+    let info = FileRepository::validate_binary(&package.config, &binary, Abi::Arm64V8a, false)?;
+    assert_eq!(info.soname.as_deref(), Some("libother.so"));
+    Ok(())
+}
+
+/// `qpm install` checks its binaries before they replace the cached ones
+#[test]
+fn install_rejects_invalid_binaries() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let package = build_artifact_nodeps("foo", Version::new(1, 0, 0));
+    let binary = temp.path().join("libfoo.so");
+    let mut repo = FileRepository::default();
+
+    std::fs::write(
+        &binary,
+        shared_object(EM_X86_64, Some(&so_name(&package)), &[]),
+    )?;
+    let err = repo
+        .add_artifact_and_cache(
+            package.clone(),
+            temp.path().to_path_buf(),
+            Some(binary.clone()),
+            None,
+            &[],
+            true,
+            true,
+        )
+        .unwrap_err();
+    assert!(format!("{err:?}").contains("X86_64"));
+
+    // libbar.so is not provided by any dependency of foo
+    std::fs::write(
+        &binary,
+        shared_object(EM_AARCH64, Some(&so_name(&package)), &["libbar.so"]),
+    )?;
+    let err = repo
+        .add_artifact_and_cache(
+            package.clone(),
+            temp.path().to_path_buf(),
+            Some(binary),
+            None,
+            &[],
+            true,
+            true,
+        )
+        .unwrap_err();
+    assert!(format!("{err:?}").contains("libbar.so"));
+    assert!(
+        repo.get_artifact("foo", &package.config.info.version)
+            .is_none()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn needed_library_from_dependencies() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let bar = build_artifact_nodeps("bar", Version::new(1, 0, 0));
+    let foo = build_artifact_and_depend(
+        "foo",
+        Version::new(1, 0, 0),
+        &bar,
+        VersionReq::parse("^1.0.0")?,
+    );
+
+    let binary = temp.path().join("libfoo.so");
+    std::fs::write(
+        &binary,
+        shared_object(
+            EM_AARCH64,
+            Some(&so_name(&foo)),
+            &["liblog.so", &so_name(&bar)],
+        ),
+    )?;
+    let info = FileRepository::validate_binary(&foo.config, &binary, Abi::Arm64V8a, true)?;
+    assert_eq!(info.needed, ["liblog.so".to_string(), so_name(&bar)]);
+
+    FileRepository::validate_needed(&foo, &info, Abi::Arm64V8a, &[foo.clone(), bar.clone()])?;
+
+    // bar is restored as well, but foo does not depend on it
+    let standalone = build_artifact_nodeps("foo", Version::new(1, 0, 0));
+    let err = FileRepository::validate_needed(
+        &standalone,
+        &info,
+        Abi::Arm64V8a,
+        &[standalone.clone(), bar.clone()],
+    )
+    .unwrap_err();
+    assert!(format!("{err:?}").contains(&so_name(&bar)));
+    Ok(())
+}
+
+#[test]
+fn static_archive_validated() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let archive = temp.path().join("libfoo.a");
+
+    std::fs::write(
+        &archive,
+        "!<arch>\n/               0           0     0     0       4         `\n",
+    )?;
+    assert!(elf::is_static_archive(&archive));
+    elf::ensure_static_archive(&archive)?;
+
+    std::fs::write(&archive, "<!DOCTYPE html><html>Not Found</html>")?;
+    assert!(elf::ensure_static_archive(&archive).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn static_library_not_bundled() -> Result<()> {
+    let mut dep = build_artifact_nodeps("static-dep", Version::new(1, 0, 0));
+    let shared = build_artifact_and_depend(
+        "pkg",
+        Version::new(0, 1, 0),
+        &dep,
+        VersionReq::parse("^1.0.0")?,
+    );
+    assert_eq!(shared.to_mod_json().library_files.len(), 1);
+
+    dep.config.info.additional_data.static_linking = Some(true);
+    assert_eq!(
+        dep.config.info.get_binary_name().extension(),
+        Some("a".as_ref())
+    );
+
+    let shared = build_artifact_and_depend(
+        "pkg",
+        Version::new(0, 1, 0),
+        &dep,
+        VersionReq::parse("^1.0.0")?,
+    );
+    assert!(shared.to_mod_json().library_files.is_empty());
+
+    Ok(())
+}
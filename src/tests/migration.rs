@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use color_eyre::Result;
+use serde_json::json;
+
+use qpm_cli::models::migration::migrate_package_config;
+
+#[test]
+fn newer_schema_rejected() {
+    let err = migrate_package_config(json!({
+        "version": "999.0.0",
+        "sharedDir": "shared",
+        "dependenciesDir": "extern",
+        "info": { "name": "Foo", "id": "foo", "version": "1.0.0" },
+        "dependencies": []
+    }))
+    .unwrap_err();
+
+    assert!(format!("{err:?}").contains("upgrade qpm"));
+}
+
+#[test]
+fn legacy_config_migrated() -> Result<()> {
+    let migration = migrate_package_config(json!({
+        "info": {
+            "name": "Foo",
+            "id": "foo",
+            "version": "1.0.0",
+            "additionalData": { "styles": [] }
+        },
+        "dependencies": [],
+        "additionalData": { "headersOnly": true }
+    }))?;
+
+    assert_eq!(migration.from, None);
+    assert_eq!(Path::new(&migration.config.shared_dir), Path::new("shared"));
+    assert_eq!(
+        migration.config.info.additional_data.headers_only,
+        Some(true)
+    );
+    Ok(())
+}
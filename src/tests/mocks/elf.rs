@@ -0,0 +1,118 @@
+//! Minimal ELF shared libraries for tests, only headers, `.dynstr` and `.dynamic`
+
+pub use object::elf::{EM_AARCH64, EM_X86_64};
+
+const HEADER_SIZE: usize = 64;
+const SECTION_HEADER_SIZE: usize = 64;
+
+const SHT_STRTAB: u32 = 3;
+const SHT_DYNAMIC: u32 = 6;
+const ET_DYN: u16 = 3;
+const DT_NULL: u64 = 0;
+const DT_NEEDED: u64 = 1;
+const DT_SONAME: u64 = 14;
+
+/// A shared library for `machine` with the given SONAME and `DT_NEEDED` entries
+pub fn shared_object(machine: u16, soname: Option<&str>, needed: &[&str]) -> Vec<u8> {
+    // string table of the dynamic section, starts with the empty string
+    let mut dynstr = vec![0u8];
+    let mut add_string = |s: &str| {
+        let offset = dynstr.len() as u64;
+        dynstr.extend_from_slice(s.as_bytes());
+        dynstr.push(0);
+        offset
+    };
+
+    let mut dynamic = vec![];
+    if let Some(soname) = soname {
+        dynamic.push((DT_SONAME, add_string(soname)));
+    }
+    for lib in needed {
+        dynamic.push((DT_NEEDED, add_string(lib)));
+    }
+    dynamic.push((DT_NULL, 0));
+
+    let shstrtab = b"\0.dynstr\0.dynamic\0.shstrtab\0";
+
+    let dynstr_offset = HEADER_SIZE;
+    let dynamic_offset = align(dynstr_offset + dynstr.len());
+    let dynamic_size = dynamic.len() * 16;
+    let shstrtab_offset = dynamic_offset + dynamic_size;
+    let section_headers_offset = align(shstrtab_offset + shstrtab.len());
+
+    let mut data = vec![];
+    // e_ident: 64 bit, little endian, current version
+    data.extend_from_slice(&[0x7f, b'E', b'L', b'F', 2, 1, 1, 0]);
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&ET_DYN.to_le_bytes());
+    data.extend_from_slice(&machine.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes()); // e_version
+    data.extend_from_slice(&0u64.to_le_bytes()); // e_entry
+    data.extend_from_slice(&0u64.to_le_bytes()); // e_phoff
+    data.extend_from_slice(&(section_headers_offset as u64).to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+    data.extend_from_slice(&(HEADER_SIZE as u16).to_le_bytes());
+    data.extend_from_slice(&56u16.to_le_bytes()); // e_phentsize
+    data.extend_from_slice(&0u16.to_le_bytes()); // e_phnum
+    data.extend_from_slice(&(SECTION_HEADER_SIZE as u16).to_le_bytes());
+    data.extend_from_slice(&4u16.to_le_bytes()); // e_shnum
+    data.extend_from_slice(&3u16.to_le_bytes()); // e_shstrndx
+
+    data.extend_from_slice(&dynstr);
+    data.resize(dynamic_offset, 0);
+    for (tag, value) in dynamic {
+        data.extend_from_slice(&tag.to_le_bytes());
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(shstrtab);
+    data.resize(section_headers_offset, 0);
+
+    // null section
+    data.extend_from_slice(&[0; SECTION_HEADER_SIZE]);
+    section_header(&mut data, 1, SHT_STRTAB, dynstr_offset, dynstr.len(), 0, 0);
+    section_header(
+        &mut data,
+        9,
+        SHT_DYNAMIC,
+        dynamic_offset,
+        dynamic_size,
+        1,
+        16,
+    );
+    section_header(
+        &mut data,
+        18,
+        SHT_STRTAB,
+        shstrtab_offset,
+        shstrtab.len(),
+        0,
+        0,
+    );
+
+    data
+}
+
+fn section_header(
+    data: &mut Vec<u8>,
+    name: u32,
+    kind: u32,
+    offset: usize,
+    size: usize,
+    link: u32,
+    entry_size: u64,
+) {
+    data.extend_from_slice(&name.to_le_bytes());
+    data.extend_from_slice(&kind.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes()); // sh_flags
+    data.extend_from_slice(&0u64.to_le_bytes()); // sh_addr
+    data.extend_from_slice(&(offset as u64).to_le_bytes());
+    data.extend_from_slice(&(size as u64).to_le_bytes());
+    data.extend_from_slice(&link.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes()); // sh_info
+    data.extend_from_slice(&8u64.to_le_bytes()); // sh_addralign
+    data.extend_from_slice(&entry_size.to_le_bytes());
+}
+
+fn align(offset: usize) -> usize {
+    offset.next_multiple_of(8)
+}
@@ -1 +1,3 @@
+pub mod elf;
 pub mod repo;
+pub mod server;
@@ -150,3 +150,17 @@ pub fn get_mock_repository() -> FileRepository {
             .collect(),
     }
 }
+
+/// Builds a repository holding every given artifact, several versions of a package allowed
+pub fn build_repository(
+    artifacts: impl IntoIterator<Item = SharedPackageConfig>,
+) -> FileRepository {
+    let mut repo = FileRepository::default();
+    for artifact in artifacts {
+        repo.artifacts
+            .entry(artifact.config.info.id.clone())
+            .or_default()
+            .insert(artifact.config.info.version.clone(), artifact);
+    }
+    repo
+}
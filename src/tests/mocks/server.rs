@@ -0,0 +1,210 @@
+//! In-process stand-in for qpackages.com and the file hosts packages link to.
+//!
+//! Serves the qpackages API (`/`, `/{id}`, `/{id}/{version}`) for the added packages and
+//! any other file registered with [`MockServer::serve`]. Point the code under test at it with
+//! `QPMRepository::new(server.url())`, or `QPM_QPACKAGES_URL` for a spawned qpm.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{BufRead, BufReader, Cursor, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+};
+
+use qpm_package::models::{backend::PackageVersion, dependency::SharedPackageConfig};
+use semver::Version;
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+#[derive(Default)]
+struct State {
+    packages: BTreeMap<String, BTreeMap<Version, SharedPackageConfig>>,
+    files: HashMap<String, Vec<u8>>,
+    /// Paths of every request, in order
+    requests: Vec<String>,
+}
+
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Starts serving on a free port of the loopback interface
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Unable to bind mock server");
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(State::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let state = state.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let Ok(stream) = stream else { continue };
+                    let state = state.clone();
+                    thread::spawn(move || {
+                        let _ = handle(stream, &state);
+                    });
+                }
+            })
+        };
+
+        Self {
+            addr,
+            state,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Base url, e.g. `http://127.0.0.1:12345`
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Url a file served at `path` is reachable under
+    pub fn file_url(&self, path: &str) -> String {
+        format!("{}/{}", self.url(), path.trim_start_matches('/'))
+    }
+
+    /// Serves `body` at `path`, taking precedence over the qpackages API
+    pub fn serve(&self, path: &str, body: impl Into<Vec<u8>>) {
+        self.state
+            .lock()
+            .unwrap()
+            .files
+            .insert(format!("/{}", path.trim_start_matches('/')), body.into());
+    }
+
+    /// Lists `package` in the qpackages API
+    pub fn add_package(&self, package: SharedPackageConfig) {
+        self.state
+            .lock()
+            .unwrap()
+            .packages
+            .entry(package.config.info.id.clone())
+            .or_default()
+            .insert(package.config.info.version.clone(), package);
+    }
+
+    /// Paths of every request received so far
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // wake up the accept loop
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Zips `files`, given as path and content
+pub fn zip_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (path, content) in files {
+        zip.start_file(*path, SimpleFileOptions::default())
+            .expect("Unable to add file to zip");
+        zip.write_all(content).expect("Unable to write zip");
+    }
+    zip.finish().expect("Unable to finish zip").into_inner()
+}
+
+fn handle(mut stream: TcpStream, state: &Mutex<State>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let target = request_line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or("/")
+        .to_string();
+
+    // headers, only the body length matters
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let path = target.split('?').next().unwrap_or("/").to_string();
+    let response = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(target);
+        respond(&state, &path)
+    };
+
+    let (status, content_type, body) = match response {
+        Some((content_type, body)) => ("200 OK", content_type, body),
+        None => ("404 Not Found", "text/plain", b"not found".to_vec()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()
+}
+
+fn respond(state: &State, path: &str) -> Option<(&'static str, Vec<u8>)> {
+    if let Some(file) = state.files.get(path) {
+        return Some(("application/octet-stream", file.clone()));
+    }
+
+    let json =
+        |value: serde_json::Value| Some(("application/json", value.to_string().into_bytes()));
+    let segments = path
+        .trim_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    match segments.as_slice() {
+        [] => json(serde_json::json!(state.packages.keys().collect::<Vec<_>>())),
+        [id] => {
+            let versions = state
+                .packages
+                .get(*id)?
+                .keys()
+                .rev()
+                .map(|version| PackageVersion {
+                    id: id.to_string(),
+                    version: version.clone(),
+                })
+                .collect::<Vec<_>>();
+            json(serde_json::to_value(versions).ok()?)
+        }
+        [id, version] => {
+            let version = Version::parse(version).ok()?;
+            let package = state.packages.get(*id)?.get(&version)?;
+            json(serde_json::to_value(package).ok()?)
+        }
+        _ => None,
+    }
+}
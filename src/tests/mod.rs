@@ -1,5 +1,19 @@
+pub mod abi;
+pub mod api;
+pub mod audit;
+pub mod cpp;
+pub mod daemon;
+pub mod fuzzy;
+pub mod license;
+pub mod lint;
+pub mod local;
+pub mod migration;
 pub mod mocks;
+pub mod prefetch;
+pub mod publish;
+pub mod qpackages;
+pub mod registry;
 pub mod resolve;
-
-#[cfg(feature = "network_test")]
-pub mod network;
+pub mod sbom;
+pub mod terminal;
+pub mod version;
@@ -0,0 +1,147 @@
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+};
+
+use color_eyre::Result;
+use pubgrub::Ranges;
+use qpm_package::models::{
+    backend::PackageVersion, dependency::SharedPackageConfig, package::PackageConfig,
+};
+use semver::{Version, VersionReq};
+
+use qpm_cli::{
+    repository::Repository,
+    resolver::{
+        prefetch::Prefetcher,
+        semver::{VersionWrapper, req_to_range},
+    },
+};
+
+use super::mocks::repo::build_artifact_nodeps;
+
+/// Repository whose version requests wait until the test releases them
+struct GatedRepository {
+    entered: Mutex<Sender<String>>,
+    release: Mutex<Receiver<()>>,
+    /// Version requests of this id panic instead
+    panicking: Option<&'static str>,
+    package_calls: AtomicUsize,
+}
+
+impl GatedRepository {
+    fn new(panicking: Option<&'static str>) -> (Self, Receiver<String>, Sender<()>) {
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel();
+        let repo = Self {
+            entered: Mutex::new(entered_tx),
+            release: Mutex::new(release_rx),
+            panicking,
+            package_calls: AtomicUsize::new(0),
+        };
+        (repo, entered_rx, release_tx)
+    }
+}
+
+impl Repository for GatedRepository {
+    fn get_package_names(&self) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+
+    fn get_package_versions(&self, id: &str) -> Result<Option<Vec<PackageVersion>>> {
+        self.entered.lock().unwrap().send(id.to_string())?;
+        self.release.lock().unwrap().recv()?;
+        if self.panicking == Some(id) {
+            panic!("{id} is broken");
+        }
+
+        Ok(Some(vec![PackageVersion {
+            id: id.to_string(),
+            version: Version::new(1, 0, 0),
+        }]))
+    }
+
+    fn get_package(&self, id: &str, version: &Version) -> Result<Option<SharedPackageConfig>> {
+        self.package_calls.fetch_add(1, Ordering::SeqCst);
+        Ok(Some(build_artifact_nodeps(id, version.clone())))
+    }
+
+    fn add_to_db_cache(&mut self, _config: SharedPackageConfig, _permanent: bool) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_online(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        "gated".to_string()
+    }
+
+    fn download_to_cache(&mut self, _config: &PackageConfig) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn write_repo(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn dependency(id: &str) -> (String, Ranges<VersionWrapper>) {
+    (id.to_string(), req_to_range(VersionReq::STAR))
+}
+
+/// The resolver finishing while versions are fetched stops the prefetch of the package
+#[test]
+fn finish_skips_remaining_requests() -> Result<()> {
+    let (repo, entered, release) = GatedRepository::new(None);
+    let prefetcher = Prefetcher::new(&repo, "root");
+
+    thread::scope(|scope| -> Result<()> {
+        let worker = scope.spawn(|| prefetcher.run_worker());
+        prefetcher.enqueue([dependency("foo")]);
+
+        assert_eq!(entered.recv()?, "foo");
+        prefetcher.finish();
+        release.send(())?;
+
+        worker.join().expect("worker panicked");
+        Ok(())
+    })?;
+
+    assert_eq!(repo.package_calls.load(Ordering::SeqCst), 0);
+    Ok(())
+}
+
+/// A panicking repository does not stop the worker
+#[test]
+fn worker_survives_panics() -> Result<()> {
+    let (repo, entered, release) = GatedRepository::new(Some("broken"));
+    let prefetcher = Prefetcher::new(&repo, "root");
+
+    thread::scope(|scope| -> Result<()> {
+        let worker = scope.spawn(|| prefetcher.run_worker());
+        prefetcher.enqueue([dependency("broken"), dependency("foo")]);
+
+        assert_eq!(entered.recv()?, "broken");
+        release.send(())?;
+        assert_eq!(entered.recv()?, "foo");
+        release.send(())?;
+
+        // foo was prefetched completely before the worker stops
+        while repo.package_calls.load(Ordering::SeqCst) == 0 {
+            thread::yield_now();
+        }
+        prefetcher.finish();
+
+        worker.join().expect("worker panicked");
+        Ok(())
+    })?;
+
+    assert_eq!(repo.package_calls.load(Ordering::SeqCst), 1);
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use color_eyre::Result;
+use qpm_package::{
+    extensions::package_metadata::PackageMetadataExtensions,
+    models::dependency::SharedPackageConfig,
+};
+use semver::Version;
+
+use qpm_cli::commands::publish::check::preflight;
+
+use super::mocks::{
+    elf::{EM_AARCH64, EM_X86_64, shared_object},
+    repo::build_artifact_nodeps,
+    server::{MockServer, zip_of},
+};
+
+/// Serves the sources of a package whose release binary is linked to `binary_path`
+fn serve_package(server: &MockServer, id: &str, binary_path: &str) -> SharedPackageConfig {
+    let mut package = build_artifact_nodeps(id, Version::new(1, 0, 0));
+    package.config.info.url = Some(server.file_url(&format!("{id}/1.0.0.zip")));
+    package.config.info.additional_data.so_link = Some(server.file_url(binary_path));
+
+    let package_json = serde_json::to_vec_pretty(&package).unwrap();
+    server.serve(
+        &format!("{id}/1.0.0.zip"),
+        zip_of(&[
+            ("qpm.shared.json", package_json.as_slice()),
+            ("shared/header.hpp", b"#pragma once\n".as_slice()),
+        ]),
+    );
+    package
+}
+
+fn so_name(package: &SharedPackageConfig) -> String {
+    let so_name = package.config.info.get_so_name2();
+    so_name.file_name().unwrap().to_string_lossy().to_string()
+}
+
+#[test]
+fn preflight_accepts_package() -> Result<()> {
+    let server = MockServer::start();
+    let package = serve_package(&server, "preflight-ok", "bin/lib.so");
+    server.serve(
+        "bin/lib.so",
+        shared_object(EM_AARCH64, Some(&so_name(&package)), &[]),
+    );
+
+    preflight(&package.config)
+}
+
+#[test]
+fn preflight_rejects_bad_link() {
+    let server = MockServer::start();
+    let package = serve_package(&server, "preflight-missing", "bin/missing.so");
+
+    let err = preflight(&package.config).unwrap_err();
+    assert!(format!("{err:?}").contains("404"));
+}
+
+#[test]
+fn preflight_rejects_other_architecture() {
+    let server = MockServer::start();
+    let package = serve_package(&server, "preflight-x86", "bin/lib.so");
+    server.serve(
+        "bin/lib.so",
+        shared_object(EM_X86_64, Some(&so_name(&package)), &[]),
+    );
+
+    let err = preflight(&package.config).unwrap_err();
+    assert!(format!("{err:?}").contains("X86_64"));
+}
+
+#[test]
+fn preflight_rejects_other_lock_file() {
+    let server = MockServer::start();
+    let mut package = serve_package(&server, "preflight-stale", "bin/lib.so");
+    server.serve(
+        "bin/lib.so",
+        shared_object(EM_AARCH64, Some(&so_name(&package)), &[]),
+    );
+
+    // the published branch still has the lock file of the previous release
+    package.config.info.version = Version::new(1, 1, 0);
+    let err = preflight(&package.config).unwrap_err();
+    assert!(format!("{err:?}").contains("expected"));
+}
+
+#[test]
+fn preflight_rejects_invalid_lock_file() {
+    let server = MockServer::start();
+    let package = serve_package(&server, "preflight-invalid", "bin/lib.so");
+    server.serve(
+        "preflight-invalid/1.0.0.zip",
+        zip_of(&[
+            ("qpm.shared.json", b"{ \"config\": 1 }".as_slice()),
+            ("shared/header.hpp", b"#pragma once\n".as_slice()),
+        ]),
+    );
+
+    let err = preflight(&package.config).unwrap_err();
+    assert!(format!("{err:?}").contains("invalid lock file"));
+}
+
+#[test]
+fn preflight_checks_every_abi() {
+    let server = MockServer::start();
+    let mut package = serve_package(&server, "preflight-abis", "bin/lib.so");
+    server.serve(
+        "bin/lib.so",
+        shared_object(EM_AARCH64, Some(&so_name(&package)), &[]),
+    );
+    // the emulator binary is an arm64 build by mistake
+    server.serve(
+        "bin/emulator.so",
+        shared_object(EM_AARCH64, Some(&so_name(&package)), &[]),
+    );
+
+    let package_json = serde_json::to_vec_pretty(&serde_json::json!({
+        "abiLinks": { "x86_64": { "soLink": server.file_url("bin/emulator.so") } },
+    }))
+    .unwrap();
+    let shared_json = serde_json::to_vec_pretty(&package).unwrap();
+    package.config.info.url = Some(server.file_url("preflight-abis/1.0.0-abis.zip"));
+    server.serve(
+        "preflight-abis/1.0.0-abis.zip",
+        zip_of(&[
+            ("qpm.json", package_json.as_slice()),
+            ("qpm.shared.json", shared_json.as_slice()),
+            ("shared/header.hpp", b"#pragma once\n".as_slice()),
+        ]),
+    );
+
+    let err = preflight(&package.config).unwrap_err();
+    assert!(format!("{err:?}").contains("x86_64"));
+}
@@ -0,0 +1,195 @@
+use std::fs;
+
+use color_eyre::Result;
+use itertools::Itertools;
+use qpm_package::extensions::package_metadata::PackageMetadataExtensions;
+use semver::{Version, VersionReq};
+
+use qpm_cli::{
+    network::agent::download_file,
+    repository::{Repository, local::FileRepository, qpackages::QPMRepository},
+    resolver::dependency,
+};
+
+use super::mocks::{
+    elf::{EM_AARCH64, shared_object},
+    repo::{build_artifact_and_depend, build_artifact_nodeps},
+    server::{MockServer, zip_of},
+};
+
+fn mock_qpackages() -> MockServer {
+    let server = MockServer::start();
+    let hook_old = build_artifact_nodeps("beatsaber-hook", Version::new(5, 0, 0));
+    let hook = build_artifact_nodeps("beatsaber-hook", Version::new(5, 1, 9));
+    let mod_package = build_artifact_and_depend(
+        "my-mod",
+        Version::new(1, 0, 0),
+        &hook,
+        VersionReq::parse("^5.0.0").unwrap(),
+    );
+
+    server.add_package(hook_old);
+    server.add_package(hook);
+    server.add_package(mod_package);
+    server
+}
+
+#[test]
+fn mock_qpackages_api() -> Result<()> {
+    let server = mock_qpackages();
+    let repo = QPMRepository::new(server.url());
+
+    assert_eq!(
+        repo.get_package_names()?.into_iter().sorted().collect_vec(),
+        ["beatsaber-hook", "my-mod"]
+    );
+    assert_eq!(
+        repo.get_package_versions("beatsaber-hook")?
+            .unwrap()
+            .into_iter()
+            .map(|v| v.version)
+            .collect_vec(),
+        [Version::new(5, 1, 9), Version::new(5, 0, 0)]
+    );
+    assert_eq!(repo.get_package_versions("missing")?, None);
+    assert_eq!(repo.get_package("my-mod", &Version::new(2, 0, 0))?, None);
+
+    let package = repo.get_package("my-mod", &Version::new(1, 0, 0))?.unwrap();
+    assert_eq!(package.config.info.id, "my-mod");
+    assert_eq!(repo.name(), server.url());
+
+    assert!(
+        server
+            .requests()
+            .contains(&"/beatsaber-hook?limit=0".to_string())
+    );
+    Ok(())
+}
+
+#[test]
+fn mock_qpackages_resolve() -> Result<()> {
+    let server = mock_qpackages();
+    let repo = QPMRepository::new(server.url());
+
+    let root = repo.get_package("my-mod", &Version::new(1, 0, 0))?.unwrap();
+    let resolved = dependency::resolve(&root.config, &repo)?.collect_vec();
+
+    assert_eq!(resolved.len(), 1);
+    assert_eq!(resolved[0].config.info.version, Version::new(5, 1, 9));
+    Ok(())
+}
+
+#[test]
+fn mock_file_download() -> Result<()> {
+    let server = MockServer::start();
+    let zip = zip_of(&[("shared/hook.hpp", b"#pragma once\n".as_slice())]);
+    server.serve("hook/src.zip", zip.clone());
+
+    let mut bytes = vec![];
+    download_file(&server.file_url("hook/src.zip"), &mut bytes, |_, _| {})?;
+    assert_eq!(bytes, zip);
+
+    let missing = download_file(&server.file_url("hook/missing.so"), &mut vec![], |_, _| {});
+    assert!(missing.is_err());
+    Ok(())
+}
+
+#[test]
+fn mock_binary_download() -> Result<()> {
+    let server = MockServer::start();
+    let binary = shared_object(EM_AARCH64, Some("libhook.so"), &["liblog.so"]);
+    server.serve("hook/libhook.so", binary.clone());
+
+    let mut hook = build_artifact_nodeps("beatsaber-hook", Version::new(5, 1, 9));
+    hook.config.info.additional_data.so_link = Some(server.file_url("hook/libhook.so"));
+    server.add_package(hook);
+
+    let repo = QPMRepository::new(server.url());
+    let package = repo
+        .get_package("beatsaber-hook", &Version::new(5, 1, 9))?
+        .unwrap();
+    let link = package.config.info.additional_data.so_link.unwrap();
+
+    let mut bytes = vec![];
+    download_file(&link, &mut bytes, |_, _| {})?;
+    assert_eq!(bytes, binary);
+    Ok(())
+}
+
+#[test]
+fn mock_resolve_conflict() {
+    let server = mock_qpackages();
+    let repo = QPMRepository::new(server.url());
+
+    let hook = build_artifact_nodeps("beatsaber-hook", Version::new(5, 1, 9));
+    let mut root = build_artifact_and_depend(
+        "root",
+        Version::new(1, 0, 0),
+        &hook,
+        VersionReq::parse(">5.0.0").unwrap(),
+    );
+    let mut conflicting = root.config.dependencies[0].clone();
+    conflicting.version_range = VersionReq::parse("<5.0.0").unwrap();
+    root.config.dependencies.push(conflicting);
+
+    assert!(dependency::resolve(&root.config, &repo).is_err());
+}
+
+#[test]
+fn mock_restore_redownloads_missing_binary() -> Result<()> {
+    let server = MockServer::start();
+    let workspace = tempfile::tempdir()?;
+
+    // an id no real package uses, the cache is shared with the user
+    let id = "qpm-mock-redownload";
+    let version = Version::new(1, 0, 0);
+    let cache_path = FileRepository::get_package_cache_path(id, &version);
+    if cache_path.exists() {
+        fs::remove_dir_all(&cache_path)?;
+    }
+
+    let mut dep = build_artifact_nodeps(id, version.clone());
+    dep.config.info.url = Some(server.file_url(&format!("{id}/src.zip")));
+    dep.config.info.additional_data.so_link = Some(server.file_url(&format!("{id}/lib.so")));
+    let so_name = dep.config.info.get_so_name2();
+    let so_name = so_name.file_name().unwrap().to_string_lossy().to_string();
+    let dep_json = serde_json::to_vec_pretty(&dep)?;
+    server.serve(
+        &format!("{id}/src.zip"),
+        zip_of(&[
+            ("qpm.shared.json", dep_json.as_slice()),
+            ("shared/header.hpp", b"#pragma once\n".as_slice()),
+        ]),
+    );
+    server.serve(
+        &format!("{id}/lib.so"),
+        shared_object(EM_AARCH64, Some(&so_name), &[]),
+    );
+    server.add_package(dep.clone());
+
+    let mut root = build_artifact_and_depend(
+        "root",
+        Version::new(1, 0, 0),
+        &dep,
+        VersionReq::parse("^1.0.0")?,
+    );
+    root.config.dependencies_dir = workspace.path().join("extern");
+
+    let restore = || -> Result<_> {
+        let mut repo = QPMRepository::new(server.url());
+        let resolved = dependency::resolve(&root.config, &repo)?.collect_vec();
+        dependency::restore(workspace.path(), &root, &resolved, &mut repo)?;
+        Ok(FileRepository::collect_files_of_package(&dep.config)?.release_binary)
+    };
+
+    let result = (|| -> Result<()> {
+        let binary = restore()?.expect("binary was downloaded");
+        fs::remove_file(&binary)?;
+
+        assert_eq!(restore()?, Some(binary));
+        Ok(())
+    })();
+
+    fs::remove_dir_all(FileRepository::get_package_versions_cache_path(id))?;
+    result
+}
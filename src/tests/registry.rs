@@ -0,0 +1,159 @@
+use std::{fs, path::Path, process::Command};
+
+use color_eyre::Result;
+use itertools::Itertools;
+use semver::{Version, VersionReq};
+
+use qpm_cli::{
+    models::config::RegistryConfig,
+    repository::{
+        Repository,
+        git_index::{GitIndexRepository, YANKED_FILE},
+        multi::MultiDependencyRepository,
+    },
+    resolver::dependency,
+};
+
+use super::mocks::repo::{build_artifact_and_depend, build_artifact_nodeps, build_repository};
+
+fn git(args: &[&str], cwd: &Path) {
+    let status = Command::new("git")
+        .args(["-c", "user.name=qpm", "-c", "user.email=qpm@example.com"])
+        .args(args)
+        .current_dir(cwd)
+        .status()
+        .expect("git is installed");
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// A git index with foo 1.0.0 and a yanked foo 1.1.0
+fn yanked_index(origin: &Path) -> Result<()> {
+    let package_dir = origin.join("foo");
+    fs::create_dir_all(&package_dir)?;
+    for version in [Version::new(1, 0, 0), Version::new(1, 1, 0)] {
+        fs::write(
+            package_dir.join(format!("{version}.json")),
+            serde_json::to_vec_pretty(&build_artifact_nodeps("foo", version))?,
+        )?;
+    }
+    fs::write(package_dir.join(YANKED_FILE), r#"["1.1.0"]"#)?;
+
+    git(&["init", "--quiet"], origin);
+    git(&["add", "-A"], origin);
+    git(&["commit", "--quiet", "-m", "Index"], origin);
+    Ok(())
+}
+
+fn index_repository(origin: &Path, clone: &Path) -> GitIndexRepository {
+    GitIndexRepository::new(
+        "test".into(),
+        origin.to_string_lossy().to_string(),
+        None,
+        clone.to_path_buf(),
+    )
+}
+
+#[test]
+fn git_index_skips_yanked_versions() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let origin = temp.path().join("origin");
+    yanked_index(&origin)?;
+
+    let repo = index_repository(&origin, &temp.path().join("index"));
+    assert_eq!(repo.get_package_names()?, vec!["foo".to_string()]);
+
+    let versions = repo
+        .get_package_versions("foo")?
+        .unwrap()
+        .into_iter()
+        .map(|v| v.version)
+        .collect_vec();
+    assert_eq!(versions, vec![Version::new(1, 0, 0)]);
+
+    // lock files pinning the yanked release still restore
+    let yanked = Version::new(1, 1, 0);
+    assert!(repo.get_package("foo", &yanked)?.is_some());
+    assert!(repo.is_yanked("foo", &yanked)?);
+    assert!(!repo.is_yanked("foo", &Version::new(1, 0, 0))?);
+    Ok(())
+}
+
+#[test]
+fn resolve_skips_yanked_versions() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let origin = temp.path().join("origin");
+    yanked_index(&origin)?;
+
+    // the yanked release was cached before it was yanked
+    let foo = build_artifact_nodeps("foo", Version::new(1, 1, 0));
+    let root = build_artifact_and_depend(
+        "root",
+        Version::new(1, 0, 0),
+        &foo,
+        VersionReq::parse("^1.0.0")?,
+    );
+    let repo = MultiDependencyRepository::new(vec![
+        Box::new(build_repository([foo])),
+        Box::new(index_repository(&origin, &temp.path().join("index"))),
+    ]);
+
+    let versions = repo.get_package_versions("foo")?.unwrap();
+    assert!(versions.iter().all(|v| v.version != Version::new(1, 1, 0)));
+
+    let resolved = dependency::resolve(&root.config, &repo)?.collect_vec();
+    assert_eq!(resolved.len(), 1);
+    assert_eq!(resolved[0].config.info.version, Version::new(1, 0, 0));
+    Ok(())
+}
+
+#[test]
+fn invalid_yanked_file_fails_resolve() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let origin = temp.path().join("origin");
+    yanked_index(&origin)?;
+    fs::write(origin.join("foo").join(YANKED_FILE), "1.1.0")?;
+    git(&["commit", "--quiet", "-am", "Break yanked"], &origin);
+
+    let foo = build_artifact_nodeps("foo", Version::new(1, 1, 0));
+    let repo = MultiDependencyRepository::new(vec![
+        Box::new(build_repository([foo])),
+        Box::new(index_repository(&origin, &temp.path().join("index"))),
+    ]);
+
+    // reported instead of panicking inside the resolver
+    assert!(repo.get_package_versions("foo").is_err());
+    assert!(repo.is_yanked("foo", &Version::new(1, 1, 0)).is_err());
+    Ok(())
+}
+
+#[test]
+fn failed_index_clone_is_empty() -> Result<()> {
+    let temp = tempfile::tempdir()?;
+    let clone = temp.path().join("index");
+
+    let repo = index_repository(&temp.path().join("missing"), &clone);
+    assert!(repo.get_package_names()?.is_empty());
+    assert!(repo.get_package_versions("foo")?.is_none());
+    // the partial clone is removed
+    assert!(!clone.exists());
+    assert!(!clone.with_extension("tmp").exists());
+    Ok(())
+}
+
+#[test]
+fn registry_display() {
+    let http = RegistryConfig::Http {
+        url: "https://qpackages.com".to_string(),
+    };
+    assert_eq!(http.to_string(), "http https://qpackages.com");
+
+    let git = RegistryConfig::Git {
+        url: "https://github.com/foo/index.git".to_string(),
+        branch: Some("main".to_string()),
+    };
+    assert_eq!(
+        git.to_string(),
+        "git https://github.com/foo/index.git (main)"
+    );
+    assert_eq!(RegistryConfig::Local.to_string(), "local");
+}
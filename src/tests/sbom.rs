@@ -0,0 +1,89 @@
+use semver::Version;
+use serde_json::json;
+
+use qpm_cli::models::sbom::{Sbom, SbomComponent};
+
+fn component(id: &str, bundled: bool, depends_on: &[&str]) -> SbomComponent {
+    SbomComponent {
+        id: id.to_string(),
+        version: Version::new(1, 0, 0),
+        url: Some(format!("https://github.com/example/{id}")),
+        branch: None,
+        license: Some("MIT".to_string()),
+        binary_links: vec![("soLink", format!("https://example.com/lib{id}.so"))],
+        sha256: bundled.then(|| "ab".repeat(32)),
+        bundled,
+        depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+fn sbom() -> Sbom {
+    let mut root = component("my-mod", false, &["hook", "headers"]);
+    root.binary_links.clear();
+    Sbom {
+        root,
+        components: vec![
+            component("hook", true, &[]),
+            component("headers", false, &[]),
+        ],
+        timestamp: "2026-10-18T00:00:00Z".to_string(),
+    }
+}
+
+#[test]
+fn cyclonedx_components() {
+    let bom = sbom().to_cyclonedx();
+
+    assert_eq!(bom["bomFormat"], "CycloneDX");
+    assert_eq!(bom["metadata"]["component"]["bom-ref"], "my-mod@1.0.0");
+
+    let hook = &bom["components"][0];
+    assert_eq!(hook["hashes"][0]["content"], "ab".repeat(32));
+    assert_eq!(
+        hook["externalReferences"][1],
+        json!({ "type": "distribution", "url": "https://example.com/libhook.so", "comment": "soLink" })
+    );
+    assert_eq!(hook["properties"][0]["value"], "true");
+    assert_eq!(
+        bom["dependencies"][0]["dependsOn"],
+        json!(["hook@1.0.0", "headers@1.0.0"])
+    );
+}
+
+#[test]
+fn spdx_relationships() {
+    let doc = sbom().to_spdx();
+
+    assert_eq!(doc["spdxVersion"], "SPDX-2.3");
+    assert_eq!(
+        doc["packages"][1]["downloadLocation"],
+        "git+https://github.com/example/hook"
+    );
+    assert_eq!(doc["packages"][1]["checksums"][0]["algorithm"], "SHA256");
+    assert_eq!(doc["packages"][1]["licenseDeclared"], "MIT");
+
+    let relationships = doc["relationships"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["spdxElementId"].as_str().unwrap(),
+                r["relationshipType"].as_str().unwrap(),
+                r["relatedSpdxElement"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        relationships,
+        [
+            ("SPDXRef-DOCUMENT", "DESCRIBES", "SPDXRef-Package-my-mod"),
+            ("SPDXRef-Package-my-mod", "CONTAINS", "SPDXRef-Package-hook"),
+            (
+                "SPDXRef-Package-my-mod",
+                "DEPENDS_ON",
+                "SPDXRef-Package-headers"
+            ),
+        ]
+    );
+}
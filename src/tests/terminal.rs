@@ -0,0 +1,59 @@
+use std::sync::{Arc, Mutex};
+
+use log::{Level, LevelFilter};
+use owo_colors::OwoColorize;
+
+use qpm_cli::terminal::{
+    colors::strip_colors,
+    logging::{self, is_qpm_target, verbosity_level},
+};
+
+#[test]
+fn strip_colors_removes_escapes() {
+    let colored = format!("{} {}", "id".blue(), "1.0.0".bright_green().bold());
+    assert_ne!(colored, "id 1.0.0");
+    assert_eq!(strip_colors(&colored), "id 1.0.0");
+    assert_eq!(strip_colors("plain"), "plain");
+}
+
+#[test]
+fn verbosity_flags() {
+    assert_eq!(verbosity_level(false, 0), LevelFilter::Info);
+    assert_eq!(verbosity_level(false, 1), LevelFilter::Debug);
+    assert_eq!(verbosity_level(false, 3), LevelFilter::Trace);
+    assert_eq!(verbosity_level(true, 0), LevelFilter::Warn);
+}
+
+#[test]
+fn qpm_targets() {
+    assert!(is_qpm_target("qpm"));
+    assert!(is_qpm_target("qpm_cli"));
+    assert!(is_qpm_target("qpm_cli::resolver::dependency"));
+    assert!(is_qpm_target("qpm::commands::restore"));
+    assert!(!is_qpm_target("qpm_package::models::package"));
+    assert!(!is_qpm_target("qpm_qmod"));
+    assert!(!is_qpm_target("qpmfoo"));
+}
+
+/// The daemon reports progress to its clients even when started with -q
+#[test]
+fn listener_ignores_console_level() -> color_eyre::Result<()> {
+    logging::init(verbosity_level(true, 0), None)?;
+
+    let received = Arc::new(Mutex::new(vec![]));
+    let sink = received.clone();
+    logging::set_listener(Some(Box::new(move |level, message| {
+        sink.lock().unwrap().push((level, message.to_string()));
+    })));
+
+    log::info!(target: "qpm", "Pulling {}", "foo".blue());
+    log::debug!(target: "qpm", "not progress");
+    logging::set_listener(None);
+    log::info!(target: "qpm", "after the listener was removed");
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        [(Level::Info, "Pulling foo".to_string())]
+    );
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use semver::Version;
+
+use qpm_cli::{
+    commands::package::bump::BumpTarget,
+    utils::version::{VersionBump, next_prerelease},
+};
+
+fn v(version: &str) -> Version {
+    Version::parse(version).unwrap()
+}
+
+#[test]
+fn required_version_bump() {
+    assert_eq!(
+        VersionBump::required(&v("1.2.0"), true, true),
+        VersionBump::Major
+    );
+    assert_eq!(
+        VersionBump::required(&v("1.2.0"), false, true),
+        VersionBump::Minor
+    );
+    assert_eq!(
+        VersionBump::required(&v("1.2.0"), false, false),
+        VersionBump::Patch
+    );
+    assert_eq!(
+        VersionBump::required(&v("0.3.0"), true, false),
+        VersionBump::Minor
+    );
+
+    assert_eq!(
+        VersionBump::between(&v("1.2.0"), &v("1.2.1")),
+        Some(VersionBump::Patch)
+    );
+    assert_eq!(
+        VersionBump::between(&v("1.2.0"), &v("1.3.0")),
+        Some(VersionBump::Minor)
+    );
+    assert_eq!(
+        VersionBump::between(&v("2.0.0-beta.1"), &v("2.0.0")),
+        Some(VersionBump::Major)
+    );
+    assert_eq!(VersionBump::between(&v("1.2.0"), &v("1.2.0")), None);
+
+    assert_eq!(VersionBump::Major.apply(&v("1.2.3-rc.1")), v("2.0.0"));
+    assert_eq!(VersionBump::Minor.apply(&v("1.2.3")), v("1.3.0"));
+}
+
+/// `^0.0.3` only matches 0.0.3, so any 0.0.x release already breaks dependents
+#[test]
+fn unstable_patch_bump_is_breaking() {
+    assert_eq!(
+        VersionBump::required(&v("0.0.3"), true, true),
+        VersionBump::Patch
+    );
+    assert_eq!(
+        VersionBump::required(&v("0.0.3"), false, true),
+        VersionBump::Patch
+    );
+    assert_eq!(
+        VersionBump::between(&v("0.0.3"), &v("0.0.4")),
+        Some(VersionBump::Patch)
+    );
+    assert_eq!(VersionBump::Patch.apply(&v("0.0.3")), v("0.0.4"));
+
+    // from 0.1.0 on, the minor version is the breaking one again
+    assert_eq!(
+        VersionBump::required(&v("0.1.0"), true, false),
+        VersionBump::Minor
+    );
+}
+
+#[test]
+fn release_prerelease_bump() {
+    assert_eq!(VersionBump::Patch.apply(&v("1.2.3-rc.1")), v("1.2.3"));
+    assert_eq!(VersionBump::Minor.apply(&v("1.3.0-beta.2")), v("1.3.0"));
+    assert_eq!(VersionBump::Minor.apply(&v("1.2.3-rc.1")), v("1.3.0"));
+    assert_eq!(VersionBump::Major.apply(&v("2.0.0-rc.1")), v("2.0.0"));
+    assert_eq!(VersionBump::Patch.apply(&v("1.2.3+build.5")), v("1.2.4"));
+}
+
+#[test]
+fn prerelease_bump() -> color_eyre::Result<()> {
+    assert_eq!(next_prerelease(&v("1.2.0"), None)?, v("1.2.1-0"));
+    assert_eq!(
+        next_prerelease(&v("1.2.0"), Some("beta"))?,
+        v("1.2.1-beta.0")
+    );
+    assert_eq!(
+        next_prerelease(&v("1.2.1-beta.0"), Some("beta"))?,
+        v("1.2.1-beta.1")
+    );
+    assert_eq!(
+        next_prerelease(&v("1.2.1-beta.4"), None)?,
+        v("1.2.1-beta.5")
+    );
+    assert_eq!(
+        next_prerelease(&v("1.2.1-beta.4"), Some("rc"))?,
+        v("1.2.1-rc.0")
+    );
+    assert_eq!(next_prerelease(&v("1.2.1-3"), None)?, v("1.2.1-4"));
+    assert_eq!(next_prerelease(&v("1.2.1-beta"), None)?, v("1.2.1-beta.0"));
+
+    Ok(())
+}
+
+#[test]
+fn bump_targets() -> color_eyre::Result<()> {
+    let parse = |s: &str| s.parse::<BumpTarget>().unwrap();
+    assert_eq!(parse("major"), BumpTarget::Major);
+    assert_eq!(parse("prerelease"), BumpTarget::Prerelease);
+    assert_eq!(parse("2.0.0-rc.1"), BumpTarget::Version(v("2.0.0-rc.1")));
+    assert!("huge".parse::<BumpTarget>().is_err());
+
+    assert_eq!(BumpTarget::Major.apply(&v("1.2.3"), None)?, v("2.0.0"));
+    assert_eq!(BumpTarget::Minor.apply(&v("1.2.3"), None)?, v("1.3.0"));
+    assert_eq!(BumpTarget::Patch.apply(&v("1.2.3"), None)?, v("1.2.4"));
+    assert_eq!(
+        BumpTarget::Prerelease.apply(&v("1.2.3"), Some("beta"))?,
+        v("1.2.4-beta.0")
+    );
+    assert_eq!(
+        BumpTarget::Version(v("1.5.0")).apply(&v("1.2.3"), None)?,
+        v("1.5.0")
+    );
+
+    // never goes back
+    assert!(
+        BumpTarget::Version(v("1.2.3"))
+            .apply(&v("1.2.3"), None)
+            .is_err()
+    );
+    assert!(
+        BumpTarget::Version(v("1.0.0"))
+            .apply(&v("1.2.3"), None)
+            .is_err()
+    );
+    Ok(())
+}
@@ -1,9 +1,10 @@
 use std::{collections::HashMap, env, fs, io::Cursor, path::PathBuf};
 
 use bytes::{BufMut, BytesMut};
-use color_eyre::Result;
+use color_eyre::{Result, eyre::bail};
+use itertools::Itertools;
 use owo_colors::OwoColorize;
-use semver::{BuildMetadata, Prerelease, Version};
+use semver::{BuildMetadata, Prerelease, Version, VersionReq};
 
 use zip::ZipArchive;
 
@@ -75,6 +76,27 @@ pub fn get_ndk_str_versions_str(
         .collect()
 }
 
+/// Highest NDK in the manifest that satisfies `fuzzy_version_range`
+pub fn range_match_ndk<'a>(
+    manifest: &'a AndroidRepositoryManifest,
+    fuzzy_version_range: &VersionReq,
+) -> Result<(String, &'a RemotePackage)> {
+    // find version closest to specified
+    let ndks = get_ndk_str_versions(manifest);
+    let ndks_versions = ndks.keys().sorted().rev().collect_vec();
+    let matching_version_opt = ndks_versions
+        .iter()
+        .find(|probe| fuzzy_version_range.matches(probe));
+
+    match matching_version_opt {
+        Some(matching_version) => Ok((
+            matching_version.to_string(),
+            ndks.get(matching_version).unwrap(),
+        )),
+        None => bail!("Could not find any ndk version matching requirement {fuzzy_version_range}"),
+    }
+}
+
 ///
 /// Gets the archive matching the triplet for the current OS
 ///
@@ -106,7 +128,7 @@ pub fn download_ndk_version(ndk: &RemotePackage, show_progress: bool) -> Result<
 
     let archive_url = format!("{ANDROID_DL_URL}/{}", archive.complete.url);
 
-    println!(
+    log::info!(
         "Downloading {} from {}, this may take some time",
         get_ndk_version(ndk).blue(),
         &archive_url.yellow()
@@ -127,7 +149,7 @@ pub fn download_ndk_version(ndk: &RemotePackage, show_progress: bool) -> Result<
             download_file(&archive_url, &mut bytes, |_, _| {})?;
         }
     }
-    println!("Extracting ndk");
+    log::info!("Extracting ndk");
     let buffer = Cursor::new(bytes.into_inner());
 
     // Extract to tmp folde
@@ -136,7 +158,7 @@ pub fn download_ndk_version(ndk: &RemotePackage, show_progress: bool) -> Result<
 
     archive.extract(&dir)?;
 
-    println!(
+    log::info!(
         "Downloaded {} to {}",
         get_ndk_version(ndk).green(),
         extract_path.to_str().unwrap().file_path_color()
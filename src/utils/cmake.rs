@@ -10,7 +10,7 @@ use qpm_package::{
     models::dependency::SharedPackageConfig,
 };
 
-use crate::repository::Repository;
+use crate::{models::abi::Abi, repository::Repository};
 use std::fmt::Write as OtherWrite;
 
 const EXTERN_CMAKE_FILE: &str = "extern.cmake";
@@ -24,11 +24,30 @@ macro_rules! concatln {
     }
 }
 
-pub fn write_cmake(shared_package: &SharedPackageConfig, repo: &impl Repository) -> Result<()> {
+pub fn write_cmake(
+    workspace: &Path,
+    shared_package: &SharedPackageConfig,
+    repo: &impl Repository,
+) -> Result<()> {
+    for (path, content) in cmake_files(workspace, shared_package, repo)? {
+        let mut file = File::create(&path).with_context(|| format!("Unable to create {path:?}"))?;
+        file.write_all(content.as_bytes())
+            .with_context(|| format!("Failed to write out {path:?}"))?;
+    }
+
+    Ok(())
+}
+
+/// The cmake files [`write_cmake`] writes in `workspace`, with their contents
+pub fn cmake_files(
+    workspace: &Path,
+    shared_package: &SharedPackageConfig,
+    repo: &impl Repository,
+) -> Result<Vec<(PathBuf, String)>> {
     let cmake_opt = shared_package.config.info.additional_data.cmake;
 
-    if cmake_opt.is_none() && Path::new("./CMakeLists.txt").exists() {
-        eprintln!(
+    if cmake_opt.is_none() && workspace.join("CMakeLists.txt").exists() {
+        log::warn!(
             "qpm.json::info::additional_data::cmake is undefined in a CMake project, consider setting it to true"
         );
     }
@@ -36,18 +55,40 @@ pub fn write_cmake(shared_package: &SharedPackageConfig, repo: &impl Repository)
     // default to true
     let cmake = cmake_opt.unwrap_or(true);
     if !cmake {
-        return Ok(());
+        return Ok(vec![]);
     }
-    write_extern_cmake(shared_package, repo)?;
-    write_define_cmake(shared_package)?;
 
-    Ok(())
+    Ok(vec![
+        (
+            workspace.join(EXTERN_CMAKE_FILE),
+            make_extern_cmake_string(shared_package, repo)?,
+        ),
+        (
+            workspace.join(QPM_CMAKE_FILE),
+            make_defines_string(shared_package)?,
+        ),
+    ])
 }
 
-pub fn write_extern_cmake(dep: &SharedPackageConfig, repo: &impl Repository) -> Result<()> {
-    let path = Path::new("./").join(EXTERN_CMAKE_FILE);
+pub fn write_extern_cmake(
+    workspace: &Path,
+    dep: &SharedPackageConfig,
+    repo: &impl Repository,
+) -> Result<()> {
+    let path = workspace.join(EXTERN_CMAKE_FILE);
     let mut extern_cmake_file =
         File::create(path).context(format!("Unable to create {EXTERN_CMAKE_FILE}"))?;
+
+    extern_cmake_file
+        .write_all(make_extern_cmake_string(dep, repo)?.as_bytes())
+        .context("Failed to write out extern cmake file")?;
+    Ok(())
+}
+
+pub fn make_extern_cmake_string(
+    dep: &SharedPackageConfig,
+    repo: &impl Repository,
+) -> Result<String> {
     let mut result = concatln!(
             "# YOU SHOULD NOT MANUALLY EDIT THIS FILE, QPM WILL VOID ALL CHANGES",
             "# always added",
@@ -228,22 +269,28 @@ pub fn write_extern_cmake(dep: &SharedPackageConfig, repo: &impl Repository) ->
 
     result.push_str(concatln!(
         "\n# libs dir -> stores .so or .a files (or symlinked!)",
-        "target_link_directories(${COMPILE_ID} PRIVATE ${EXTERN_DIR}/libs)",
-        "RECURSE_FILES(so_list ${EXTERN_DIR}/libs/*.so)",
-        "RECURSE_FILES(a_list ${EXTERN_DIR}/libs/*.a)\n",
-        "# every .so or .a that needs to be linked, put here!",
-        "# I don't believe you need to specify if a lib is static or not, poggers!",
-        "target_link_libraries(${COMPILE_ID} PRIVATE\n\t${so_list}\n\t${a_list}\n)\n"
+        "# arm64-v8a binaries are at the top, other ABIs in a folder named after them",
+        "if (ANDROID_ABI STREQUAL \"arm64-v8a\")",
+        "\tset(EXTERN_LIBS_DIR ${EXTERN_DIR}/libs)",
+        "else()",
+        "\tset(EXTERN_LIBS_DIR ${EXTERN_DIR}/libs/${ANDROID_ABI})",
+        "endif()",
+        "target_link_directories(${COMPILE_ID} PRIVATE ${EXTERN_LIBS_DIR})",
+        "file(GLOB so_list ${EXTERN_LIBS_DIR}/*.so)",
+        "file(GLOB a_list ${EXTERN_LIBS_DIR}/*.a)\n",
+        "# static libraries go first and are grouped, as they may depend on each other",
+        "if (a_list)",
+        "\ttarget_link_libraries(${COMPILE_ID} PRIVATE -Wl,--start-group ${a_list} -Wl,--end-group)",
+        "endif()\n",
+        "# every .so that needs to be linked, put here!",
+        "target_link_libraries(${COMPILE_ID} PRIVATE\n\t${so_list}\n)\n"
     ));
 
-    extern_cmake_file
-        .write_all(result.as_bytes())
-        .context("Failed to write out extern cmake file")?;
-    Ok(())
+    Ok(result)
 }
 
-pub fn write_define_cmake(dep: &SharedPackageConfig) -> Result<()> {
-    let path = Path::new("./").join(QPM_CMAKE_FILE);
+pub fn write_define_cmake(workspace: &Path, dep: &SharedPackageConfig) -> Result<()> {
+    let path = workspace.join(QPM_CMAKE_FILE);
 
     let mut defines_cmake_file =
         File::create(path).context("Failed to create defines cmake file")?;
@@ -333,8 +380,15 @@ pub fn make_defines_string(dep: &SharedPackageConfig) -> Result<String> {
         "endif()",
         "message(Using NDK ${CMAKE_ANDROID_NDK})",
         "string(REPLACE \"\\\\\" \"/\" CMAKE_ANDROID_NDK ${CMAKE_ANDROID_NDK})",
-        "\nset(ANDROID_PLATFORM 24)",
-        "set(ANDROID_ABI arm64-v8a)",
+        "\nset(ANDROID_PLATFORM 24)"
+    ));
+    // the first ABI of `qpm config abis`, others are built by passing -DANDROID_ABI
+    writeln!(
+        result,
+        "if (NOT DEFINED ANDROID_ABI)\n\tset(ANDROID_ABI {})\nendif()",
+        Abi::primary()
+    )?;
+    result.push_str(concatln!(
         "set(ANDROID_STL c++_static)",
         "set(ANDROID_USE_LEGACY_TOOLCHAIN_FILE OFF)",
         "\nset(CMAKE_TOOLCHAIN_FILE ${CMAKE_ANDROID_NDK}/build/cmake/android.toolchain.cmake)"
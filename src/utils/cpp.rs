@@ -0,0 +1,836 @@
+//! Lightweight scan of the public declarations in C++ headers.
+//! This is not a C++ parser, it understands just enough of the language
+//! to compare the API surface of two versions of a package.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{Result, eyre::Context};
+use itertools::Itertools;
+use walkdir::WalkDir;
+
+/// Extensions of files considered headers
+pub const HEADER_EXTENSIONS: &[&str] = &["h", "hh", "hpp", "hxx", "inl"];
+
+/// Builtin type keywords which are never parameter names
+const TYPE_KEYWORDS: &[&str] = &[
+    "auto", "bool", "char", "char8_t", "char16_t", "char32_t", "const", "double", "float", "int",
+    "long", "short", "signed", "unsigned", "void", "volatile", "wchar_t",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeclarationKind {
+    Macro,
+    Class,
+    Enum,
+    Enumerator,
+    Alias,
+    Function,
+    Variable,
+}
+
+impl fmt::Display for DeclarationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DeclarationKind::Macro => "macro",
+            DeclarationKind::Class => "class",
+            DeclarationKind::Enum => "enum",
+            DeclarationKind::Enumerator => "enumerator",
+            DeclarationKind::Alias => "alias",
+            DeclarationKind::Function => "function",
+            DeclarationKind::Variable => "variable",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A public declaration found in a header
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Declaration {
+    pub kind: DeclarationKind,
+    /// Fully qualified name, e.g. `ns::Class::method`
+    pub name: String,
+    /// The declaration as written, without bodies
+    pub signature: String,
+    /// The declaration normalized for comparison, parameter names are not part of the API
+    pub key: String,
+    /// Header the declaration was found in, relative to the scanned directory
+    pub header: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiChange {
+    Removed(Declaration),
+    Changed { old: Declaration, new: Declaration },
+    Added(Declaration),
+}
+
+impl ApiChange {
+    /// Whether code compiled against the old headers may fail to compile against the new ones
+    pub fn is_breaking(&self) -> bool {
+        !matches!(self, ApiChange::Added(_))
+    }
+}
+
+/// Scans all headers in `dir` recursively
+pub fn scan_headers(dir: &Path) -> Result<Vec<Declaration>> {
+    let mut declarations = Vec::new();
+
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry?;
+        let is_header = entry
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| HEADER_EXTENSIONS.contains(&ext));
+        if !entry.file_type().is_file() || !is_header {
+            continue;
+        }
+
+        let bytes = fs::read(entry.path())
+            .with_context(|| format!("Unable to read header {}", entry.path().display()))?;
+        let header = entry.path().strip_prefix(dir)?;
+
+        declarations.extend(scan_header(&String::from_utf8_lossy(&bytes), header));
+    }
+
+    Ok(declarations)
+}
+
+/// Scans the public declarations of a single header
+pub fn scan_header(source: &str, header: &Path) -> Vec<Declaration> {
+    let (tokens, macros) = lex(source);
+
+    let mut scanner = Scanner {
+        header,
+        tokens: &tokens,
+        pos: 0,
+        scopes: Vec::new(),
+        declarations: macros
+            .into_iter()
+            .map(|(name, signature)| Declaration {
+                kind: DeclarationKind::Macro,
+                name,
+                key: signature.clone(),
+                signature,
+                header: header.to_path_buf(),
+            })
+            .collect(),
+    };
+    scanner.run();
+    scanner.declarations
+}
+
+/// Compares the declarations of two header sets.
+/// Members of removed classes are not reported separately
+pub fn diff_declarations(old: &[Declaration], new: &[Declaration]) -> Vec<ApiChange> {
+    let mut by_name: BTreeMap<&str, (Vec<&Declaration>, Vec<&Declaration>)> = BTreeMap::new();
+    for decl in old {
+        by_name.entry(&decl.name).or_default().0.push(decl);
+    }
+    for decl in new {
+        by_name.entry(&decl.name).or_default().1.push(decl);
+    }
+
+    let mut changes = Vec::new();
+    for (old_decls, new_decls) in by_name.values() {
+        let old_keys: BTreeSet<&str> = old_decls.iter().map(|d| d.key.as_str()).collect();
+        let new_keys: BTreeSet<&str> = new_decls.iter().map(|d| d.key.as_str()).collect();
+
+        let removed = old_decls
+            .iter()
+            .filter(|d| !new_keys.contains(d.key.as_str()))
+            .unique_by(|d| d.key.clone())
+            .collect_vec();
+        let added = new_decls
+            .iter()
+            .filter(|d| !old_keys.contains(d.key.as_str()))
+            .unique_by(|d| d.key.clone())
+            .collect_vec();
+
+        match (removed.as_slice(), added.as_slice()) {
+            ([old], [new]) => changes.push(ApiChange::Changed {
+                old: (**old).clone(),
+                new: (**new).clone(),
+            }),
+            _ => {
+                changes.extend(
+                    removed
+                        .into_iter()
+                        .map(|d| ApiChange::Removed((*d).clone())),
+                );
+                changes.extend(added.into_iter().map(|d| ApiChange::Added((*d).clone())));
+            }
+        }
+    }
+
+    let removed_classes = changes
+        .iter()
+        .filter_map(|change| match change {
+            ApiChange::Removed(d) if d.kind == DeclarationKind::Class => {
+                Some(format!("{}::", d.name))
+            }
+            _ => None,
+        })
+        .collect_vec();
+
+    changes.retain(|change| match change {
+        ApiChange::Removed(d) => !removed_classes.iter().any(|c| d.name.starts_with(c)),
+        _ => true,
+    });
+    changes
+}
+
+/// Splits `source` into tokens, dropping comments and preprocessor directives.
+/// Returns the tokens and the `#define`d macros as (name, signature)
+fn lex(source: &str) -> (Vec<String>, Vec<(String, String)>) {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut macros = Vec::new();
+    let mut line_start = true;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '\n' {
+            line_start = true;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '\\' && chars.get(i + 1) == Some(&'\n') {
+            i += 2;
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i = skip_block_comment(&chars, i);
+            continue;
+        }
+
+        if c == '#' && line_start {
+            let mut directive = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != '\n' {
+                match chars[i] {
+                    '\\' if chars.get(i + 1) == Some(&'\n') => {
+                        directive.push(' ');
+                        i += 2;
+                    }
+                    '/' if chars.get(i + 1) == Some(&'/') => {
+                        while i < chars.len() && chars[i] != '\n' {
+                            i += 1;
+                        }
+                    }
+                    '/' if chars.get(i + 1) == Some(&'*') => {
+                        i = skip_block_comment(&chars, i);
+                        directive.push(' ');
+                    }
+                    ch => {
+                        directive.push(ch);
+                        i += 1;
+                    }
+                }
+            }
+            macros.extend(parse_define(&directive));
+            continue;
+        }
+        line_start = false;
+
+        let start = i;
+        if c.is_alphanumeric() || c == '_' {
+            // raw strings R"delim( ... )delim"
+            if c == 'R' && chars.get(i + 1) == Some(&'"') {
+                let delim_end = (i + 2..chars.len()).find(|&j| chars[j] == '(');
+                if let Some(delim_end) = delim_end {
+                    let terminator: String = std::iter::once(')')
+                        .chain(chars[i + 2..delim_end].iter().copied())
+                        .chain(std::iter::once('"'))
+                        .collect();
+                    let rest: String = chars[delim_end..].iter().collect();
+                    let len = rest
+                        .find(&terminator)
+                        .map(|end| rest[..end + terminator.len()].chars().count())
+                        .unwrap_or(rest.chars().count());
+                    i = delim_end + len;
+                    tokens.push("R\"\"".to_string());
+                    continue;
+                }
+            }
+            // numbers may contain ' as digit separator and . as decimal point
+            let number = c.is_ascii_digit();
+            while i < chars.len()
+                && (chars[i].is_alphanumeric()
+                    || chars[i] == '_'
+                    || (number && matches!(chars[i], '\'' | '.')))
+            {
+                i += 1;
+            }
+        } else if c == '"' || c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != c && chars[i] != '\n' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 3)].iter().collect();
+            let len = [
+                "...", "<=>", "->*", "::", "->", "&&", "||", "==", "!=", "<=", ">=", "+=", "-=",
+                "*=", "/=", "%=", "&=", "|=", "^=", "++", "--",
+            ]
+            .iter()
+            .find(|p| rest.starts_with(**p))
+            .map_or(1, |p| p.len());
+            i += len;
+        }
+        tokens.push(chars[start..i].iter().collect());
+    }
+
+    (tokens, macros)
+}
+
+fn skip_block_comment(chars: &[char], start: usize) -> usize {
+    let mut i = start + 2;
+    while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+        i += 1;
+    }
+    (i + 2).min(chars.len())
+}
+
+/// Macros without parameters or value are include guards or feature flags, not API
+fn parse_define(directive: &str) -> Option<(String, String)> {
+    let rest = directive.trim_start().strip_prefix("define")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let rest = rest.trim_start();
+
+    let name_len = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    let name = &rest[..name_len];
+    let after_name = &rest[name_len..];
+
+    if let Some(params) = after_name.strip_prefix('(') {
+        let params = params.split(')').next().unwrap_or_default();
+        let params = params.split(',').map(str::trim).join(", ");
+        return Some((name.to_string(), format!("#define {name}({params})")));
+    }
+
+    if after_name.trim().is_empty() {
+        return None;
+    }
+    Some((name.to_string(), format!("#define {name}")))
+}
+
+fn is_word(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Joins tokens back into readable C++
+fn join_tokens<S: AsRef<str>>(tokens: &[S]) -> String {
+    let mut out = String::new();
+    let mut prev: Option<&str> = None;
+    let mut before_prev: Option<&str> = None;
+    for token in tokens {
+        let token = token.as_ref();
+        if let Some(prev) = prev {
+            let prev_word = is_word(prev) || prev.starts_with(['"', '\'']);
+            let word = is_word(token) || token.starts_with(['"', '\'']);
+            // void (*name)(args) keeps the declarator together
+            let declarator = matches!(prev, "*" | "&") && before_prev == Some("(");
+            let space = (prev_word && word)
+                || (word
+                    && !declarator
+                    && matches!(prev, "*" | "&" | "&&" | ">" | ")" | "," | "=" | "..."))
+                || (token == "=" || prev == "=")
+                || (token == "{" || prev == "{" || token == "}")
+                || (token == ":" || prev == ":")
+                || (token == "->" || prev == "->");
+            if space {
+                out.push(' ');
+            }
+        }
+        out.push_str(token);
+        before_prev = prev;
+        prev = Some(token);
+    }
+    out
+}
+
+/// Finds the index of the token closing the group opened at `open`
+fn matching_close(tokens: &[String], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token.as_str() {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len().saturating_sub(1)
+}
+
+/// Length of a leading `template <...>` clause
+fn template_prefix_len(tokens: &[String]) -> usize {
+    if tokens.first().map(String::as_str) != Some("template")
+        || tokens.get(1).map(String::as_str) != Some("<")
+    {
+        return 0;
+    }
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(1) {
+        match token.as_str() {
+            "<" => depth += 1,
+            ">" => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// Index of the `(` opening a function's parameters, skipping template arguments
+fn function_paren(tokens: &[String]) -> Option<usize> {
+    let mut angle = 0usize;
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i].as_str() {
+            "operator" => {
+                // operator() has its parameters after the call operator itself
+                let skip = if tokens.get(i + 1).map(String::as_str) == Some("(") {
+                    3
+                } else {
+                    1
+                };
+                return (i + skip..tokens.len()).find(|&j| tokens[j] == "(");
+            }
+            "<" if i > 0 && is_word(&tokens[i - 1]) => angle += 1,
+            ">" if angle > 0 => angle -= 1,
+            "(" if angle == 0 => return Some(i),
+            "(" | "[" | "{" => i = matching_close(tokens, i),
+            "=" if angle == 0 => return None,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// The possibly qualified name declared right before `end`, e.g. `Foo::bar` or `operator==`
+fn name_before(tokens: &[String], end: usize) -> Option<String> {
+    if let Some(operator) = tokens[..end].iter().rposition(|t| t == "operator") {
+        let mut start = operator;
+        while start >= 2 && tokens[start - 1] == "::" && is_word(&tokens[start - 2]) {
+            start -= 2;
+        }
+        return Some(join_tokens(&tokens[start..end]));
+    }
+
+    let mut start = end.checked_sub(1)?;
+    if !is_word(&tokens[start]) {
+        return None;
+    }
+    if start >= 1 && tokens[start - 1] == "~" {
+        start -= 1;
+    }
+    while start >= 2 && tokens[start - 1] == "::" && is_word(&tokens[start - 2]) {
+        start -= 2;
+    }
+    Some(tokens[start..end].concat())
+}
+
+/// Removes the name of every parameter in the group opened at `open`
+fn strip_parameter_names(tokens: &[String], open: usize) -> Vec<String> {
+    let close = matching_close(tokens, open);
+    let mut out = tokens[..=open].to_vec();
+
+    let params = &tokens[open + 1..close];
+    let mut depth = 0usize;
+    let mut param_start = 0;
+    for i in 0..=params.len() {
+        let token = params.get(i).map(String::as_str);
+        match token {
+            Some("(" | "[" | "{" | "<") => depth += 1,
+            Some(")" | "]" | "}" | ">") => depth = depth.saturating_sub(1),
+            Some(",") | None if depth == 0 => {
+                let param = &params[param_start..i];
+                let default = param.iter().position(|t| t == "=").unwrap_or(param.len());
+                let (decl, default) = param.split_at(default);
+
+                let named = decl.len() >= 2
+                    && decl
+                        .last()
+                        .is_some_and(|t| is_word(t) && !TYPE_KEYWORDS.contains(&t.as_str()))
+                    && decl[decl.len() - 2] != "::";
+                let decl = if named { &decl[..decl.len() - 1] } else { decl };
+
+                out.extend(decl.iter().chain(default).cloned());
+                if let Some(token) = token {
+                    out.push(token.to_string());
+                }
+                param_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    out.extend(tokens[close..].iter().cloned());
+    out
+}
+
+#[derive(Debug)]
+enum ScopeKind {
+    Namespace,
+    Class,
+    Enum,
+    /// `extern "C" { ... }`
+    Linkage,
+}
+
+#[derive(Debug)]
+struct Scope {
+    kind: ScopeKind,
+    name: Option<String>,
+    public: bool,
+}
+
+struct Scanner<'a> {
+    header: &'a Path,
+    tokens: &'a [String],
+    pos: usize,
+    scopes: Vec<Scope>,
+    declarations: Vec<Declaration>,
+}
+
+impl Scanner<'_> {
+    fn run(&mut self) {
+        let mut statement: Vec<String> = Vec::new();
+
+        while self.pos < self.tokens.len() {
+            let token = self.tokens[self.pos].as_str();
+            self.pos += 1;
+
+            if matches!(
+                self.scopes.last(),
+                Some(Scope {
+                    kind: ScopeKind::Enum,
+                    ..
+                })
+            ) {
+                match token {
+                    "," => self.enumerator(std::mem::take(&mut statement)),
+                    "}" => {
+                        self.enumerator(std::mem::take(&mut statement));
+                        self.scopes.pop();
+                    }
+                    "(" | "[" | "{" => self.take_group(&mut statement),
+                    _ => statement.push(token.to_string()),
+                }
+                continue;
+            }
+
+            match token {
+                ";" => self.statement(std::mem::take(&mut statement)),
+                "(" | "[" => self.take_group(&mut statement),
+                ":" if matches!(
+                    statement.as_slice(),
+                    [access] if matches!(access.as_str(), "public" | "protected" | "private")
+                ) =>
+                {
+                    if let Some(scope) = self.scopes.last_mut()
+                        && matches!(scope.kind, ScopeKind::Class)
+                    {
+                        scope.public = statement[0] != "private";
+                    }
+                    statement.clear();
+                }
+                "{" => self.open_brace(&mut statement),
+                "}" => {
+                    if !statement.is_empty() {
+                        self.statement(std::mem::take(&mut statement));
+                    }
+                    self.scopes.pop();
+                }
+                _ => statement.push(token.to_string()),
+            }
+        }
+    }
+
+    /// Appends the group opened by the token before `pos` to `statement`
+    fn take_group(&mut self, statement: &mut Vec<String>) {
+        let open = self.pos - 1;
+        let close = matching_close(self.tokens, open);
+        statement.extend(self.tokens[open..=close].iter().cloned());
+        self.pos = close + 1;
+    }
+
+    fn skip_group(&mut self) {
+        self.pos = matching_close(self.tokens, self.pos - 1) + 1;
+    }
+
+    fn open_brace(&mut self, statement: &mut Vec<String>) {
+        let template = template_prefix_len(statement);
+        let body = &statement[template..];
+        let first = body.first().map(String::as_str);
+
+        match first {
+            Some("namespace" | "inline") if body.iter().take(2).any(|t| t == "namespace") => {
+                let name_start = body.iter().position(|t| t == "namespace").unwrap() + 1;
+                let name = body[name_start..].concat();
+                self.scopes.push(Scope {
+                    kind: ScopeKind::Namespace,
+                    name: (!name.is_empty()).then_some(name),
+                    public: true,
+                });
+            }
+            Some("extern") if body.len() == 2 => self.scopes.push(Scope {
+                kind: ScopeKind::Linkage,
+                name: None,
+                public: true,
+            }),
+            Some("class" | "struct" | "union") => {
+                let public = first != Some("class");
+                let name = self.class_name(&body[1..]);
+                if let Some(name) = &name {
+                    self.declare(DeclarationKind::Class, name, statement.clone());
+                }
+                self.scopes.push(Scope {
+                    kind: ScopeKind::Class,
+                    name,
+                    public,
+                });
+            }
+            Some("enum") => {
+                let name = body
+                    .iter()
+                    .skip(1)
+                    .find(|t| !matches!(t.as_str(), "class" | "struct"))
+                    .filter(|t| is_word(t))
+                    .cloned();
+                if let Some(name) = &name {
+                    self.declare(DeclarationKind::Enum, name, statement.clone());
+                }
+                self.scopes.push(Scope {
+                    kind: ScopeKind::Enum,
+                    name,
+                    public: true,
+                });
+            }
+            Some("typedef") => {
+                // typedef struct { ... } Name; only the alias is API
+                self.skip_group();
+                let end = (self.pos..self.tokens.len())
+                    .find(|&i| self.tokens[i] == ";")
+                    .unwrap_or(self.tokens.len());
+                if let Some(name) = self.tokens[self.pos..end].iter().find(|t| is_word(t)) {
+                    self.declare(
+                        DeclarationKind::Alias,
+                        name,
+                        vec!["typedef".to_string(), name.clone()],
+                    );
+                }
+                self.pos = end + 1;
+                statement.clear();
+                return;
+            }
+            _ if function_paren(body).is_some() => {
+                let in_initializer_list = statement.last().is_some_and(|t| is_word(t) || t == ">")
+                    && body
+                        .iter()
+                        .skip(function_paren(body).unwrap())
+                        .any(|t| t == ":");
+                if in_initializer_list {
+                    // brace initialized member in a constructor initializer list
+                    self.take_group(statement);
+                    return;
+                }
+                // function definition
+                self.statement(std::mem::take(statement));
+                self.skip_group();
+                return;
+            }
+            Some(_) => {
+                // brace initialized variable
+                self.take_group(statement);
+                return;
+            }
+            None => {
+                // block without declaration
+                self.skip_group();
+                return;
+            }
+        }
+
+        statement.clear();
+    }
+
+    /// Name of a class from the tokens after its keyword
+    fn class_name(&self, head: &[String]) -> Option<String> {
+        let mut angle = 0usize;
+        let mut end = head.len();
+        for (i, token) in head.iter().enumerate() {
+            match token.as_str() {
+                "<" => angle += 1,
+                ">" => angle = angle.saturating_sub(1),
+                ":" if angle == 0 => {
+                    end = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let head = &head[..end];
+        let head = match head.last().map(String::as_str) {
+            Some("final") => &head[..head.len() - 1],
+            _ => head,
+        };
+
+        // template specializations keep their arguments
+        if head.last().map(String::as_str) == Some(">") {
+            let open = head.iter().position(|t| t == "<")?;
+            return Some(join_tokens(&head[open.checked_sub(1)?..]));
+        }
+        head.last().filter(|t| is_word(t)).cloned()
+    }
+
+    fn enumerator(&mut self, statement: Vec<String>) {
+        let Some(name) = statement.first().filter(|t| is_word(t)) else {
+            return;
+        };
+        let name = name.clone();
+        self.declare(DeclarationKind::Enumerator, &name, statement);
+    }
+
+    fn statement(&mut self, statement: Vec<String>) {
+        let template = template_prefix_len(&statement);
+        let body = &statement[template..];
+        let Some(first) = body.first().map(String::as_str) else {
+            return;
+        };
+
+        match first {
+            "friend" | "static_assert" | "class" | "struct" | "union" | "enum" | "namespace" => {}
+            // explicit instantiations
+            "template" => {}
+            "extern" if body.get(1).map(String::as_str) == Some("template") => {}
+            "using" => {
+                if let Some(eq) = body.iter().position(|t| t == "=") {
+                    self.declare(DeclarationKind::Alias, &body[eq - 1], statement.clone());
+                }
+            }
+            "typedef" => {
+                // function pointer typedefs name the alias in the first group
+                let name = match body.iter().position(|t| t == "(") {
+                    Some(open) if body.get(open + 1).map(String::as_str) == Some("*") => {
+                        body.get(open + 2)
+                    }
+                    _ => body.iter().rev().find(|t| is_word(t)),
+                };
+                if let Some(name) = name {
+                    let name = name.clone();
+                    self.declare(DeclarationKind::Alias, &name, statement);
+                }
+            }
+            _ if body.len() < 2 => {}
+            _ => match function_paren(body) {
+                Some(open) => match name_before(body, open)
+                    .filter(|_| body.get(open + 1).map(String::as_str) != Some("*"))
+                {
+                    Some(name) => {
+                        // constructor initializer lists are not part of the declaration
+                        let close = matching_close(body, open);
+                        let end = body[close..]
+                            .iter()
+                            .position(|t| t == ":")
+                            .map_or(statement.len(), |p| template + close + p);
+                        let declaration = &statement[..end];
+
+                        let key = strip_parameter_names(declaration, template + open)
+                            .into_iter()
+                            .filter(|t| t != "override" && t != "final")
+                            .collect_vec();
+                        self.declare_with_key(DeclarationKind::Function, &name, declaration, key);
+                    }
+                    // function pointer variable, void (*name)(args)
+                    None => {
+                        let close = matching_close(body, open);
+                        if let Some(name) = body[open..close].iter().rev().find(|t| is_word(t)) {
+                            let name = name.clone();
+                            self.declare(DeclarationKind::Variable, &name, statement);
+                        }
+                    }
+                },
+                None => {
+                    let end = body
+                        .iter()
+                        .position(|t| matches!(t.as_str(), "=" | "{" | "[" | "," | ":"))
+                        .unwrap_or(body.len());
+                    let Some(name) = name_before(body, end) else {
+                        return;
+                    };
+                    let key = statement[..template + end].to_vec();
+                    self.declare_with_key(DeclarationKind::Variable, &name, &statement, key);
+                }
+            },
+        }
+    }
+
+    fn declare(&mut self, kind: DeclarationKind, name: &str, tokens: Vec<String>) {
+        self.declare_with_key(kind, name, &tokens, tokens.clone());
+    }
+
+    fn declare_with_key(
+        &mut self,
+        kind: DeclarationKind,
+        name: &str,
+        tokens: &[String],
+        key: Vec<String>,
+    ) {
+        if !self.scopes.iter().all(|s| s.public) {
+            return;
+        }
+
+        let name = self
+            .scopes
+            .iter()
+            .filter_map(|s| s.name.as_deref())
+            .chain(std::iter::once(name))
+            .join("::");
+
+        self.declarations.push(Declaration {
+            kind,
+            name,
+            signature: join_tokens(tokens),
+            key: join_tokens(&key),
+            header: self.header.to_path_buf(),
+        });
+    }
+}
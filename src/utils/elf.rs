@@ -0,0 +1,167 @@
+use std::{collections::BTreeSet, fs, path::Path};
+
+use color_eyre::{
+    Result,
+    eyre::{Context, bail},
+};
+use object::{
+    Architecture, BinaryFormat, Endianness, Object, ObjectSymbol, SymbolScope,
+    elf::{DT_NEEDED, DT_SONAME},
+    read::elf::{Dyn, ElfFile64},
+};
+
+use crate::{models::abi::Abi, terminal::colors::QPMColor};
+
+/// Libraries provided by Android or the game process.
+/// Any other `DT_NEEDED` entry of a dependency must be provided by another dependency
+pub const SYSTEM_LIBRARIES: &[&str] = &[
+    "libaaudio.so",
+    "libamidi.so",
+    "libandroid.so",
+    "libbinder_ndk.so",
+    "libc.so",
+    "libc++_shared.so",
+    "libcamera2ndk.so",
+    "libdl.so",
+    "libEGL.so",
+    "libGLESv1_CM.so",
+    "libGLESv2.so",
+    "libGLESv3.so",
+    "libil2cpp.so",
+    "libjnigraphics.so",
+    "liblog.so",
+    "libm.so",
+    "libmain.so",
+    "libmediandk.so",
+    "libnativewindow.so",
+    "libOpenMAXAL.so",
+    "libOpenSLES.so",
+    "libstdc++.so",
+    "libsync.so",
+    "libunity.so",
+    "libvulkan.so",
+    "libz.so",
+];
+
+/// Dynamic linking information of a shared library
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SharedObjectInfo {
+    pub soname: Option<String>,
+    /// `DT_NEEDED` entries in load order
+    pub needed: Vec<String>,
+    /// Dynamic symbols defined by the library
+    pub exports: BTreeSet<String>,
+}
+
+/// Whether `path` names a shared library, as opposed to a static archive
+pub fn is_shared_object(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "so")
+}
+
+/// Whether `path` is named like a static library
+pub fn is_static_archive(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "a")
+}
+
+/// Ensures `path` is an `ar` archive, the format of static libraries
+pub fn ensure_static_archive(path: &Path) -> Result<()> {
+    const AR_MAGIC: &[u8] = b"!<arch>\n";
+
+    if !read(path)?.starts_with(AR_MAGIC) {
+        bail!(
+            "{} is not a valid static library, is the link pointing to an html page?",
+            path.display().file_path_color()
+        );
+    }
+    Ok(())
+}
+
+/// Architecture of the binaries for `abi`
+pub fn architecture(abi: Abi) -> Architecture {
+    match abi {
+        Abi::Arm64V8a => Architecture::Aarch64,
+        Abi::X86_64 => Architecture::X86_64,
+    }
+}
+
+/// Ensures `path` is an ELF binary that can be loaded on `abi`, e.g. arm64-v8a for the Quest
+pub fn ensure_elf(path: &Path, abi: Abi) -> Result<()> {
+    let data = read(path)?;
+    parse_elf(path, &data, abi)?;
+    Ok(())
+}
+
+/// Reads the SONAME, needed libraries and exported symbols of a shared library built for `abi`
+pub fn read_shared_object(path: &Path, abi: Abi) -> Result<SharedObjectInfo> {
+    let data = read(path)?;
+    let file = parse_elf(path, &data, abi)?;
+
+    let exports = file
+        .dynamic_symbols()
+        .filter(|s| s.is_definition() && s.scope() == SymbolScope::Dynamic)
+        .filter_map(|s| s.name().ok())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let elf = ElfFile64::<Endianness>::parse(&*data)
+        .with_context(|| format!("Unable to parse {}", path.display()))?;
+    let endian = elf.endian();
+    let sections = elf.elf_section_table();
+
+    let mut info = SharedObjectInfo {
+        exports,
+        ..Default::default()
+    };
+
+    let Some((dynamic, strings_index)) = sections.dynamic(endian, elf.data())? else {
+        return Ok(info);
+    };
+    let strings = sections.strings(endian, elf.data(), strings_index)?;
+
+    for entry in dynamic {
+        let tag = entry.tag32(endian);
+        if tag != Some(DT_NEEDED) && tag != Some(DT_SONAME) {
+            continue;
+        }
+        let value = String::from_utf8_lossy(entry.string(endian, strings)?).into_owned();
+        match tag {
+            Some(DT_SONAME) => info.soname = Some(value),
+            _ => info.needed.push(value),
+        }
+    }
+
+    Ok(info)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("Unable to read {}", path.display()))
+}
+
+fn parse_elf<'a>(path: &Path, data: &'a [u8], abi: Abi) -> Result<object::File<'a>> {
+    let Ok(file) = object::File::parse(data) else {
+        bail!(
+            "{} is not a valid binary, is the link pointing to an html page?",
+            path.display().file_path_color()
+        );
+    };
+
+    if file.format() != BinaryFormat::Elf {
+        bail!(
+            "{} is a {:?} binary, expected ELF",
+            path.display().file_path_color(),
+            file.format()
+        );
+    }
+
+    if file.architecture() != architecture(abi) {
+        bail!(
+            "{} is built for {:?}, expected {:?} ({abi})",
+            path.display().file_path_color(),
+            file.architecture(),
+            architecture(abi)
+        );
+    }
+
+    Ok(file)
+}
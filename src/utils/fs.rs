@@ -1,7 +1,11 @@
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
 
 use color_eyre::Result;
 use fs_extra::{dir::copy as copy_directory, file::copy as copy_file};
+use walkdir::WalkDir;
 
 pub fn copy_things(a: &Path, b: &Path) -> Result<()> {
     if a.is_dir() {
@@ -28,3 +32,93 @@ pub fn copy_things(a: &Path, b: &Path) -> Result<()> {
     result?;
     Ok(())
 }
+
+/// Removes a file, folder or symlink without following symlinks
+pub fn remove_path(path: &Path) -> Result<()> {
+    let metadata = fs::symlink_metadata(path)?;
+
+    if metadata.is_symlink() {
+        // windows distinguishes between directory and file symlinks
+        if path.is_dir() {
+            symlink::remove_symlink_dir(path)?;
+        } else if symlink::remove_symlink_file(path).is_err() {
+            // broken symlink, we can't know which kind it was
+            fs::remove_dir(path).or_else(|_| fs::remove_file(path))?;
+        }
+    } else if metadata.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+/// Returns true if both paths are regular files with identical contents
+pub fn files_equal(a: &Path, b: &Path) -> Result<bool> {
+    // a symlink is never equal to a copy
+    if b.is_symlink() || !b.is_file() {
+        return Ok(false);
+    }
+
+    if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+
+    Ok(fs::read(a)? == fs::read(b)?)
+}
+
+/// Returns true if `b` is a copy of `a`, a file with identical contents
+/// or a folder with exactly the same files
+pub fn copy_equal(a: &Path, b: &Path) -> Result<bool> {
+    if a.is_file() {
+        return files_equal(a, b);
+    }
+
+    if b.is_symlink() || !b.is_dir() {
+        return Ok(false);
+    }
+
+    for entry in WalkDir::new(a).min_depth(1) {
+        let entry = entry?;
+        let copy = b.join(entry.path().strip_prefix(a)?);
+        let equal = match entry.file_type().is_dir() {
+            true => copy.is_dir() && !copy.is_symlink(),
+            false => files_equal(entry.path(), &copy)?,
+        };
+        if !equal {
+            return Ok(false);
+        }
+    }
+
+    // nothing may be left over from an older copy
+    for entry in WalkDir::new(b).min_depth(1) {
+        let entry = entry?;
+        if !a.join(entry.path().strip_prefix(b)?).exists() {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Resolves `.` and `..` without touching the file system, so paths that do not exist yet
+/// or point through symlinks are compared as written
+pub fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                // `..` of the root is the root
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
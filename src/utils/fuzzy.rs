@@ -0,0 +1,61 @@
+/// Scores how well `candidate` matches `query`, `None` if the query is not a subsequence of it.
+/// Case insensitive, higher is better. Consecutive matches, matches at the start of a word
+/// and short candidates score higher.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query = query.to_lowercase();
+    let candidate = candidate.to_lowercase();
+    let query_chars = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    let candidate_chars = candidate.chars().collect::<Vec<_>>();
+
+    if query_chars.is_empty() {
+        return Some(0);
+    }
+    if query == candidate {
+        return Some(1000);
+    }
+
+    let mut score = 0i64;
+    let mut matched = 0;
+    let mut previous: Option<usize> = None;
+    for (i, c) in candidate_chars.iter().enumerate() {
+        if matched == query_chars.len() {
+            break;
+        }
+        if *c != query_chars[matched] {
+            continue;
+        }
+
+        score += 1;
+        if i > 0 && previous == Some(i - 1) {
+            score += 5;
+        }
+        if i == 0 || matches!(candidate_chars[i - 1], '-' | '_' | '.' | ' ') {
+            score += 8;
+        }
+        previous = Some(i);
+        matched += 1;
+    }
+
+    if matched < query_chars.len() {
+        return None;
+    }
+
+    if candidate.starts_with(&query) {
+        score += 30;
+    } else if candidate.contains(&query) {
+        score += 20;
+    }
+    score -= (candidate_chars.len() - query_chars.len()) as i64 / 4;
+
+    Some(score)
+}
+
+/// Best score of `query` against the id and, if known, the display name of a package
+pub fn package_score(query: &str, id: &str, name: Option<&str>) -> Option<i64> {
+    let id_score = fuzzy_score(query, id);
+    let name_score = name.and_then(|name| fuzzy_score(query, name));
+    id_score.max(name_score)
+}
@@ -15,7 +15,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     models::config::get_keyring,
-    network::agent::{download_file_report, get_agent},
+    network::agent::{download_file_report, get_agent_for, loopback_override},
     terminal::colors::QPMColor,
 };
 
@@ -25,8 +25,7 @@ pub fn check_git() -> Result<()> {
 
     match git.output() {
         Ok(_) => {
-            #[cfg(debug_assertions)]
-            println!("git detected on command line!");
+            log::debug!("git detected on command line!");
             Ok(())
         }
         Err(_e) => {
@@ -49,6 +48,65 @@ pub fn check_git() -> Result<()> {
     }
 }
 
+/// Runs git with `args` in `cwd`, failing if git exits unsuccessfully
+pub fn run(args: &[&str], cwd: &Path) -> Result<()> {
+    run_with_auth(args, cwd, None)
+}
+
+/// Environment variables the credential helper of [`run_with_auth`] reads
+const USERNAME_ENV: &str = "QPM_GIT_USERNAME";
+const PASSWORD_ENV: &str = "QPM_GIT_PASSWORD";
+
+/// [`run`] authenticating to https remotes with `auth`, a token or `user:token`.
+/// The credential reaches git through a credential helper reading the environment,
+/// so it never ends up in a remote url, `.git/config`, the process arguments or error output
+pub fn run_with_auth(args: &[&str], cwd: &Path, auth: Option<&str>) -> Result<()> {
+    let mut git = Command::new("git");
+    git.args(args).current_dir(cwd);
+
+    if let Some(auth) = auth {
+        let (username, password) = auth.split_once(':').unwrap_or(("x-access-token", auth));
+        git.env("GIT_CONFIG_COUNT", "2")
+            // an empty helper disables the ones configured by the user
+            .env("GIT_CONFIG_KEY_0", "credential.helper")
+            .env("GIT_CONFIG_VALUE_0", "")
+            .env("GIT_CONFIG_KEY_1", "credential.helper")
+            .env(
+                "GIT_CONFIG_VALUE_1",
+                format!(
+                    "!f() {{ echo \"username=${USERNAME_ENV}\"; echo \"password=${PASSWORD_ENV}\"; }}; f"
+                ),
+            )
+            .env(USERNAME_ENV, username)
+            .env(PASSWORD_ENV, password);
+    }
+
+    let status = git.status().context("Failed to run git")?;
+    if !status.success() {
+        bail!("git {} failed with {status}", args.first().unwrap_or(&""));
+    }
+    Ok(())
+}
+
+/// Environment variable overriding the GitHub API used for private releases with a test server on this machine
+pub const GITHUB_API_URL_ENV: &str = "QPM_GITHUB_API_URL";
+
+fn github_api_url() -> String {
+    loopback_override(GITHUB_API_URL_ENV).unwrap_or_else(|| "https://api.github.com".to_string())
+}
+
+fn host_of(url: &str) -> Option<String> {
+    reqwest::Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(str::to_string)
+}
+
+/// Puts `token` into the userinfo of `url`
+fn with_token(url: &str, token: &str) -> String {
+    url.replacen("://", &format!("://{token}@"), 1)
+}
+
 pub fn get_release(url: &str, out: &std::path::Path) -> Result<bool> {
     check_git()?;
     if let Some(token_unwrapped) = get_keyring().and_then(|e| e.get_password().ok()) {
@@ -89,11 +147,14 @@ pub fn get_release_with_token(url: &str, out: &std::path::Path, token: &str) ->
     let filename = split.get(8).unwrap();
 
     let asset_data_link = format!(
-        "https://{}@api.github.com/repos/{}/{}/releases/tags/{}",
-        &token, &user, &repo, &tag
+        "{}/repos/{}/{}/releases/tags/{}",
+        with_token(&github_api_url(), token),
+        &user,
+        &repo,
+        &tag
     );
 
-    let data = match get_agent().get(asset_data_link).send() {
+    let data = match get_agent_for(&asset_data_link).get(&asset_data_link).send() {
         Ok(o) => o.json::<GithubReleaseData>().unwrap(),
         Err(e) => {
             let error_string = e.to_string().replace(token, "***");
@@ -104,9 +165,13 @@ pub fn get_release_with_token(url: &str, out: &std::path::Path, token: &str) ->
     for asset in data.assets.iter() {
         if asset.name.eq(filename) {
             // this is the correct asset!
-            let download = asset
-                .url
-                .replace("api.github.com", &format!("{token}@api.github.com"));
+            if host_of(&asset.url) != host_of(&asset_data_link) {
+                bail!(
+                    "Release asset {} is not hosted by the GitHub API, refusing to send the token",
+                    asset.url.blue()
+                );
+            }
+            let download = with_token(&asset.url, token);
 
             let file = File::create(out).context("create so file failed")?;
             let mut buf = BufWriter::new(file);
@@ -153,7 +218,7 @@ pub fn clone(mut url: String, branch: Option<&String>, out: &Path) -> Result<boo
     if let Some(branch_unwrapped) = branch {
         git.arg("-b").arg(branch_unwrapped);
     } else {
-        println!("No branch name found, cloning default branch");
+        log::info!("No branch name found, cloning default branch");
     }
 
     let mut child = git
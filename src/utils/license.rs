@@ -0,0 +1,204 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{Result, eyre::Context};
+use itertools::Itertools;
+use qpm_package::models::dependency::{SharedDependency, SharedPackageConfig};
+use semver::Version;
+use serde_json::Value;
+
+use crate::{
+    models::package::{PACKAGE_FILE_NAME, SHARED_PACKAGE_FILE_NAME},
+    repository::local::FileRepository,
+    terminal::colors::QPMColor,
+};
+
+/// File name stems that hold license texts, matched case-insensitively
+const LICENSE_FILE_STEMS: &[&str] = &["LICENSE", "LICENCE", "COPYING", "UNLICENSE", "NOTICE"];
+
+/// Name of the notices file bundled into qmods
+pub const THIRD_PARTY_NOTICES_FILE: &str = "THIRD_PARTY_NOTICES.txt";
+
+/// The license of a package version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageLicense {
+    pub id: String,
+    pub version: Version,
+    /// `info.additionalData.license` of the package
+    pub declared: Option<String>,
+    /// SPDX id guessed from the license files
+    pub detected: Option<String>,
+    pub files: Vec<PathBuf>,
+}
+
+impl PackageLicense {
+    /// The declared license, falling back to the detected one
+    pub fn license(&self) -> Option<&str> {
+        self.declared.as_deref().or(self.detected.as_deref())
+    }
+
+    /// Whether nothing about the license is known
+    pub fn is_unknown(&self) -> bool {
+        self.license().is_none() && self.files.is_empty()
+    }
+}
+
+/// Reads the license of the package whose sources are at `src_path`
+pub fn read_package_license(
+    id: &str,
+    version: &Version,
+    src_path: &Path,
+) -> Result<PackageLicense> {
+    let files = find_license_files(src_path)?;
+    let detected = files
+        .iter()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .find_map(|text| detect_spdx_id(&text))
+        .map(str::to_string);
+
+    Ok(PackageLicense {
+        id: id.to_string(),
+        version: version.clone(),
+        declared: declared_license(src_path),
+        detected,
+        files,
+    })
+}
+
+/// Licenses of every restored dependency, read from the cache
+pub fn collect_licenses(shared_package: &SharedPackageConfig) -> Result<Vec<PackageLicense>> {
+    shared_package
+        .restored_dependencies
+        .iter()
+        .map(dependency_license)
+        .try_collect()
+}
+
+/// Warns about every package whose license could not be found
+pub fn warn_unknown_licenses(licenses: &[PackageLicense]) {
+    for l in licenses.iter().filter(|l| l.is_unknown()) {
+        log::warn!(
+            "No license found for {}:{}, set info.additionalData.license or add a LICENSE file to it",
+            l.id.dependency_id_color(),
+            l.version.version_id_color()
+        );
+    }
+}
+
+pub fn dependency_license(dep: &SharedDependency) -> Result<PackageLicense> {
+    let src_path =
+        FileRepository::get_package_cache_path(&dep.dependency.id, &dep.version).join("src");
+    read_package_license(&dep.dependency.id, &dep.version, &src_path)
+}
+
+/// License files at the top of `dir`, e.g. `LICENSE`, `LICENSE.md` or `COPYING.LESSER`
+pub fn find_license_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut files = vec![];
+    for entry in fs::read_dir(dir).with_context(|| format!("Unable to read {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let stem = name.split(['.', '-', '_']).next().unwrap_or_default();
+        if path.is_file()
+            && LICENSE_FILE_STEMS
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(stem))
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// `info.additionalData.license` of the qpm.json or qpm.shared.json in `dir`
+pub fn declared_license(dir: &Path) -> Option<String> {
+    let read = |file: &str| -> Option<Value> {
+        serde_json::from_str(&fs::read_to_string(dir.join(file)).ok()?).ok()
+    };
+
+    read(PACKAGE_FILE_NAME)
+        .and_then(|package| license_of(&package))
+        .or_else(|| read(SHARED_PACKAGE_FILE_NAME).and_then(|shared| license_of(&shared["config"])))
+}
+
+fn license_of(package: &Value) -> Option<String> {
+    package
+        .pointer("/info/additionalData/license")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Guesses the SPDX id of common license texts
+pub fn detect_spdx_id(text: &str) -> Option<&'static str> {
+    let text = text.split_whitespace().join(" ").to_lowercase();
+    let has = |needle: &str| text.contains(needle);
+
+    let id = if has("gnu lesser general public license") {
+        if has("version 3") {
+            "LGPL-3.0"
+        } else {
+            "LGPL-2.1"
+        }
+    } else if has("gnu general public license") {
+        if has("version 3") {
+            "GPL-3.0"
+        } else {
+            "GPL-2.0"
+        }
+    } else if has("mozilla public license version 2.0") {
+        "MPL-2.0"
+    } else if has("apache license") && has("version 2.0") {
+        "Apache-2.0"
+    } else if has("boost software license") {
+        "BSL-1.0"
+    } else if has("this is free and unencumbered software released into the public domain") {
+        "Unlicense"
+    } else if has("permission is hereby granted, free of charge") {
+        "MIT"
+    } else if has("redistribution and use in source and binary forms") {
+        if has("neither the name") {
+            "BSD-3-Clause"
+        } else {
+            "BSD-2-Clause"
+        }
+    } else if has("this software is provided 'as-is'") && has("altered source versions") {
+        "Zlib"
+    } else {
+        return None;
+    };
+    Some(id)
+}
+
+/// Concatenates the license texts of `licenses` into a notices file
+pub fn third_party_notices(licenses: &[PackageLicense]) -> Result<String> {
+    let mut notices = String::from("This mod includes the following third party libraries.\n");
+
+    for license in licenses {
+        notices.push_str(&format!(
+            "\n{}\n{} {} - {}\n{}\n",
+            "=".repeat(80),
+            license.id,
+            license.version,
+            license.license().unwrap_or("unknown license"),
+            "=".repeat(80)
+        ));
+
+        for file in &license.files {
+            let text = fs::read_to_string(file)
+                .with_context(|| format!("Unable to read {}", file.display()))?;
+            notices.push('\n');
+            notices.push_str(text.trim_end());
+            notices.push('\n');
+        }
+    }
+
+    Ok(notices)
+}
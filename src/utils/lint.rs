@@ -0,0 +1,263 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use color_eyre::{Result, eyre::Context};
+use qpm_package::models::{dependency::SharedPackageConfig, package::PackageConfig};
+use semver::{Op, VersionReq};
+
+use crate::{models::output::LintSeverity, repository::local::FileRepository};
+
+/// A problem found in a package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub id: &'static str,
+    pub severity: LintSeverity,
+    pub message: String,
+    pub fix: Option<Fix>,
+}
+
+/// Change that resolves a finding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fix {
+    CreateSharedDir,
+    EnableCmake,
+    SetDependenciesDir(PathBuf),
+    SetVersionRange {
+        dependency: String,
+        range: VersionReq,
+    },
+    RemoveExtraFile {
+        dependency: String,
+        file: String,
+    },
+}
+
+impl Fix {
+    /// Applies the fix to `package`, creating directories relative to `root`
+    pub fn apply(&self, package: &mut PackageConfig, root: &Path) -> Result<()> {
+        match self {
+            Fix::CreateSharedDir => {
+                let shared_dir = root.join(&package.shared_dir);
+                fs::create_dir_all(&shared_dir)
+                    .with_context(|| format!("Unable to create {}", shared_dir.display()))?;
+            }
+            Fix::EnableCmake => package.info.additional_data.cmake = Some(true),
+            Fix::SetDependenciesDir(dir) => package.dependencies_dir = dir.clone(),
+            Fix::SetVersionRange { dependency, range } => {
+                for dep in package
+                    .dependencies
+                    .iter_mut()
+                    .filter(|d| &d.id == dependency)
+                {
+                    dep.version_range = range.clone();
+                }
+            }
+            Fix::RemoveExtraFile { dependency, file } => {
+                for dep in package
+                    .dependencies
+                    .iter_mut()
+                    .filter(|d| &d.id == dependency)
+                {
+                    if let Some(extra_files) = &mut dep.additional_data.extra_files {
+                        extra_files.retain(|f| f != file);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks `package` in the workspace at `root`.
+/// `shared` enables the checks that need the restored dependencies
+pub fn lint_package(
+    package: &PackageConfig,
+    shared: Option<&SharedPackageConfig>,
+    root: &Path,
+) -> Vec<Finding> {
+    let mut findings = vec![];
+    let data = &package.info.additional_data;
+
+    if !data.headers_only.unwrap_or(false) && data.so_link.is_none() && data.static_link.is_none() {
+        findings.push(Finding {
+            id: "missing-binary-link",
+            severity: LintSeverity::Error,
+            message: "Package is not headersOnly but has no soLink or staticLink".to_string(),
+            fix: None,
+        });
+    }
+
+    let static_linking = data.static_linking.unwrap_or(false);
+    if static_linking && data.static_link.is_none() && data.so_link.is_some() {
+        findings.push(Finding {
+            id: "static-link-mismatch",
+            severity: LintSeverity::Warning,
+            message:
+                "Package sets staticLinking but only has a soLink, dependents expect a staticLink"
+                    .to_string(),
+            fix: None,
+        });
+    }
+    if !static_linking && data.static_link.is_some() && data.so_link.is_none() {
+        findings.push(Finding {
+            id: "static-link-mismatch",
+            severity: LintSeverity::Warning,
+            message: "Package only has a staticLink but does not set staticLinking, dependents will look for a .so"
+                .to_string(),
+            fix: None,
+        });
+    }
+
+    if !root.join(&package.shared_dir).is_dir() {
+        findings.push(Finding {
+            id: "missing-shared-dir",
+            severity: LintSeverity::Error,
+            message: format!("sharedDir {} does not exist", package.shared_dir.display()),
+            fix: Some(Fix::CreateSharedDir),
+        });
+    }
+
+    if data.cmake.is_none() && root.join("CMakeLists.txt").exists() {
+        findings.push(Finding {
+            id: "cmake-unset",
+            severity: LintSeverity::Warning,
+            message: "cmake is unset in a CMake project".to_string(),
+            fix: Some(Fix::EnableCmake),
+        });
+    }
+
+    if package.info.url.is_none() {
+        findings.push(Finding {
+            id: "missing-url",
+            severity: LintSeverity::Warning,
+            message: "url is not set, dependents will be unable to download the headers"
+                .to_string(),
+            fix: None,
+        });
+    }
+
+    if is_ancestor_or_self(root, &package.dependencies_dir) {
+        findings.push(Finding {
+            id: "dependencies-dir-ancestor",
+            severity: LintSeverity::Error,
+            message: format!(
+                "dependenciesDir {} contains the workspace, restoring would clear it",
+                package.dependencies_dir.display()
+            ),
+            fix: Some(Fix::SetDependenciesDir(PathBuf::from("extern"))),
+        });
+    }
+
+    for dep in &package.dependencies {
+        if has_upper_bound(&dep.version_range) {
+            continue;
+        }
+        let restored = shared.and_then(|shared| {
+            shared
+                .restored_dependencies
+                .iter()
+                .find(|r| r.dependency.id == dep.id)
+        });
+        let range = lower_bound_caret(&dep.version_range)
+            .or_else(|| restored.and_then(|r| VersionReq::parse(&format!("^{}", r.version)).ok()));
+
+        findings.push(Finding {
+            id: "unbounded-range",
+            severity: LintSeverity::Warning,
+            message: format!(
+                "Range {} of {} has no upper bound, a breaking release will be picked up",
+                dep.version_range, dep.id
+            ),
+            fix: range.map(|range| Fix::SetVersionRange {
+                dependency: dep.id.clone(),
+                range,
+            }),
+        });
+    }
+
+    let Some(shared) = shared else {
+        return findings;
+    };
+    for dep in &package.dependencies {
+        let Some(extra_files) = &dep.additional_data.extra_files else {
+            continue;
+        };
+        let Some(restored) = shared
+            .restored_dependencies
+            .iter()
+            .find(|r| r.dependency.id == dep.id)
+        else {
+            continue;
+        };
+
+        let src_path =
+            FileRepository::get_package_cache_path(&dep.id, &restored.version).join("src");
+        if !src_path.exists() {
+            log::debug!("{} is not cached, skipping its extraFiles", dep.id);
+            continue;
+        }
+
+        for file in extra_files.iter().filter(|f| !src_path.join(f).exists()) {
+            findings.push(Finding {
+                id: "missing-extra-file",
+                severity: LintSeverity::Error,
+                message: format!(
+                    "extraFiles entry {file} does not exist in {}:{}",
+                    dep.id, restored.version
+                ),
+                fix: Some(Fix::RemoveExtraFile {
+                    dependency: dep.id.clone(),
+                    file: file.clone(),
+                }),
+            });
+        }
+    }
+
+    findings
+}
+
+/// Whether any comparator of `req` limits how new a version may be
+pub fn has_upper_bound(req: &VersionReq) -> bool {
+    req.comparators
+        .iter()
+        .any(|c| !matches!(c.op, Op::Greater | Op::GreaterEq))
+}
+
+/// Caret range from the lowest version `req` allows, `>=1.2` becomes `^1.2.0` and `>1.2.3` becomes `^1.2.4`
+pub fn lower_bound_caret(req: &VersionReq) -> Option<VersionReq> {
+    let lower = req
+        .comparators
+        .iter()
+        .find(|c| matches!(c.op, Op::Greater | Op::GreaterEq))?;
+
+    let (major, minor, patch) = match (lower.op, lower.minor, lower.patch) {
+        (Op::GreaterEq, minor, patch) => (lower.major, minor.unwrap_or(0), patch.unwrap_or(0)),
+        // the release of a pre-release is newer than it
+        (_, Some(minor), Some(patch)) if !lower.pre.is_empty() => (lower.major, minor, patch),
+        (_, Some(minor), Some(patch)) => (lower.major, minor, patch + 1),
+        (_, Some(minor), None) => (lower.major, minor + 1, 0),
+        (_, None, _) => (lower.major + 1, 0, 0),
+    };
+    VersionReq::parse(&format!("^{major}.{minor}.{patch}")).ok()
+}
+
+/// Whether `dir`, relative to the workspace at `root`, is the workspace or one of its parents
+fn is_ancestor_or_self(root: &Path, dir: &Path) -> bool {
+    let Ok(root) = root.canonicalize() else {
+        return false;
+    };
+
+    let mut target = PathBuf::new();
+    for component in root.join(dir).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                target.pop();
+            }
+            _ => target.push(component),
+        }
+    }
+    root.starts_with(target)
+}
@@ -1,10 +1,17 @@
 pub mod android;
 pub mod cmake;
+pub mod cpp;
+pub mod elf;
 pub mod fs;
+pub mod fuzzy;
 pub mod git;
 pub mod json;
+pub mod license;
+pub mod lint;
 
 pub mod ndk;
+pub mod scripts;
+pub mod version;
 
 #[cfg(feature = "cli")]
 pub mod toggle;
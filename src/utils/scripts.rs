@@ -0,0 +1,70 @@
+use std::{path::Path, process::Stdio};
+
+use color_eyre::{Result, eyre::anyhow};
+use itertools::Itertools;
+use qpm_arg_tokenizer::arg::Expression;
+use qpm_package::models::package::PackageConfig;
+
+use crate::utils::ndk;
+
+/// Runs every command of a script in `workspace`.
+/// Stops at the first failing command and returns its exit code, 0 if all succeeded
+pub fn run_script(
+    script_commands: &[String],
+    supplied_args: &[String],
+    package: &PackageConfig,
+    workspace: &Path,
+) -> Result<i32> {
+    let android_ndk_home = ndk::resolve_ndk_version(package);
+
+    for command_str in script_commands {
+        let split = command_str.split_once(' ');
+
+        let exec = match split {
+            Some(s) => s.0,
+            None => command_str,
+        };
+
+        let args: Vec<String> = match split {
+            Some(s) => {
+                let expression = s.1;
+                let tokenized_args = Expression::parse(expression);
+
+                let formatted_args = tokenized_args
+                    .replace(
+                        supplied_args
+                            .iter()
+                            .map(|s| s.as_str())
+                            .collect_vec()
+                            .as_slice(),
+                    )
+                    .map_err(|e| anyhow!("{}", e))?;
+
+                formatted_args
+                    .split(' ')
+                    .map(|s| s.to_string())
+                    .filter(|s| s.trim() != "")
+                    .collect_vec()
+            }
+            None => vec![],
+        };
+
+        let mut c = std::process::Command::new(exec);
+        c.args(args)
+            .current_dir(workspace)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit());
+
+        // Set the environment variable for Android NDK home if provided
+        if let Some(path) = &android_ndk_home {
+            c.env("ANDROID_NDK_HOME", path);
+        }
+
+        let code = c.spawn()?.wait()?.code().unwrap_or_else(|| 1);
+        if code != 0 {
+            return Ok(code);
+        }
+    }
+    Ok(0)
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+
+use color_eyre::Result;
+use semver::{BuildMetadata, Prerelease, Version};
+
+/// Semver component incremented by a release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VersionBump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl VersionBump {
+    /// The smallest bump after `previous` that allows `breaking` changes or `additions`.
+    /// Like cargo, breaking changes before 1.0.0 only require a minor bump.
+    /// Every 0.0.x release is incompatible with the others, so a patch bump allows any change
+    pub fn required(previous: &Version, breaking: bool, additions: bool) -> Self {
+        if previous.major == 0 && previous.minor == 0 {
+            return VersionBump::Patch;
+        }
+
+        match (previous.major, breaking, additions) {
+            (0, true, _) => VersionBump::Minor,
+            (0, false, _) => VersionBump::Patch,
+            (_, true, _) => VersionBump::Major,
+            (_, false, true) => VersionBump::Minor,
+            (_, false, false) => VersionBump::Patch,
+        }
+    }
+
+    /// The bump made going from `previous` to `current`, `None` if `current` is not newer.
+    /// Stabilizing a pre-release may change anything and counts as a major bump
+    pub fn between(previous: &Version, current: &Version) -> Option<Self> {
+        if current <= previous {
+            return None;
+        }
+
+        let same_release = (previous.major, previous.minor, previous.patch)
+            == (current.major, current.minor, current.patch);
+
+        Some(
+            if current.major > previous.major || (same_release && !previous.pre.is_empty()) {
+                VersionBump::Major
+            } else if current.minor > previous.minor {
+                VersionBump::Minor
+            } else {
+                VersionBump::Patch
+            },
+        )
+    }
+
+    /// Applies the bump to `version`, dropping pre-release and build metadata.
+    /// Like npm, a pre-release of a version the bump would reach is released instead,
+    /// e.g. a patch bump of 1.2.3-rc.1 is 1.2.3
+    pub fn apply(self, version: &Version) -> Version {
+        let mut bumped = version.clone();
+        let pre = !version.pre.is_empty();
+        match self {
+            VersionBump::Major if pre && version.minor == 0 && version.patch == 0 => {}
+            VersionBump::Major => {
+                bumped.major += 1;
+                bumped.minor = 0;
+                bumped.patch = 0;
+            }
+            VersionBump::Minor if pre && version.patch == 0 => {}
+            VersionBump::Minor => {
+                bumped.minor += 1;
+                bumped.patch = 0;
+            }
+            VersionBump::Patch if pre => {}
+            VersionBump::Patch => bumped.patch += 1,
+        }
+        bumped.pre = Prerelease::EMPTY;
+        bumped.build = BuildMetadata::EMPTY;
+        bumped
+    }
+}
+
+impl fmt::Display for VersionBump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionBump::Patch => write!(f, "patch"),
+            VersionBump::Minor => write!(f, "minor"),
+            VersionBump::Major => write!(f, "major"),
+        }
+    }
+}
+
+/// The next pre-release of `version`, like `npm version prerelease`.
+/// `1.2.0` becomes `1.2.1-0`, `1.2.1-beta.0` becomes `1.2.1-beta.1`.
+/// A different `preid` starts a new pre-release series of the same version
+pub fn next_prerelease(version: &Version, preid: Option<&str>) -> Result<Version> {
+    let mut next = version.clone();
+    next.build = BuildMetadata::EMPTY;
+
+    if version.pre.is_empty() {
+        next.patch += 1;
+        next.pre = Prerelease::new(&preid.map_or("0".to_string(), |id| format!("{id}.0")))?;
+        return Ok(next);
+    }
+
+    let current = version.pre.as_str();
+    let pre = match preid {
+        Some(id) if current.split('.').next() != Some(id) => format!("{id}.0"),
+        _ => {
+            let (rest, last) = match current.rsplit_once('.') {
+                Some((rest, last)) => (Some(rest), last),
+                None => (None, current),
+            };
+            match (rest, last.parse::<u64>()) {
+                (Some(rest), Ok(n)) => format!("{rest}.{}", n + 1),
+                (None, Ok(n)) => (n + 1).to_string(),
+                _ => format!("{current}.0"),
+            }
+        }
+    };
+    next.pre = Prerelease::new(&pre)?;
+    Ok(next)
+}
@@ -0,0 +1,28 @@
+# YOU SHOULD NOT MANUALLY EDIT THIS FILE, QPM WILL VOID ALL CHANGES
+# always added
+target_include_directories(${COMPILE_ID} PRIVATE ${EXTERN_DIR}/includes)
+target_include_directories(${COMPILE_ID} SYSTEM PRIVATE ${EXTERN_DIR}/includes/libil2cpp/il2cpp/libil2cpp)
+
+# includes and compile options added by other libraries
+# Sadly, there were none with extra include dirs
+
+# libs dir -> stores .so or .a files (or symlinked!)
+# arm64-v8a binaries are at the top, other ABIs in a folder named after them
+if (ANDROID_ABI STREQUAL "arm64-v8a")
+	set(EXTERN_LIBS_DIR ${EXTERN_DIR}/libs)
+else()
+	set(EXTERN_LIBS_DIR ${EXTERN_DIR}/libs/${ANDROID_ABI})
+endif()
+target_link_directories(${COMPILE_ID} PRIVATE ${EXTERN_LIBS_DIR})
+file(GLOB so_list ${EXTERN_LIBS_DIR}/*.so)
+file(GLOB a_list ${EXTERN_LIBS_DIR}/*.a)
+
+# static libraries go first and are grouped, as they may depend on each other
+if (a_list)
+	target_link_libraries(${COMPILE_ID} PRIVATE -Wl,--start-group ${a_list} -Wl,--end-group)
+endif()
+
+# every .so that needs to be linked, put here!
+target_link_libraries(${COMPILE_ID} PRIVATE
+	${so_list}
+)
@@ -3,6 +3,13 @@
 #[path = "commands/common.rs"]
 mod common;
 
+#[path = "../src/tests/mocks/server.rs"]
+mod mock_server;
+
+#[path = "../src/tests/mocks/elf.rs"]
+#[allow(unused_imports)]
+mod mock_elf;
+
 /// This module contains the tests for the dependency command
 mod dependency {
     use crate::common;
@@ -153,6 +160,18 @@ mod qmod {
 
         Ok(())
     }
+
+    /// Logging flags are global, so they can also follow the subcommand
+    #[test]
+    fn test_qmod_manifest_verbose() -> Result<()> {
+        common::test_command(
+            &["qmod", "manifest", "-v"],
+            Path::new("test_cmd/qmod_manifest.in"),
+            Path::new("test_cmd/qmod_manifest.out"),
+        )?;
+
+        Ok(())
+    }
     #[test]
     fn test_qmod_zip() -> Result<()> {
         // For qmod_zip, we only check that the output file exists, not compare directories
@@ -165,11 +184,47 @@ mod qmod {
     }
 }
 
+/// This module contains the tests for the package command
+mod package {
+    use crate::common;
+    use color_eyre::eyre::Result;
+    use std::path::Path;
+
+    #[test]
+    fn test_package_migrate_legacy() -> Result<()> {
+        common::test_command(
+            &["package", "migrate"],
+            Path::new("test_cmd/package_migrate.in"),
+            Path::new("test_cmd/package_migrate.out"),
+        )?;
+        Ok(())
+    }
+
+    /// Bumps qpm.json, the copy in the lock file and the mod template, only their versions change
+    #[test]
+    fn test_package_bump() -> Result<()> {
+        common::test_command_exact(
+            &["package", "bump", "minor"],
+            Path::new("test_cmd/package_bump.in"),
+            Path::new("test_cmd/package_bump.out"),
+            &[],
+            &[],
+            &[],
+        )?;
+        Ok(())
+    }
+}
+
 /// This module contains the tests for the restore command
 mod restore {
     use crate::common;
+    use crate::mock_elf::{EM_AARCH64, EM_X86_64, shared_object};
+    use crate::mock_server::{MockServer, zip_of};
     use color_eyre::eyre::Result;
-    use std::path::Path;
+    use qpm_cli::commands::restore::{
+        EXIT_CHANGES_PENDING, EXIT_DOWNLOAD_REQUIRED, EXIT_LOCK_OUT_OF_DATE,
+    };
+    use std::{fs, path::Path};
 
     #[test]
     fn test_restore() -> Result<()> {
@@ -180,6 +235,239 @@ mod restore {
         )?;
         Ok(())
     }
+
+    /// Serves a package with a header and the `soLink` binary, its sources keep `abi_links`
+    fn serve_mock_package(
+        server: &MockServer,
+        id: &str,
+        header: (&str, &[u8]),
+        (so_name, binary): (&str, Vec<u8>),
+        abi_links: serde_json::Value,
+    ) -> Result<()> {
+        let package = serde_json::json!({
+            "config": {
+                "version": "0.4.0",
+                "sharedDir": "shared",
+                "dependenciesDir": "extern",
+                "info": {
+                    "name": id,
+                    "id": id,
+                    "version": "1.0.0",
+                    "url": server.file_url(&format!("{id}/1.0.0.zip")),
+                    "additionalData": {
+                        "soLink": server.file_url(&format!("{id}/{so_name}")),
+                        "overrideSoName": so_name
+                    }
+                },
+                "workspace": {
+                    "scripts": {},
+                    "qmodIncludeDirs": [],
+                    "qmodIncludeFiles": [],
+                    "qmodOutput": null
+                },
+                "dependencies": []
+            },
+            "restoredDependencies": []
+        });
+        let mut package_config = package["config"].clone();
+        if !abi_links.is_null() {
+            package_config["abiLinks"] = abi_links;
+        }
+        let package_json = serde_json::to_vec_pretty(&package_config)?;
+        let shared_json = serde_json::to_vec_pretty(&package)?;
+
+        server.serve(
+            &format!("{id}/1.0.0.zip"),
+            zip_of(&[
+                ("qpm.json", package_json.as_slice()),
+                ("qpm.shared.json", shared_json.as_slice()),
+                (format!("shared/{}", header.0).as_str(), header.1),
+            ]),
+        );
+        server.serve(&format!("{id}/{so_name}"), binary);
+        server.add_package(serde_json::from_value(package)?);
+        Ok(())
+    }
+
+    /// Restores a package served by the mock qpackages server, without network access
+    #[test]
+    fn test_restore_mock() -> Result<()> {
+        let server = MockServer::start();
+        let binary = shared_object(EM_AARCH64, Some("libmock.so"), &["liblog.so"]);
+        serve_mock_package(
+            &server,
+            "mock-lib",
+            ("mock.hpp", b"#pragma once\n\nint mock();\n".as_slice()),
+            ("libmock.so", binary.clone()),
+            serde_json::Value::Null,
+        )?;
+
+        let url = server.url();
+        let out = common::test_command_exact(
+            &["restore"],
+            Path::new("test_cmd/restore_mock.in"),
+            Path::new("test_cmd/restore_mock.out"),
+            &[("QPM_QPACKAGES_URL", url.as_str())],
+            // the cache, and defines derived from qpm_package naming rules
+            &["qpm_junk/", "qpm_defines.cmake", "extern/libs/libmock.so"],
+            &[("mock_url", url.as_str())],
+        )?;
+
+        assert_eq!(fs::read(out.join("extern/libs/libmock.so"))?, binary);
+        assert!(out.join("qpm_defines.cmake").exists());
+        assert!(
+            server
+                .requests()
+                .contains(&"/mock-lib/libmock.so".to_string())
+        );
+        Ok(())
+    }
+
+    /// `restore --frozen` without a lock file
+    #[test]
+    fn test_restore_frozen_no_lock() -> Result<()> {
+        let out = common::test_command_exit_code(
+            &["restore", "--frozen"],
+            Path::new("test_cmd/restore_frozen_no_lock.in"),
+            &[],
+            EXIT_LOCK_OUT_OF_DATE,
+        )?;
+
+        assert!(!out.join("qpm.shared.json").exists());
+        Ok(())
+    }
+
+    /// `restore --frozen` with a dependency added to qpm.json but not to the lock file
+    #[test]
+    fn test_restore_frozen_out_of_sync() -> Result<()> {
+        let out = common::test_command_exit_code(
+            &["restore", "--frozen"],
+            Path::new("test_cmd/restore_frozen_out_of_sync.in"),
+            &[],
+            EXIT_LOCK_OUT_OF_DATE,
+        )?;
+
+        assert!(!out.join("extern").exists());
+        Ok(())
+    }
+
+    /// `restore --frozen` with an up to date lock file, but an empty cache
+    #[test]
+    fn test_restore_frozen_download_required() -> Result<()> {
+        let server = MockServer::start();
+        serve_mock_package(
+            &server,
+            "mock-lib",
+            ("mock.hpp", b"#pragma once\n\nint mock();\n".as_slice()),
+            (
+                "libmock.so",
+                shared_object(EM_AARCH64, Some("libmock.so"), &[]),
+            ),
+            serde_json::Value::Null,
+        )?;
+
+        let url = server.url();
+        let out = common::test_command_exit_code(
+            &["restore", "--frozen"],
+            Path::new("test_cmd/restore_frozen_download.in"),
+            &[("QPM_QPACKAGES_URL", url.as_str())],
+            EXIT_DOWNLOAD_REQUIRED,
+        )?;
+
+        assert!(!out.join("extern").exists());
+        assert!(
+            !server
+                .requests()
+                .contains(&"/mock-lib/1.0.0.zip".to_string())
+        );
+        Ok(())
+    }
+
+    /// `restore --dry-run` of a package that was never restored writes nothing
+    #[test]
+    fn test_restore_dry_run_changes_pending() -> Result<()> {
+        let server = MockServer::start();
+        serve_mock_package(
+            &server,
+            "mock-lib",
+            ("mock.hpp", b"#pragma once\n\nint mock();\n".as_slice()),
+            (
+                "libmock.so",
+                shared_object(EM_AARCH64, Some("libmock.so"), &[]),
+            ),
+            serde_json::Value::Null,
+        )?;
+
+        let url = server.url();
+        let out = common::test_command_exit_code(
+            &["restore", "--dry-run"],
+            Path::new("test_cmd/restore_mock.in"),
+            &[("QPM_QPACKAGES_URL", url.as_str())],
+            EXIT_CHANGES_PENDING,
+        )?;
+
+        for generated in [
+            "qpm.shared.json",
+            "extern",
+            "extern.cmake",
+            "qpm_defines.cmake",
+        ] {
+            assert!(!out.join(generated).exists(), "{generated} was written");
+        }
+        Ok(())
+    }
+
+    /// Restores arm64-v8a and x86_64 binaries, a package without x86_64 binaries only warns
+    #[test]
+    fn test_restore_mock_abis() -> Result<()> {
+        let server = MockServer::start();
+        let binary = shared_object(EM_AARCH64, Some("libmock.so"), &["liblog.so"]);
+        let emulator_binary = shared_object(EM_X86_64, Some("libmock.so"), &["liblog.so"]);
+        let arm_binary = shared_object(EM_AARCH64, Some("libarm.so"), &[]);
+        serve_mock_package(
+            &server,
+            "mock-lib",
+            ("mock.hpp", b"#pragma once\n\nint mock();\n".as_slice()),
+            ("libmock.so", binary.clone()),
+            serde_json::json!({
+                "x86_64": { "soLink": server.file_url("mock-lib/x86_64/libmock.so") }
+            }),
+        )?;
+        server.serve("mock-lib/x86_64/libmock.so", emulator_binary.clone());
+        serve_mock_package(
+            &server,
+            "mock-arm",
+            ("arm.hpp", b"#pragma once\n\nint arm();\n".as_slice()),
+            ("libarm.so", arm_binary.clone()),
+            serde_json::Value::Null,
+        )?;
+
+        let url = server.url();
+        let out = common::test_command_exact(
+            &["restore"],
+            Path::new("test_cmd/restore_mock_abis.in"),
+            Path::new("test_cmd/restore_mock_abis.out"),
+            &[("QPM_QPACKAGES_URL", url.as_str())],
+            // the cache, defines and binaries, the lock file lists dependencies in resolution order
+            &[
+                "qpm_junk/",
+                "qpm_defines.cmake",
+                "qpm.shared.json",
+                "extern/libs/",
+            ],
+            &[("mock_url", url.as_str())],
+        )?;
+
+        let libs = out.join("extern/libs");
+        assert_eq!(fs::read(libs.join("libmock.so"))?, binary);
+        assert_eq!(fs::read(libs.join("x86_64/libmock.so"))?, emulator_binary);
+        assert_eq!(fs::read(libs.join("libarm.so"))?, arm_binary);
+        assert!(!libs.join("x86_64/libarm.so").exists());
+
+        let shared = fs::read_to_string(out.join("qpm.shared.json"))?;
+        assert!(shared.contains("\"mock-lib\"") && shared.contains("\"mock-arm\""));
+        Ok(())
+    }
 }
 
 /// This module contains the tests for the download command
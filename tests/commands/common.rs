@@ -14,6 +14,16 @@ pub fn test_command(
     args: &[&str],
     input_dir: &Path,
     expected_dir: &Path,
+) -> color_eyre::Result<TempDir> {
+    test_command_with_env(args, input_dir, expected_dir, &[])
+}
+
+/// [`test_command`] with extra environment variables for qpm, e.g. to point it at a mock server
+pub fn test_command_with_env(
+    args: &[&str],
+    input_dir: &Path,
+    expected_dir: &Path,
+    envs: &[(&str, &str)],
 ) -> color_eyre::Result<TempDir> {
     // Create a temporary directory using assert_fs
     let temp = TempDir::new().wrap_err("Failed to create temporary directory")?;
@@ -34,6 +44,7 @@ pub fn test_command(
         .args(args)
         .current_dir(temp.path())
         .env("QPM_DISABLE_GLOBAL_CONFIG", "1") // Set test environment variable to disable global config
+        .envs(envs.iter().copied())
         .assert()
         .success();
 
@@ -63,6 +74,132 @@ pub fn test_command(
     Ok(temp)
 }
 
+/// Runs `args` in a copy of `input_dir` and requires qpm to exit with `code`
+pub fn test_command_exit_code(
+    args: &[&str],
+    input_dir: &Path,
+    envs: &[(&str, &str)],
+    code: i32,
+) -> color_eyre::Result<TempDir> {
+    let temp = TempDir::new().wrap_err("Failed to create temporary directory")?;
+    let copy_options = CopyOptions::new()
+        .overwrite(true)
+        .content_only(true)
+        .copy_inside(true);
+    dir::copy(input_dir, temp.path(), &copy_options)
+        .wrap_err_with(|| format!("Failed to copy from {:?} to {:?}", input_dir, temp.path()))?;
+
+    Command::cargo_bin("qpm")
+        .wrap_err("Failed to find qpm binary")?
+        .args(args)
+        .current_dir(temp.path())
+        .env("QPM_DISABLE_GLOBAL_CONFIG", "1")
+        .envs(envs.iter().copied())
+        .assert()
+        .code(code);
+
+    Ok(temp)
+}
+
+/// Runs `args` in a copy of `input_dir`, then requires the output to be exactly `expected_dir`:
+/// every file must match, including nested ones, and no other file may exist.
+/// Paths starting with an entry of `ignored` are not compared, e.g. the package cache.
+/// `{key}` placeholders in expected text files are replaced with their value, e.g. the mock server url
+pub fn test_command_exact(
+    args: &[&str],
+    input_dir: &Path,
+    expected_dir: &Path,
+    envs: &[(&str, &str)],
+    ignored: &[&str],
+    placeholders: &[(&str, &str)],
+) -> color_eyre::Result<TempDir> {
+    let temp = TempDir::new().wrap_err("Failed to create temporary directory")?;
+    let copy_options = CopyOptions::new()
+        .overwrite(true)
+        .content_only(true)
+        .copy_inside(true);
+    dir::copy(input_dir, temp.path(), &copy_options)
+        .wrap_err_with(|| format!("Failed to copy from {:?} to {:?}", input_dir, temp.path()))?;
+
+    Command::cargo_bin("qpm")
+        .wrap_err("Failed to find qpm binary")?
+        .args(args)
+        .current_dir(temp.path())
+        .env("QPM_DISABLE_GLOBAL_CONFIG", "1")
+        .envs(envs.iter().copied())
+        .assert()
+        .success();
+
+    let expected_files = relative_files(expected_dir, ignored)?;
+    let actual_files = relative_files(temp.path(), ignored)?;
+
+    if std::env::var_os("QPM_TEST_UPDATE").is_some_and(|v| v == "1") {
+        println!("Updating expected output for args: {args:?}");
+        if expected_dir.exists() {
+            fs::remove_dir_all(expected_dir)?;
+        }
+        for file in &actual_files {
+            let mut content = fs::read(temp.path().join(file))?;
+            if !content.contains(&0) {
+                for (key, value) in placeholders {
+                    content = content.replace(value.as_bytes(), format!("{{{key}}}"));
+                }
+            }
+            let expected_path = expected_dir.join(file);
+            fs::create_dir_all(expected_path.parent().unwrap())?;
+            fs::write(expected_path, content)?;
+        }
+        return Ok(temp);
+    }
+
+    ensure!(
+        expected_files == actual_files,
+        "Args {args:?} produced files {actual_files:?}, expected {expected_files:?}"
+    );
+
+    for file in &expected_files {
+        let mut expected_content = fs::read(expected_dir.join(file))?;
+        let mut actual_content = fs::read(temp.path().join(file))?;
+        if !expected_content.contains(&0) {
+            for (key, value) in placeholders {
+                expected_content = expected_content.replace(format!("{{{key}}}"), value.as_bytes());
+            }
+            if cfg!(windows) {
+                actual_content = actual_content.replace(b"\r\n", b"\n");
+            }
+        }
+
+        ensure!(
+            expected_content == actual_content,
+            "Args {args:?}: file {file:?} does not match {:?}",
+            expected_dir.join(file)
+        );
+    }
+
+    Ok(temp)
+}
+
+/// Paths of all files below `dir` relative to it, sorted, skipping those starting with an `ignored` path
+fn relative_files(dir: &Path, ignored: &[&str]) -> color_eyre::Result<Vec<String>> {
+    let mut files = vec![];
+    for entry in walkdir::WalkDir::new(dir).min_depth(1) {
+        let entry = entry.wrap_err("Failed to read directory entry")?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry
+            .path()
+            .strip_prefix(dir)?
+            .to_string_lossy()
+            .replace('\\', "/");
+        if !ignored.iter().any(|ignored| path.starts_with(ignored)) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
 /// Function to check for specific output files without comparing content
 pub fn test_command_check_files(
     args: &[&str],